@@ -0,0 +1,214 @@
+//! A baseline of known diagnostics, used to only report the diagnostics introduced after it was
+//! written, which makes adopting the linter in an existing codebase possible.
+
+use crate::fingerprint::{fingerprint, fingerprint_of};
+use crate::normalize_separators;
+use rslint_core::LintResult;
+use rslint_errors::{file::Files, Diagnostic};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The version of the baseline format, baselines of version 1 do not store fingerprints.
+const BASELINE_VERSION: u32 = 2;
+
+/// A diagnostic recorded in a baseline.
+///
+/// Diagnostics are matched by their file and their [fingerprint](crate::fingerprint()) instead of
+/// their position, so edits which only move or reformat a diagnostic do not report it again.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// The name of the file with `/` separators and without a leading `./`.
+    pub file: String,
+    pub rule: Option<String>,
+    pub message: String,
+    /// The 1-based line of the diagnostic when it was recorded, this is not used for matching.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// The trimmed text of the line of the diagnostic, this is not used for matching.
+    pub source: String,
+    /// The fingerprint of the diagnostic, which is computed from the other fields if the entry
+    /// was written without one.
+    #[serde(default)]
+    pub fingerprint: String,
+}
+
+impl BaselineEntry {
+    fn new(diagnostic: &Diagnostic, files: &dyn Files) -> Self {
+        let file = normalize_separators(files.name(diagnostic.file_id).unwrap_or_default());
+        let line = diagnostic
+            .primary
+            .as_ref()
+            .and_then(|primary| files.line_index(diagnostic.file_id, primary.span.range.start));
+        let source = line
+            .and_then(|line| {
+                let range = files.line_range(diagnostic.file_id, line)?;
+                Some(
+                    files
+                        .source(diagnostic.file_id)?
+                        .get(range)?
+                        .trim()
+                        .to_string(),
+                )
+            })
+            .unwrap_or_default();
+
+        Self {
+            file: file.trim_start_matches("./").to_string(),
+            rule: diagnostic.code.clone(),
+            message: diagnostic.title.clone(),
+            line: line.map(|line| line + 1),
+            source,
+            fingerprint: fingerprint(diagnostic, files),
+        }
+    }
+
+    /// The fields which are used for matching.
+    fn key(self) -> (String, String) {
+        (self.file, self.fingerprint)
+    }
+}
+
+/// A versioned list of known diagnostics.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    pub diagnostics: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Record every diagnostic of the results.
+    pub fn new(results: &[LintResult], files: &dyn Files) -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            diagnostics: results
+                .iter()
+                .flat_map(|res| res.diagnostics())
+                .map(|diagnostic| BaselineEntry::new(diagnostic, files))
+                .collect(),
+        }
+    }
+
+    /// Load the baseline stored at `path`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let src = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let mut baseline = serde_json::from_str::<Baseline>(&src).map_err(|err| err.to_string())?;
+        if !(1..=BASELINE_VERSION).contains(&baseline.version) {
+            return Err(format!(
+                "unsupported baseline version {}, expected {}",
+                baseline.version, BASELINE_VERSION
+            ));
+        }
+        // the whitespace around the line does not change its tokens, so trimming it is fine
+        for entry in baseline
+            .diagnostics
+            .iter_mut()
+            .filter(|entry| entry.fingerprint.is_empty())
+        {
+            entry.fingerprint =
+                fingerprint_of(entry.rule.as_deref(), &entry.message, &entry.source);
+        }
+        baseline.version = BASELINE_VERSION;
+        Ok(baseline)
+    }
+
+    /// Write the baseline to `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Remove the diagnostics of the results which are in the baseline, returning how many were
+    /// removed. Every entry only suppresses a single diagnostic, so new occurrences of a known
+    /// problem on an identical line are still reported.
+    pub fn suppress(&self, results: &mut [LintResult], files: &dyn Files) -> usize {
+        let mut remaining = HashMap::new();
+        for entry in &self.diagnostics {
+            *remaining.entry(entry.clone().key()).or_insert(0usize) += 1;
+        }
+
+        let mut suppressed = 0;
+        let mut is_new = |diagnostic: &Diagnostic| {
+            let entry = BaselineEntry::new(diagnostic, files).key();
+            match remaining.get_mut(&entry) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    suppressed += 1;
+                    false
+                }
+                _ => true,
+            }
+        };
+        for result in results {
+            result.parser_diagnostics.retain(|d| is_new(d));
+            for rule_result in result.rule_results.values_mut() {
+                rule_result.diagnostics.retain(|d| is_new(d));
+            }
+            result
+                .directive_diagnostics
+                .retain(|d| is_new(&d.diagnostic));
+        }
+        suppressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileWalker;
+    use rslint_core::{lint_file, CstRuleStore};
+    use rslint_parser::FileKind;
+
+    #[test]
+    fn suppress_known_diagnostics() {
+        let store = CstRuleStore::new().builtins();
+        let lint = |source: &str| {
+            let mut walker = FileWalker::empty();
+            let id = walker.add_virtual(source.to_string(), "./a.js".to_string(), FileKind::Script);
+            let result = lint_file(&walker.files[&id], &store, false);
+            (walker, vec![result])
+        };
+
+        let (walker, results) = lint("if (a) {}\n");
+        let baseline = Baseline::new(&results, &walker);
+        assert_eq!(baseline.diagnostics[0].file, "a.js");
+        assert_eq!(baseline.diagnostics[0].source, "if (a) {}");
+
+        // the known diagnostic moved, and another one with the same line was added
+        let (walker, mut results) = lint("let b;\n\nif (a) {}\nif (a) {}\n");
+        assert_eq!(baseline.suppress(&mut results, &walker), 1);
+        let remaining = results[0].diagnostics().collect::<Vec<_>>();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].code.as_deref(), Some("no-empty"));
+
+        // reformatting the line keeps its fingerprint
+        let (walker, mut results) = lint("  if(a){ }\n");
+        assert_eq!(baseline.suppress(&mut results, &walker), 1);
+    }
+
+    #[test]
+    fn load_baselines_without_fingerprints() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        let store = CstRuleStore::new().builtins();
+        let mut walker = FileWalker::empty();
+        let id = walker.add_virtual(
+            "if (a) {}\n".to_string(),
+            "a.js".to_string(),
+            FileKind::Script,
+        );
+        let mut results = vec![lint_file(&walker.files[&id], &store, false)];
+
+        let mut old = serde_json::to_value(Baseline::new(&results, &walker)).unwrap();
+        old["version"] = 1.into();
+        old["diagnostics"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("fingerprint");
+        fs::write(&path, old.to_string()).unwrap();
+        let baseline = Baseline::load(&path);
+
+        assert_eq!(baseline.unwrap().suppress(&mut results, &walker), 1);
+    }
+}
@@ -0,0 +1,182 @@
+//! A manifest of the files which had no problems on the previous run, used to skip reading and
+//! linting files which did not change since then.
+
+use crate::lint_warn;
+pub use rslint_core::util::hash_source;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// The default location of the cache file.
+pub const DEFAULT_CACHE_LOCATION: &str = ".rslintcache";
+
+/// The version of the manifest format.
+const CACHE_FORMAT: u32 = 2;
+
+/// The stamp of a manifest, which combines the version of the manifest format, of rslint and of
+/// its rules with the `fingerprint` of anything else which affects the lint results, such as the
+/// configured rules. A manifest with a different stamp is discarded as a whole.
+pub fn stamp(fingerprint: u64) -> String {
+    format!(
+        "{}-{}-{}-{:016x}",
+        CACHE_FORMAT,
+        env!("CARGO_PKG_VERSION"),
+        rslint_core::VERSION,
+        fingerprint
+    )
+}
+
+/// The recorded state of a file which had no problems when it was last linted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The modification time of the file in nanoseconds since the unix epoch.
+    pub mtime: u64,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The hash of the source code of the file, see [`hash_source`].
+    pub hash: u64,
+}
+
+/// A versioned manifest of the files which were linted without any problems.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The stamp the manifest was written with, see [`stamp`].
+    stamp: String,
+    files: HashMap<PathBuf, CacheEntry>,
+    /// Where the manifest is stored.
+    #[serde(skip)]
+    pub location: PathBuf,
+}
+
+impl Manifest {
+    /// Make a new empty manifest which will be stored at `location`.
+    pub fn new(location: PathBuf, fingerprint: u64) -> Self {
+        Self {
+            stamp: stamp(fingerprint),
+            files: HashMap::new(),
+            location,
+        }
+    }
+
+    /// Load the manifest stored at `location`, this is empty if there is no manifest yet, if it is invalid,
+    /// or if its stamp differs, which happens if it was written by a different version of rslint or
+    /// with a different `fingerprint`.
+    pub fn load(location: PathBuf, fingerprint: u64) -> Self {
+        let stamp = stamp(fingerprint);
+        let manifest = fs::read_to_string(&location)
+            .ok()
+            .and_then(|src| serde_json::from_str::<Manifest>(&src).ok())
+            .filter(|manifest| manifest.stamp == stamp);
+
+        match manifest {
+            Some(manifest) => Self {
+                location,
+                ..manifest
+            },
+            None => Self::new(location, fingerprint),
+        }
+    }
+
+    /// Write the manifest to its location, dropping entries for files which no longer exist.
+    pub fn save(&mut self) {
+        self.files.retain(|path, _| path.is_file());
+        let res = serde_json::to_string(self)
+            .map_err(|err| err.to_string())
+            .and_then(|src| fs::write(&self.location, src).map_err(|err| err.to_string()));
+        if let Err(err) = res {
+            lint_warn!(
+                "failed to write the cache to `{}`: {}",
+                self.location.display(),
+                err
+            );
+        }
+    }
+
+    /// Whether the file at `path` has the same modification time and size as when it was recorded,
+    /// in which case it does not have to be read again.
+    pub fn is_unchanged(&self, path: &Path, meta: &Metadata) -> bool {
+        match (self.files.get(path), mtime(meta)) {
+            (Some(entry), Some(mtime)) => entry.mtime == mtime && entry.size == meta.len(),
+            _ => false,
+        }
+    }
+
+    /// Whether the file at `path` has the same source code as when it was recorded.
+    pub fn has_source(&self, path: &Path, source: &str) -> bool {
+        matches!(self.files.get(path), Some(entry) if entry.hash == hash_source(source))
+    }
+
+    /// Record that the file at `path` with `source` had no problems.
+    pub fn insert(&mut self, path: PathBuf, source: &str) {
+        let meta = fs::metadata(&path).ok();
+        if let Some((meta, mtime)) = meta.as_ref().and_then(|meta| Some((meta, mtime(meta)?))) {
+            let entry = CacheEntry {
+                mtime,
+                size: meta.len(),
+                hash: hash_source(source),
+            };
+            self.files.insert(path, entry);
+        }
+    }
+
+    /// Forget the file at `path` so it is linted again on the next run.
+    pub fn remove(&mut self, path: &Path) {
+        self.files.remove(path);
+    }
+}
+
+fn mtime(meta: &Metadata) -> Option<u64> {
+    let duration = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some(duration.as_nanos() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let file = dir.join("a.js");
+        let location = dir.join(DEFAULT_CACHE_LOCATION);
+        fs::write(&file, "let a;").unwrap();
+
+        let mut manifest = Manifest::new(location.clone(), 1);
+        manifest.insert(file.clone(), "let a;");
+        manifest.save();
+
+        let loaded = Manifest::load(location.clone(), 1);
+        let meta = fs::metadata(&file).unwrap();
+        let other_fingerprint = Manifest::load(location, 2);
+
+        assert_eq!(loaded, manifest);
+        assert!(loaded.is_unchanged(&file, &meta));
+        assert!(loaded.has_source(&file, "let a;"));
+        assert!(!loaded.has_source(&file, "let b;"));
+        assert!(!other_fingerprint.is_unchanged(&file, &meta));
+    }
+
+    #[test]
+    fn discard_stale_manifests() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let file = dir.join("a.js");
+        let location = dir.join(DEFAULT_CACHE_LOCATION);
+        fs::write(&file, "let a;").unwrap();
+        let mut manifest = Manifest::new(location.clone(), 1);
+        manifest.insert(file.clone(), "let a;");
+
+        // a manifest written by an older version of rslint
+        let mut old = serde_json::to_value(&manifest).unwrap();
+        old["stamp"] = format!("{}-0.0.0-0.0.0-{:016x}", CACHE_FORMAT, 1).into();
+        fs::write(&location, old.to_string()).unwrap();
+        let loaded = Manifest::load(location.clone(), 1);
+
+        assert!(!loaded.has_source(&file, "let a;"));
+        assert!(stamp(1).contains(rslint_core::VERSION));
+        assert_ne!(stamp(1), stamp(2));
+    }
+}
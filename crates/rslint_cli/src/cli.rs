@@ -1,12 +1,14 @@
 //! CLI options
 
-use crate::lint_err;
+use crate::{config::Config, config::ConfigResolver, emit_diagnostic, lint_err};
 use ansi_term::Color::{Green, White, RGB};
 use colored::Colorize;
 use regex::{Captures, Regex};
-use rslint_core::{get_rule_docs, CstRuleStore};
+use rslint_core::{get_rule_docs, RuleLevel, RuleRegistry};
 use rslint_lexer::{ansi_term, color};
+use serde_json::{json, Map, Value};
 use std::collections::HashSet;
+use std::path::Path;
 
 /// A structure for converting user facing markdown docs to ANSI colored terminal explanations.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -85,7 +87,7 @@ impl ExplanationRunner {
 
     pub fn append_link_to_docs(&mut self) {
         for (docs, name) in self.rules.iter_mut().zip(self.rule_names.iter()) {
-            let group = rslint_core::get_rule_by_name(name).unwrap().group();
+            let group = RuleRegistry::builtins().get(name).unwrap().group;
             let link = format!("https://rslint.org/rules/{}/{}.html", group, name);
             docs.push_str(&format!("{}: {}\n", Green.paint("Docs").to_string(), link));
         }
@@ -109,27 +111,23 @@ impl ExplanationRunner {
 }
 
 pub fn show_all_rules() {
-    let rules = CstRuleStore::new().builtins().rules;
+    let rules = RuleRegistry::builtins().all_rules();
     let mut groups = HashSet::new();
     rules.iter().for_each(|r| {
-        groups.insert(r.group());
+        groups.insert(r.group);
     });
 
     for group in groups {
-        let group_rules = rules.iter().filter(|rule| rule.group() == group);
+        let group_rules = rules.iter().filter(|rule| rule.group == group);
         println!("{}:", group.bright_green());
-        let max_rule_len = group_rules
-            .clone()
-            .map(|r| r.name().len())
-            .max()
-            .unwrap_or(0);
+        let max_rule_len = group_rules.clone().map(|r| r.name.len()).max().unwrap_or(0);
 
         for rule in group_rules {
             println!(
                 " {}{} - {}",
-                rule.name().white(),
-                " ".repeat(max_rule_len - rule.name().len()),
-                rule.docs().lines().next().unwrap_or_default()
+                rule.name.white(),
+                " ".repeat(max_rule_len - rule.name.len()),
+                rule.docs.lines().next().unwrap_or_default()
             );
         }
         println!();
@@ -140,3 +138,111 @@ pub fn show_all_rules() {
         "help".bright_green()
     );
 }
+
+/// Print the config which applies to the file at `path` as JSON, including the config files it was
+/// merged from and the state, options, and origin of every rule.
+pub fn print_config(path: &Path, no_global_config: bool) {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or_else(|| Path::new(""))
+    };
+    let mut resolver = ConfigResolver::new(no_global_config);
+    let config = resolver.resolve(dir);
+    for (file, d) in &resolver.errors {
+        emit_diagnostic(d, file);
+    }
+    let json = config_to_json(&config);
+    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+}
+
+/// Describe the effective settings of a config, rules which are not enabled are `off`.
+fn config_to_json(config: &Config) -> Value {
+    let (store, _) = config.rules_store();
+    let mut rules = Map::new();
+    for rule in RuleRegistry::builtins().all_rules() {
+        let name = rule.name;
+        let mut state = match store.get(name) {
+            Some(enabled) => {
+                let level = match config.rule_level_by_name(name) {
+                    RuleLevel::Error => "error",
+                    RuleLevel::Warning => "warning",
+                };
+                // rules are serialized as `{ "RuleName": { ..options } }`
+                let options = match serde_json::to_value(&enabled) {
+                    Ok(Value::Object(map)) => map.into_iter().next().map(|(_, opts)| opts),
+                    _ => None,
+                };
+                json!({ "level": level, "options": options })
+            }
+            None => json!({ "level": "off" }),
+        };
+
+        match config.rule_origin(name) {
+            Some(file) => state["configured_in"] = json!(file),
+            None => {
+                if let Some(file) = config.group_origin(rule.group) {
+                    state["group"] = json!(rule.group);
+                    state["configured_in"] = json!(file);
+                } else if config.files().is_empty() && state["level"] != "off" {
+                    state["recommended"] = json!(true);
+                }
+            }
+        }
+        if rule.fixable() {
+            state["fixable"] = json!(true);
+        }
+        rules.insert(name.to_string(), state);
+    }
+
+    json!({
+        "files": config.files(),
+        "formatter": config.formatter(),
+        "rules": rules,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn config_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        let (top, pkg) = (
+            root.join("rslintrc.toml"),
+            root.join("pkg").join("rslintrc.toml"),
+        );
+        fs::write(
+            &top,
+            "root = true\n[rules]\ngroups = [\"errors\"]\nallowed = [\"no-debugger\"]\n",
+        )
+        .unwrap();
+        fs::write(&pkg, "[rules.warnings]\nno-empty = {}\n").unwrap();
+
+        let mut resolver = ConfigResolver::new(true);
+        let json = config_to_json(&resolver.resolve(&root.join("pkg")));
+
+        assert_eq!(json["files"], json!([top, pkg]));
+        assert_eq!(
+            json["rules"]["no-empty"],
+            json!({
+                "level": "warning",
+                "options": { "allowEmptyCatch": false, "disallowEmptyFunctions": false },
+                "configured_in": pkg,
+            })
+        );
+        assert_eq!(
+            json["rules"]["no-debugger"],
+            json!({ "level": "off", "configured_in": top })
+        );
+        assert_eq!(json["rules"]["for-direction"]["group"], "errors");
+        assert_eq!(
+            json["rules"]["block-spacing"],
+            json!({ "level": "off", "fixable": true })
+        );
+    }
+}
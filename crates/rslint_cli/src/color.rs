@@ -0,0 +1,70 @@
+//! Whether the output of the linter is colored, see [`set_color`].
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use termcolor::ColorChoice;
+
+static COLOR: AtomicBool = AtomicBool::new(true);
+
+/// When the output is colored, passed to `--color`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Only color the output if both stdout and stderr are terminals.
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            _ if s.eq_ignore_ascii_case("auto") => Ok(ColorMode::Auto),
+            _ if s.eq_ignore_ascii_case("always") => Ok(ColorMode::Always),
+            _ if s.eq_ignore_ascii_case("never") => Ok(ColorMode::Never),
+            _ => Err(format!(
+                "unknown color mode `{}`, expected `auto`, `always`, or `never`",
+                s
+            )),
+        }
+    }
+}
+
+/// Set whether diagnostics, notes, and summaries are colored for the rest of the process.
+pub fn set_color(mode: ColorMode) {
+    let enabled = match mode {
+        ColorMode::Auto => atty::is(atty::Stream::Stdout) && atty::is(atty::Stream::Stderr),
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+    };
+    COLOR.store(enabled, Ordering::Relaxed);
+    colored::control::set_override(enabled);
+}
+
+/// Whether the output is colored, which is the case until [`set_color`] is called.
+pub fn color_enabled() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+/// The color choice of streams which output diagnostics.
+pub(crate) fn color_choice() -> ColorChoice {
+    if color_enabled() {
+        ColorChoice::Always
+    } else {
+        ColorChoice::Never
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_modes() {
+        assert_eq!("auto".parse(), Ok(ColorMode::Auto));
+        assert_eq!("Always".parse(), Ok(ColorMode::Always));
+        assert_eq!("never".parse(), Ok(ColorMode::Never));
+        assert!("sometimes".parse::<ColorMode>().is_err());
+    }
+}
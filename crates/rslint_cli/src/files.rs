@@ -1,37 +1,275 @@
 //! The structure responsible for managing IO and the files implementation for codespan.
 
-use crate::lint_warn;
+use crate::{cache::Manifest, lint_warn};
+use encoding_rs::{Encoding, WINDOWS_1252};
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::{WalkBuilder, WalkState};
 use rslint_core::File;
 use rslint_errors::file::{FileId, Files};
-use std::collections::HashMap;
-use std::fs::read_to_string;
+use rslint_parser::FileKind;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read};
 use std::ops::Range;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::{error, fmt};
 
-/// A list of the extension of files linted
+/// A list of the extension of files linted by default
 const LINTED_FILES: [&str; 3] = ["js", "mjs", "ts"];
 
 /// The filename of the ignore file for RSLint
 const RSLINT_IGNORE_FILE: &str = ".rslintignore";
 
+/// The filename of the ESLint ignore file, which is respected for easier migration
+const ESLINT_IGNORE_FILE: &str = ".eslintignore";
+
+/// The filename of the files declaring the formatting conventions of a project
+const EDITORCONFIG_FILE: &str = ".editorconfig";
+
+/// The directories which are never walked by default
+const IGNORED_DIRS: [&str; 1] = ["node_modules"];
+
 /// The structure for managing IO to and from the core runner.
 /// The walker uses multithreaded IO, spawning a thread for every file being loaded.
 // TODO: use IO_Uring for linux
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileWalker {
     pub files: HashMap<usize, File>,
+    /// The extensions of the files which will be loaded, matched case-insensitively.
+    pub extensions: Vec<String>,
+    /// Whether symbolic links are followed while walking directories.
+    pub follow_symlinks: bool,
+    /// The maximum size of a file in bytes, larger files are skipped.
+    pub max_bytes: Option<u64>,
+    /// Whether files which are not valid UTF-8 are transcoded instead of skipped.
+    pub transcode: bool,
+    /// The number of threads used for walking and reading files, this overrides the thread count
+    /// given to [`FileWalker::load_files_parallel`] if it is not zero.
+    pub concurrency: usize,
+    /// Glob patterns which files must match to be loaded on top of having one of the linted
+    /// extensions, if this is empty every file with one of the linted extensions is loaded.
+    pub include: Vec<String>,
+    /// Glob patterns of files which are never loaded, these take priority over `include`.
+    pub exclude: Vec<String>,
+    /// Directories which are never walked, matched case-insensitively. Entries with a single segment
+    /// (such as `node_modules`) match a directory with that name anywhere, while entries with multiple
+    /// segments (such as `vendor/bundles`) match the path relative to the path being walked.
+    /// They are walked anyway if the files are loaded with `no_ignore`.
+    pub ignored_dirs: Vec<String>,
+    /// The manifest of files which had no problems on the previous run, unchanged files in it are skipped.
+    pub cache: Option<Manifest>,
+    /// The number of files which were skipped because they are unchanged in the cache.
+    pub cached: usize,
+    /// The directory which file names are displayed relative to, see [`FileWalker::with_base_dir`].
+    pub base_dir: Option<PathBuf>,
+    /// Whether files without an extension which start with a node shebang (`#!/usr/bin/env node`)
+    /// are loaded as scripts.
+    pub shebang_scripts: bool,
+    /// Whether diagnostics display file names with `/` separators on every platform, the json and
+    /// sarif formatters always do.
+    pub normalize_paths: bool,
+    /// The names of files whose name is the same as the name of another file, which include
+    /// enough of their parent directories to tell them apart, see [`FileWalker::disambiguate_names`].
+    pub display_names: HashMap<usize, String>,
+    /// Whether files are numbered from 1 in the order of [`FileWalker::files_sorted`] instead of
+    /// using the process wide ids, see [`FileWalker::with_local_ids`].
+    pub local_ids: bool,
+    /// Whether the errors of loading files are kept in [`FileWalker::errors`] instead of being
+    /// reported, which lets library users handle them.
+    pub collect_errors: bool,
+    /// The errors of loading files if [`FileWalker::collect_errors`] is set.
+    pub errors: Vec<WalkError>,
+    /// The id of the file loaded from every canonical path, so looking up a file by its path does
+    /// not canonicalize the path of every file, see [`FileWalker::get_by_path`].
+    canonical_paths: HashMap<PathBuf, usize>,
+}
+
+/// The files of a walk running in the background, yielded as soon as they are read.
+/// See [`FileWalker::stream_files`].
+#[derive(Debug)]
+pub struct FileStream {
+    rx: Receiver<File>,
+    seen: HashSet<PathBuf>,
+    package_types: HashMap<PathBuf, bool>,
+    editorconfigs: HashMap<PathBuf, Option<EditorConfigFile>>,
+    handle: JoinHandle<(Vec<PathBuf>, Vec<WalkError>)>,
+}
+
+impl Iterator for FileStream {
+    type Item = File;
+
+    fn next(&mut self) -> Option<File> {
+        loop {
+            let mut file = self.rx.recv().ok()?;
+            if self
+                .seen
+                .insert(canonical_path(file.path.as_deref().unwrap()))
+            {
+                infer_kind_from_package(&mut file, &mut self.package_types);
+                apply_editorconfig(&mut file, &mut self.editorconfigs);
+                return Some(file);
+            }
+        }
+    }
+}
+
+impl FileStream {
+    /// Wait for the walk to finish and return the paths which did not contribute any file along
+    /// with the errors of the walk if [`FileWalker::collect_errors`] is set, otherwise the errors
+    /// were already reported. Files which were not consumed yet are dropped.
+    pub fn finish(self) -> (Vec<PathBuf>, Vec<WalkError>) {
+        drop(self.rx);
+        self.handle.join().unwrap_or_default()
+    }
+}
+
+/// An error which happened while loading files, files which cause an error are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalkError {
+    /// A path which did not contribute any linted file.
+    Unmatched(PathBuf),
+    /// A glob pattern which could not be compiled.
+    InvalidGlob(String),
+    /// An ignore file which could not be parsed, along with its name if it is known.
+    InvalidIgnoreFile {
+        name: Option<String>,
+        message: String,
+    },
+    /// A directory entry which could not be walked.
+    Walk(String),
+    /// A file which is larger than [`FileWalker::max_bytes`].
+    TooLarge { path: PathBuf, len: u64, max: u64 },
+    /// A file which is not valid UTF-8 and was not transcoded.
+    NotUtf8 { path: PathBuf },
+    /// A file which could not be read.
+    ReadFailed {
+        path: PathBuf,
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
+
+impl WalkError {
+    fn read_failed(path: PathBuf, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::InvalidData => WalkError::NotUtf8 { path },
+            kind => WalkError::ReadFailed {
+                path,
+                kind,
+                message: err.to_string(),
+            },
+        }
+    }
+
+    /// Whether the error is reported as an error instead of a warning.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            WalkError::InvalidGlob(_) | WalkError::NotUtf8 { .. } | WalkError::ReadFailed { .. }
+        )
+    }
+
+    /// Report the error on stderr.
+    pub fn emit(&self) {
+        if self.is_error() {
+            crate::lint_err!("{}", self);
+        } else {
+            lint_warn!("{}", self);
+        }
+    }
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalkError::Unmatched(path) => {
+                write!(f, "`{}` did not match any linted files", path.display())
+            }
+            WalkError::InvalidGlob(message) => write!(f, "Invalid glob pattern: {}", message),
+            WalkError::InvalidIgnoreFile {
+                name: Some(name),
+                message,
+            } => write!(f, "invalid ignore file `{}`: {}", name, message),
+            WalkError::InvalidIgnoreFile {
+                name: None,
+                message,
+            } => write!(f, "invalid ignore file: {}", message),
+            WalkError::Walk(message) => write!(f, "failed to walk directory: {}", message),
+            WalkError::TooLarge { path, len, max } => write!(
+                f,
+                "skipping {} because its size ({} bytes) exceeds the limit of {} bytes",
+                path.display(),
+                len,
+                max
+            ),
+            WalkError::NotUtf8 { path } => write!(
+                f,
+                "failed to read file {}: the file is not valid UTF-8, re-save it as UTF-8 or rerun with `--transcode`",
+                path.display()
+            ),
+            WalkError::ReadFailed { path, message, .. } => {
+                write!(f, "failed to read file {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl error::Error for WalkError {}
+
+/// Statistics about the files loaded by a [`FileWalker`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalkSummary {
+    /// The number of files loaded.
+    pub files: usize,
+    /// The total size of all of the files in bytes.
+    pub bytes: usize,
+    /// The total number of lines in all of the files.
+    pub lines: usize,
+    /// The number of files parsed as scripts.
+    pub scripts: usize,
+    /// The number of files parsed as modules.
+    pub modules: usize,
+    /// The number of files parsed as TypeScript.
+    pub typescript: usize,
+}
+
+impl Default for FileWalker {
+    fn default() -> Self {
+        Self {
+            files: HashMap::new(),
+            extensions: LINTED_FILES.iter().map(|ext| ext.to_string()).collect(),
+            follow_symlinks: false,
+            max_bytes: None,
+            transcode: false,
+            concurrency: 0,
+            include: vec![],
+            exclude: vec![],
+            ignored_dirs: IGNORED_DIRS.iter().map(|dir| dir.to_string()).collect(),
+            cache: None,
+            cached: 0,
+            base_dir: None,
+            shebang_scripts: false,
+            normalize_paths: false,
+            display_names: HashMap::new(),
+            local_ids: false,
+            collect_errors: false,
+            errors: vec![],
+            canonical_paths: HashMap::new(),
+        }
+    }
 }
 
 impl Files for FileWalker {
     fn name(&self, id: FileId) -> Option<&str> {
-        let entry = self.files.get(&id)?;
-        let name = entry
-            .path
-            .as_ref()
-            .and_then(|path| path.to_str())
-            .unwrap_or_else(|| entry.name.as_str());
-        Some(name)
+        match self.display_names.get(&id) {
+            Some(name) => Some(name),
+            None => Some(self.base_name(self.files.get(&id)?)),
+        }
     }
 
     fn source(&self, id: FileId) -> Option<&str> {
@@ -50,19 +288,132 @@ impl Files for FileWalker {
 
 impl FileWalker {
     pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Make a new file walker which numbers its files from 1 in the order of their sorted paths,
+    /// instead of with ids which depend on every file created by the process before. The same
+    /// files always get the same ids, which makes tests including the ids reproducible.
+    ///
+    /// Every file is renumbered when files are added, so adding files may change the ids of
+    /// the files which were already loaded.
+    pub fn with_local_ids() -> Self {
         Self {
-            files: HashMap::new(),
+            local_ids: true,
+            ..Self::default()
         }
     }
 
+    /// Replace the list of file extensions which will be loaded, e.g. `["js", "cjs"]`.
+    pub fn with_extensions(
+        mut self,
+        extensions: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.extensions = extensions
+            .into_iter()
+            .map(|ext| ext.into().trim_start_matches('.').to_string())
+            .collect();
+        self
+    }
+
+    /// Follow symbolic links while walking directories. Loops are detected and skipped.
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Skip any file larger than `max_bytes` bytes, such as minified bundles.
+    pub fn max_bytes(mut self, max_bytes: Option<u64>) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Keep the errors of loading files in [`FileWalker::errors`] instead of reporting them.
+    pub fn collect_errors(mut self, collect: bool) -> Self {
+        self.collect_errors = collect;
+        self
+    }
+
+    /// Transcode files which are not valid UTF-8 instead of skipping them. UTF-16 is detected
+    /// through the byte order mark, anything else is assumed to be Windows-1252 (a superset of Latin-1).
+    pub fn transcode(mut self, transcode: bool) -> Self {
+        self.transcode = transcode;
+        self
+    }
+
+    /// Bound the number of threads used to read files to `n`, which is useful for limiting IO
+    /// on slow or network filesystems. Zero keeps the thread count given when loading.
+    pub fn with_concurrency(mut self, n: usize) -> Self {
+        self.concurrency = n;
+        self
+    }
+
+    /// Only load files matching one of the `include` patterns and none of the `exclude` patterns.
+    /// Files still need one of the linted extensions to be loaded.
+    ///
+    /// The patterns are matched against the path of each file relative to the path being walked.
+    pub fn with_patterns(mut self, include: Vec<String>, exclude: Vec<String>) -> Self {
+        self.include = include;
+        self.exclude = exclude;
+        self
+    }
+
+    /// Display the names of files relative to `base`, which keeps diagnostics short in CI logs.
+    /// Files outside of `base` keep their full path and virtual files keep their name.
+    pub fn with_base_dir(mut self, base: PathBuf) -> Self {
+        self.base_dir = Some(base);
+        self
+    }
+
+    /// Also load files without an extension if they start with a node shebang, such as executables
+    /// in `bin`. This is off by default so other extensionless files are never read.
+    pub fn shebang_scripts(mut self, shebang_scripts: bool) -> Self {
+        self.shebang_scripts = shebang_scripts;
+        self
+    }
+
+    /// Display file names with `/` separators in diagnostics, see [`FileWalker::normalize_paths`].
+    pub fn normalize_paths(mut self, normalize_paths: bool) -> Self {
+        self.normalize_paths = normalize_paths;
+        self
+    }
+
+    /// Replace the directories which are never walked, see [`FileWalker::ignored_dirs`].
+    pub fn with_ignored_dirs(mut self, dirs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.ignored_dirs = dirs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Skip loading files which had no problems on the previous run and did not change since then.
+    pub fn with_cache(mut self, cache: Manifest) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Configure every option of a walk before loading any file, see [`FileWalkerBuilder`].
+    pub fn builder() -> FileWalkerBuilder {
+        FileWalkerBuilder::default()
+    }
+
     /// Make a new file walker from a compiled glob pattern. This also
     /// skips any unreadable files/dirs
     pub fn from_glob_parallel(paths: Vec<PathBuf>, num_threads: usize) -> Self {
-        let mut base = Self::default();
-        base.load_files_parallel(paths.into_iter(), num_threads, false, None, false);
-        base
+        Self::builder().num_threads(num_threads).build_from(paths)
+    }
+
+    /// Make a new file walker from a compiled glob pattern, returning the input paths
+    /// which did not contribute any linted file if there are any.
+    pub fn try_from_glob_parallel(
+        paths: Vec<PathBuf>,
+        num_threads: usize,
+    ) -> Result<Self, Vec<PathBuf>> {
+        Self::builder()
+            .num_threads(num_threads)
+            .try_build_from(paths)
     }
 
+    /// Load all the linted files reachable from `paths`, issuing a warning for and returning
+    /// every path which did not contribute any file.
     pub fn load_files_parallel(
         &mut self,
         paths: impl Iterator<Item = PathBuf>,
@@ -70,99 +421,2376 @@ impl FileWalker {
         no_ignore: bool,
         ignore_file: Option<PathBuf>,
         use_gitignore: bool,
-    ) {
-        let build_walker = |path: &PathBuf| {
-            let mut builder = WalkBuilder::new(path);
-            builder.standard_filters(false);
-
-            if !no_ignore {
-                match ignore_file.as_ref() {
-                    Some(file) => {
-                        if let Some(err) = builder.add_ignore(file) {
-                            crate::lint_warn!("invalid gitignore file: {}", err);
-                        }
-                    }
-                    None => {
-                        builder.add_custom_ignore_filename(RSLINT_IGNORE_FILE);
-                    }
+    ) -> Vec<PathBuf> {
+        let mut package_types = HashMap::new();
+        let mut editorconfigs = HashMap::new();
+        let mut unmatched = vec![];
+        let mut seen = self.seen_paths();
+        let mut errors = vec![];
+        for path in paths {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let (read, cached) = self.walk_root(
+                &path,
+                num_threads,
+                no_ignore,
+                ignore_file.as_ref(),
+                use_gitignore,
+                &seen,
+                tx,
+                &mut errors,
+            );
+            self.cached += cached;
+
+            // sort the files so the same path is kept for every run if there are duplicates
+            let mut loaded = rx.into_iter().collect::<Vec<_>>();
+            loaded.sort_by(|a, b| a.path.cmp(&b.path));
+            for mut file in loaded {
+                let canonical = canonical_path(file.path.as_deref().unwrap());
+                if !seen.insert(canonical.clone()) {
+                    continue;
+                }
+                infer_kind_from_package(&mut file, &mut package_types);
+                apply_editorconfig(&mut file, &mut editorconfigs);
+                self.canonical_paths.insert(canonical, file.id);
+                self.files.insert(file.id, file);
+            }
+
+            if read + cached == 0 {
+                errors.push(WalkError::Unmatched(path.clone()));
+                unmatched.push(path);
+            }
+        }
+        self.report(errors);
+        self.renumber();
+        self.disambiguate_names();
+        unmatched
+    }
+
+    /// Keep the errors of loading files if [`FileWalker::collect_errors`] is set, or report them.
+    fn report(&mut self, errors: Vec<WalkError>) {
+        if self.collect_errors {
+            self.errors.extend(errors);
+        } else {
+            errors.iter().for_each(WalkError::emit);
+        }
+    }
+
+    /// Walk `paths` in the background and yield every linted file as soon as it is read, instead
+    /// of loading all of them into [`FileWalker::files`] first.
+    ///
+    /// This lets the caller lint each file and drop it right away, so the memory used stays flat
+    /// and the first results are available before the walk is done, even on huge projects.
+    /// The tradeoff is that the files arrive in no particular order and cannot be looked up by id
+    /// afterwards, which diagnostics need for rendering, so the caller must render them while it
+    /// still has the file.
+    pub fn stream_files(
+        mut self,
+        paths: Vec<PathBuf>,
+        num_threads: usize,
+        no_ignore: bool,
+        ignore_file: Option<PathBuf>,
+        use_gitignore: bool,
+    ) -> FileStream {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let seen = self.seen_paths();
+        let loaded = seen.clone();
+        let handle = std::thread::spawn(move || {
+            let mut unmatched = vec![];
+            for path in paths {
+                let mut errors = vec![];
+                let (read, cached) = self.walk_root(
+                    &path,
+                    num_threads,
+                    no_ignore,
+                    ignore_file.as_ref(),
+                    use_gitignore,
+                    &loaded,
+                    tx.clone(),
+                    &mut errors,
+                );
+                if read + cached == 0 {
+                    errors.push(WalkError::Unmatched(path.clone()));
+                    unmatched.push(path);
                 }
+                // the files are consumed while walking, so the errors are reported right away
+                self.report(errors);
+            }
+            (unmatched, self.errors)
+        });
+
+        FileStream {
+            rx,
+            seen,
+            package_types: HashMap::new(),
+            editorconfigs: HashMap::new(),
+            handle,
+        }
+    }
+
+    /// The canonical paths of the files already loaded, files can be reached through multiple paths
+    /// and only the first one seen is kept.
+    fn seen_paths(&self) -> HashSet<PathBuf> {
+        self.files
+            .values()
+            .filter_map(|file| file.path.as_deref())
+            .map(canonical_path)
+            .collect()
+    }
 
-                builder
-                    .parents(true)
-                    .hidden(true)
-                    .git_global(use_gitignore)
-                    .git_ignore(use_gitignore)
-                    .git_exclude(use_gitignore);
+    /// Walk a single path, sending every linted file to `tx` as it is read, and return the number
+    /// of files read and the number of files skipped because they are unchanged in the cache.
+    /// Files whose canonical path is in `loaded` are not read again and files over the size limit
+    /// are not read at all, but both are counted as read so their root is not unmatched.
+    /// The errors of the walk are added to `errors`.
+    #[allow(clippy::too_many_arguments)]
+    fn walk_root(
+        &self,
+        root: &Path,
+        num_threads: usize,
+        no_ignore: bool,
+        ignore_file: Option<&PathBuf>,
+        use_gitignore: bool,
+        loaded: &HashSet<PathBuf>,
+        tx: Sender<File>,
+        errors: &mut Vec<WalkError>,
+    ) -> (usize, usize) {
+        // a glob such as `src/**/*.js` walks `src` and only keeps the files matching `**/*.js`
+        let glob = glob_parts(root).and_then(|(base, pattern)| {
+            match GlobBuilder::new(&pattern).literal_separator(true).build() {
+                Ok(glob) => Some((base, glob.compile_matcher())),
+                Err(err) => {
+                    errors.push(WalkError::InvalidGlob(err.to_string()));
+                    None
+                }
             }
+        });
+        let root = glob.as_ref().map_or(root, |(base, _)| base.as_path());
+        let glob = glob.as_ref().map(|(_, glob)| glob);
 
-            builder.threads(num_threads).build_parallel()
+        let follow_symlinks = self.follow_symlinks;
+        let num_threads = if self.concurrency != 0 {
+            self.concurrency
+        } else {
+            num_threads
         };
+        // `no_ignore` lints everything, including the files in `node_modules`
+        let ignored_dirs = self
+            .ignored_dirs
+            .iter()
+            .filter(|_| !no_ignore)
+            .map(|dir| dir.trim_matches('/').replace('\\', "/").to_lowercase())
+            .collect::<Vec<_>>();
 
-        for path in paths {
-            let (tx, rx) = std::sync::mpsc::channel();
-            build_walker(&path).run(|| {
-                let tx = tx.clone();
-                Box::new(move |entry| {
-                    let path = match entry {
-                        Ok(entry) => match entry.file_type() {
-                            Some(typ) if !typ.is_dir() => entry.into_path(),
-                            _ => return WalkState::Continue,
-                        },
-                        Err(err) => {
-                            crate::lint_warn!("invalid gitignore file: {}", err);
-                            return WalkState::Continue;
-                        }
-                    };
+        let mut builder = WalkBuilder::new(root);
+        builder.standard_filters(false);
 
-                    // check if this is a js/ts file
-                    let ext = path.extension().unwrap_or_default().to_string_lossy();
-                    if !LINTED_FILES.contains(&ext.as_ref()) {
+        let filter_root = root.to_path_buf();
+        builder.filter_entry(move |entry| {
+            // explicitly requested paths are always walked
+            let is_dir = matches!(entry.file_type(), Some(typ) if typ.is_dir());
+            entry.depth() == 0 || !is_ignored_dir(entry.path(), is_dir, &filter_root, &ignored_dirs)
+        });
+
+        if !no_ignore {
+            match ignore_file {
+                Some(file) => {
+                    if let Some(err) = builder.add_ignore(file) {
+                        errors.push(WalkError::InvalidIgnoreFile {
+                            name: Some(file.display().to_string()),
+                            message: err.to_string(),
+                        });
+                    }
+                }
+                None => {
+                    builder.add_custom_ignore_filename(ESLINT_IGNORE_FILE);
+                    builder.add_custom_ignore_filename(RSLINT_IGNORE_FILE);
+                }
+            }
+
+            builder
+                .parents(true)
+                .hidden(true)
+                .git_global(use_gitignore)
+                .git_ignore(use_gitignore)
+                .git_exclude(use_gitignore);
+        }
+
+        let walker = builder
+            .follow_links(follow_symlinks)
+            .threads(num_threads)
+            .build_parallel();
+
+        let extensions = &self.extensions;
+        let max_bytes = self.max_bytes;
+        let transcode = self.transcode;
+        let shebang_scripts = self.shebang_scripts;
+        let include = compile_patterns(&self.include, errors);
+        let exclude = compile_patterns(&self.exclude, errors);
+        let (include, exclude) = (&include, &exclude);
+        let walk_errors = Mutex::new(vec![]);
+        let walk_errors_ref = &walk_errors;
+        let cache = self.cache.as_ref();
+        let (read, cached) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let (read, cached) = (&read, &cached);
+        let canonical_root = if follow_symlinks {
+            Some(canonical_path(root))
+        } else {
+            None
+        };
+        let canonical_root = canonical_root.as_ref();
+        walker.run(|| {
+            let tx = tx.clone();
+            Box::new(move |entry| {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        let err = WalkError::Walk(err.to_string());
+                        walk_errors_ref.lock().unwrap().push(err);
                         return WalkState::Continue;
                     }
+                };
+                let path = if entry.path_is_symlink() && follow_symlinks {
+                    // report symlinked files by the path they resolve to
+                    match entry.path().canonicalize() {
+                        Ok(path) if path.is_file() => path,
+                        _ => return WalkState::Continue,
+                    }
+                } else {
+                    match entry.file_type() {
+                        Some(typ) if !typ.is_dir() => entry.path().to_path_buf(),
+                        _ => return WalkState::Continue,
+                    }
+                };
+
+                // match the patterns against the path relative to the root, or the file
+                // name if the root is the file itself
+                let relative = match path.strip_prefix(root) {
+                    Ok(rel) if rel.as_os_str().is_empty() => {
+                        Path::new(path.file_name().unwrap_or_default())
+                    }
+                    Ok(rel) => rel,
+                    Err(_) => &path,
+                };
+                if exclude.is_match(relative) || glob.is_some_and(|glob| !glob.is_match(relative)) {
+                    return WalkState::Continue;
+                }
+
+                if !include.is_empty() && !include.is_match(relative) {
+                    return WalkState::Continue;
+                }
+                // check if this is a file with one of the linted extensions
+                let ext = path.extension().unwrap_or_default().to_string_lossy();
+                let is_shebang_script =
+                    || shebang_scripts && path.extension().is_none() && has_node_shebang(&path);
+                if !extensions.iter().any(|x| x.eq_ignore_ascii_case(&ext)) && !is_shebang_script()
+                {
+                    return WalkState::Continue;
+                }
 
-                    // read the content of the file
-                    let content = match std::fs::read_to_string(&path) {
-                        Ok(c) => c,
-                        Err(err) => {
-                            crate::lint_err!("failed to read file {}: {}", path.display(), err);
-                            return WalkState::Continue;
+                // report the files inside of symlinked directories by the path they resolve to too
+                let path = match canonical_root {
+                    Some(canonical_root) => {
+                        let resolved = canonical_path(&path);
+                        let through_link = match resolved.strip_prefix(canonical_root) {
+                            Ok(rel) => !rel.as_os_str().is_empty() && rel != relative,
+                            Err(_) => true,
+                        };
+                        if through_link {
+                            resolved
+                        } else {
+                            path
                         }
-                    };
+                    }
+                    None => path,
+                };
+
+                if !loaded.is_empty() && loaded.contains(&canonical_path(&path)) {
+                    read.fetch_add(1, Ordering::Relaxed);
+                    return WalkState::Continue;
+                }
+
+                // check the size before reading so huge files are never loaded into memory, the
+                // metadata of a link which is not followed is the metadata of the link itself
+                let meta = if entry.path_is_symlink() && !follow_symlinks {
+                    fs::metadata(&path).ok()
+                } else {
+                    entry.metadata().ok()
+                };
+                if let (Some(max), Some(meta)) = (max_bytes, meta.as_ref()) {
+                    if meta.len() > max {
+                        let err = WalkError::TooLarge {
+                            path,
+                            len: meta.len(),
+                            max,
+                        };
+                        walk_errors_ref.lock().unwrap().push(err);
+                        read.fetch_add(1, Ordering::Relaxed);
+                        return WalkState::Continue;
+                    }
+                }
+
+                // skip files which had no problems last run if they did not change since
+                let cache_key = cache.map(|_| canonical_path(&path));
+                if let (Some(cache), Some(key), Some(meta)) = (cache, &cache_key, &meta) {
+                    if cache.is_unchanged(key, meta) {
+                        cached.fetch_add(1, Ordering::Relaxed);
+                        return WalkState::Continue;
+                    }
+                }
+
+                // read the content of the file
+                let (content, encoding) = match read_source(&path, transcode) {
+                    Ok(c) => c,
+                    Err(err) => {
+                        let err = WalkError::read_failed(path, err);
+                        walk_errors_ref.lock().unwrap().push(err);
+                        return WalkState::Continue;
+                    }
+                };
+
+                // the modification time may change without the content changing
+                if let (Some(cache), Some(key)) = (cache, &cache_key) {
+                    if cache.has_source(key, &content) {
+                        cached.fetch_add(1, Ordering::Relaxed);
+                        return WalkState::Continue;
+                    }
+                }
+
+                let mut file = File::new_concrete(content, path);
+                file.transcoded_from = encoding.map(|enc| enc.name());
+                read.fetch_add(1, Ordering::Relaxed);
+                // the receiver is gone if the caller stopped consuming a stream early
+                match tx.send(file) {
+                    Ok(()) => WalkState::Continue,
+                    Err(_) => WalkState::Quit,
+                }
+            })
+        });
+
+        errors.extend(walk_errors.into_inner().unwrap());
+        (read.load(Ordering::Relaxed), cached.load(Ordering::Relaxed))
+    }
+
+    /// Make a new file walker containing a single virtual file read from stdin.
+    ///
+    /// `name` is only used for displaying diagnostics, and `kind` decides how the source is parsed
+    /// since there is no extension to infer it from.
+    pub fn from_stdin(name: String, kind: FileKind) -> io::Result<Self> {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        strip_bom(&mut source);
+
+        let mut base = Self::default();
+        base.add_virtual(source, name, kind);
+        Ok(base)
+    }
+
+    /// Make a new file walker containing a single virtual file read from stdin, which is treated
+    /// as if it was located at `path`.
+    ///
+    /// The kind of the file is inferred from `path` like for a file on disk (including the `type`
+    /// of the closest `package.json`), and so are its `.editorconfig` properties. Diagnostics
+    /// display `path`, but the file stays virtual
+    /// so fixes are never written to `path`.
+    pub fn from_stdin_with_path(path: PathBuf) -> io::Result<Self> {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        Ok(Self::from_source_with_path(source, path))
+    }
+
+    /// Make a new file walker containing a single virtual file with `source`, which is treated as
+    /// if it was located at `path` like the file of [`FileWalker::from_stdin_with_path`].
+    pub fn from_source_with_path(mut source: String, path: PathBuf) -> Self {
+        strip_bom(&mut source);
+
+        let mut file = File::new_concrete(source, path.clone());
+        infer_kind_from_package(&mut file, &mut HashMap::new());
+        apply_editorconfig(&mut file, &mut HashMap::new());
+
+        let mut base = Self::default();
+        let id = base.add_virtual(file.source, path.to_string_lossy().into_owned(), file.kind);
+        base.files.get_mut(&id).unwrap().editorconfig = file.editorconfig;
+        base
+    }
+
+    /// Make a new file walker containing the linted files of a `.zip`, `.tar`, `.tar.gz`, or
+    /// `.tgz` archive without extracting it, see [`FileWalker::load_archive`].
+    pub fn from_archive(path: PathBuf) -> io::Result<Self> {
+        let mut base = Self::default();
+        base.load_archive(&path, false)?;
+        Ok(base)
+    }
+
+    /// Load the linted files of an archive as virtual files named by their path in the archive,
+    /// returning how many files were loaded.
+    ///
+    /// Entries are filtered like the files of a walked directory, the path in the archive is used
+    /// to infer the kind of a file and to match the patterns and ignored directories, and the
+    /// `.rslintignore` and `.eslintignore` files in the archive are respected unless `no_ignore`.
+    /// Since the files are virtual, fixes are never written back to the archive.
+    pub fn load_archive(&mut self, path: &Path, no_ignore: bool) -> io::Result<usize> {
+        let ignored_dirs = self
+            .ignored_dirs
+            .iter()
+            .filter(|_| !no_ignore)
+            .map(|dir| dir.trim_matches('/').replace('\\', "/").to_lowercase())
+            .collect::<Vec<_>>();
+        let mut errors = vec![];
+        let include = compile_patterns(&self.include, &mut errors);
+        let exclude = compile_patterns(&self.exclude, &mut errors);
+
+        let is_linted = |name: &str| {
+            let path = Path::new(name);
+            let in_ignored_dir = path
+                .ancestors()
+                .skip(1)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .any(|dir| is_ignored_dir(dir, true, Path::new(""), &ignored_dirs));
+            if in_ignored_dir || exclude.is_match(path) {
+                return false;
+            }
+            if !include.is_empty() && !include.is_match(path) {
+                return false;
+            }
+            match path.extension() {
+                Some(ext) => self
+                    .extensions
+                    .iter()
+                    .any(|x| x.eq_ignore_ascii_case(&ext.to_string_lossy())),
+                None => self.shebang_scripts,
+            }
+        };
+        let is_ignore_file = |name: &str| {
+            let file_name = name.rsplit('/').next().unwrap_or_default();
+            !no_ignore && (file_name == RSLINT_IGNORE_FILE || file_name == ESLINT_IGNORE_FILE)
+        };
+
+        let mut ignore_files = vec![];
+        let mut entries = vec![];
+        let max_bytes = self.max_bytes;
+        let read = read_archive(
+            path,
+            |name| is_ignore_file(name) || is_linted(name),
+            max_bytes,
+        )?;
+        for (name, bytes) in read {
+            let bytes = match bytes {
+                Ok(bytes) => bytes,
+                Err(len) => {
+                    errors.push(WalkError::TooLarge {
+                        path: PathBuf::from(name),
+                        len,
+                        max: max_bytes.unwrap_or_default(),
+                    });
+                    continue;
+                }
+            };
+            if is_ignore_file(&name) {
+                ignore_files.push((name, bytes));
+                continue;
+            }
+            if Path::new(&name).extension().is_none() && !is_node_shebang(&bytes) {
+                continue;
+            }
+            entries.push((name, bytes));
+        }
+        let ignore = archive_ignore(ignore_files, &mut errors);
+
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut loaded = 0;
+        for (name, bytes) in entries {
+            if ignore.matched_path_or_any_parents(&name, false).is_ignore() {
+                continue;
+            }
+            let (source, encoding) = match decode_source(bytes, self.transcode) {
+                Ok(decoded) => decoded,
+                Err(err) => {
+                    errors.push(WalkError::read_failed(PathBuf::from(name), err));
+                    continue;
+                }
+            };
+            // the kind is inferred from the path in the archive like for a file on disk
+            let mut file = File::new_concrete(source, PathBuf::from(&name));
+            file.path = None;
+            file.name = name;
+            file.transcoded_from = encoding.map(|enc| enc.name());
+            self.files.insert(file.id, file);
+            loaded += 1;
+        }
+        self.report(errors);
+        self.renumber();
+        self.disambiguate_names();
+        Ok(loaded)
+    }
+
+    /// Add a virtual (in-memory) file to the walker and return its id.
+    ///
+    /// This renumbers the files and disambiguates their names, so use [`FileWalker::add_virtuals`]
+    /// for adding many files at once.
+    pub fn add_virtual(&mut self, source: String, name: String, kind: FileKind) -> usize {
+        self.add_virtuals(std::iter::once((source, name, kind)))[0]
+    }
+
+    /// Add virtual (in-memory) files made of a source, a name, and a kind to the walker and return
+    /// their ids in the same order, renumbering the files and disambiguating their names only once.
+    pub fn add_virtuals(
+        &mut self,
+        files: impl IntoIterator<Item = (String, String, FileKind)>,
+    ) -> Vec<usize> {
+        let ids = files
+            .into_iter()
+            .map(|(source, name, kind)| {
+                let file = File::new_virtual(source, name, kind);
+                let id = file.id;
+                self.files.insert(id, file);
+                id
+            })
+            .collect::<Vec<_>>();
+        let changed = self.renumber();
+        self.disambiguate_names();
+        ids.into_iter()
+            .map(|id| changed.get(&id).copied().unwrap_or(id))
+            .collect()
+    }
 
-                    tx.send(File::new_concrete(content, path))
-                        .expect("failed to send files to receiver thread");
-                    WalkState::Continue
+    /// Number the files from 1 in the order of [`FileWalker::files_sorted`] if the walker uses
+    /// local ids, returning the new id of every file whose id changed.
+    fn renumber(&mut self) -> HashMap<usize, usize> {
+        let mut changed = HashMap::new();
+        if !self.local_ids {
+            return changed;
+        }
+        let order = self
+            .files_sorted()
+            .into_iter()
+            .map(|file| file.id)
+            .collect::<Vec<_>>();
+        let mut files = std::mem::take(&mut self.files);
+        for (idx, old) in order.into_iter().enumerate() {
+            let mut file = files.remove(&old).unwrap();
+            if file.id != idx + 1 {
+                file.id = idx + 1;
+                // the errors of a cached parse refer to the old id
+                file.clear_parse();
+                changed.insert(old, file.id);
+            }
+            self.files.insert(file.id, file);
+        }
+        if !changed.is_empty() {
+            self.display_names.clear();
+            for id in self.canonical_paths.values_mut() {
+                *id = changed.get(id).copied().unwrap_or(*id);
+            }
+        }
+        changed
+    }
+
+    /// The name of a file without disambiguating it from other files, which is its path relative
+    /// to the base directory, or the name of the file if it is virtual.
+    fn base_name<'a>(&self, file: &'a File) -> &'a str {
+        file.path
+            .as_deref()
+            .map(|path| match &self.base_dir {
+                Some(base) => path.strip_prefix(base).unwrap_or(path),
+                None => path,
+            })
+            .and_then(|path| path.to_str())
+            .unwrap_or(&file.name)
+    }
+
+    /// Find the files whose names collide and give each of them a name which is unique, by
+    /// appending as few of their parent directories as needed, ex: `index.js — src/a` and
+    /// `index.js — src/b`. Files with unique names keep their short name.
+    ///
+    /// Files whose paths are identical (such as virtual files with the same name) are numbered
+    /// instead, ex: `<stdin> (1)`. This is done automatically after files are added or removed.
+    pub fn disambiguate_names(&mut self) {
+        let mut by_name = HashMap::<&str, Vec<&File>>::new();
+        for file in self.files_sorted() {
+            by_name.entry(self.base_name(file)).or_default().push(file);
+        }
+
+        let mut display_names = HashMap::new();
+        for (name, files) in by_name.into_iter().filter(|(_, files)| files.len() > 1) {
+            // the parent directories of every file from the innermost to the outermost one
+            let parents = files
+                .iter()
+                .map(|file| {
+                    let path = match &file.path {
+                        Some(path) => canonical_path(path),
+                        None => PathBuf::from(&file.name),
+                    };
+                    path.parent()
+                        .into_iter()
+                        .flat_map(|parent| parent.components().rev())
+                        .filter_map(|component| match component {
+                            Component::Normal(dir) => Some(dir.to_string_lossy().into_owned()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
                 })
+                .collect::<Vec<_>>();
+            let depth = parents.iter().map(Vec::len).max().unwrap_or_default();
+            let labels = (1..=depth).map(|depth| {
+                parents
+                    .iter()
+                    .map(|parent| {
+                        let mut dirs = parent[..depth.min(parent.len())].to_vec();
+                        dirs.reverse();
+                        dirs.join("/")
+                    })
+                    .collect::<Vec<_>>()
             });
+            let unique = labels
+                .into_iter()
+                .find(|labels| labels.iter().collect::<HashSet<_>>().len() == labels.len());
+
+            for (idx, file) in files.iter().enumerate() {
+                let display_name = match &unique {
+                    Some(labels) => format!("{} — {}", name, labels[idx]),
+                    None => format!("{} ({})", name, idx + 1),
+                };
+                display_names.insert(file.id, display_name);
+            }
+        }
+        self.display_names = display_names;
+    }
+
+    /// Get the files of the walker sorted by their path (or name for virtual files).
+    ///
+    /// The comparison is case-insensitive and treats `\\` and `/` the same, so the
+    /// order is identical across platforms.
+    pub fn files_sorted(&self) -> Vec<&File> {
+        let mut files = self.files.values().collect::<Vec<_>>();
+        files.sort_by_cached_key(|file| {
+            let name = file
+                .path
+                .as_ref()
+                .map_or_else(
+                    || file.name.clone(),
+                    |path| path.to_string_lossy().to_string(),
+                )
+                .replace('\\', "/");
+            (name.to_lowercase(), name, file.id)
+        });
+        files
+    }
+
+    /// Get the files which are empty or only contain whitespace, sorted like [`FileWalker::files_sorted`].
+    pub fn empty_files(&self) -> Vec<&File> {
+        self.files_sorted()
+            .into_iter()
+            .filter(|file| file.source.trim().is_empty())
+            .collect()
+    }
+
+    /// Keep the paths which are inside of one of `roots` (or matched by it if it is a glob) and
+    /// which have one of the linted extensions, such as the files changed since a git ref.
+    ///
+    /// Paths are not checked against the include patterns since those are relative to the roots,
+    /// which is left to the walker.
+    pub fn filter_paths(&self, paths: Vec<PathBuf>, roots: &[PathBuf]) -> Vec<PathBuf> {
+        paths
+            .into_iter()
+            .filter(|path| roots.iter().any(|root| root_contains(root, path)))
+            .filter(|path| {
+                let ext = path.extension().unwrap_or_default().to_string_lossy();
+                self.extensions.iter().any(|x| x.eq_ignore_ascii_case(&ext))
+                    || self.shebang_scripts && path.extension().is_none() && has_node_shebang(path)
+            })
+            .collect()
+    }
 
-            drop(tx);
+    /// Get the files of the walker which are parsed as `kind`, in the order of [`files_sorted`](Self::files_sorted).
+    pub fn iter_kind(&self, kind: FileKind) -> impl Iterator<Item = &File> {
+        self.files_sorted()
+            .into_iter()
+            .filter(move |file| file.kind == kind)
+    }
 
-            self.files
-                .extend(rx.into_iter().map(|file| (file.id, file)));
+    /// Count the files, bytes, and lines loaded by this walker.
+    pub fn summarize(&self) -> WalkSummary {
+        let mut summary = WalkSummary::default();
+        for file in self.files.values() {
+            summary.files += 1;
+            summary.bytes += file.byte_len();
+            summary.lines += file.line_count();
+            match file.kind {
+                FileKind::Script => summary.scripts += 1,
+                FileKind::Module => summary.modules += 1,
+                FileKind::TypeScript => summary.typescript += 1,
+            }
         }
+        summary
     }
 
     pub fn line_start(&self, id: usize, line_index: usize) -> Option<usize> {
         self.files.get(&id)?.line_start(line_index)
     }
 
-    /// try loading a file's source code and updating the correspoding file in the walker
-    pub fn maybe_update_file_src(&mut self, path: PathBuf) {
-        if let Some(file) = self.files.values_mut().find(|f| {
-            f.path
-                .clone()
-                .map_or(false, |x| x.file_name() == path.file_name())
-        }) {
-            let src = if let Ok(src) = read_to_string(&path) {
-                src
-            } else {
-                return lint_warn!(
+    /// Get the concrete file located at `path`, paths are canonicalized before they are compared.
+    ///
+    /// The paths of loaded files are canonicalized once when they are loaded, files which were
+    /// inserted into [`FileWalker::files`] directly are only found by the path they have.
+    pub fn get_by_path(&self, path: &Path) -> Option<&File> {
+        let id = self.id_by_path(path)?;
+        self.files.get(&id)
+    }
+
+    /// Get the concrete file located at `path` mutably, see [`FileWalker::get_by_path`].
+    pub fn get_by_path_mut(&mut self, path: &Path) -> Option<&mut File> {
+        let id = self.id_by_path(path)?;
+        self.files.get_mut(&id)
+    }
+
+    fn id_by_path(&self, path: &Path) -> Option<usize> {
+        let canonical = canonical_path(path);
+        self.canonical_paths
+            .get(&canonical)
+            .copied()
+            .filter(|id| self.files.contains_key(id))
+            .or_else(|| {
+                self.files
+                    .values()
+                    .find(|file| {
+                        file.path.as_deref() == Some(path)
+                            || file.path.as_deref() == Some(&canonical)
+                    })
+                    .map(|file| file.id)
+            })
+    }
+
+    /// Remove a file from the walker, after which every [`Files`] method returns `None` for its id.
+    pub fn remove_by_id(&mut self, id: usize) -> Option<File> {
+        let file = self.files.remove(&id)?;
+        self.canonical_paths.retain(|_, other| *other != id);
+        if self.display_names.contains_key(&id) {
+            self.disambiguate_names();
+        }
+        Some(file)
+    }
+
+    /// Remove the concrete file located at `path`, see [`FileWalker::get_by_path`].
+    ///
+    /// Paths of files which were deleted cannot be canonicalized, so they are compared as they are,
+    /// in which case `path` must be the same path the file was loaded from.
+    pub fn remove_by_path(&mut self, path: &Path) -> Option<File> {
+        let id = self.get_by_path(path)?.id;
+        self.remove_by_id(id)
+    }
+
+    /// try loading a file's source code and updating the correspoding file in the walker,
+    /// returning whether its source code changed
+    pub fn maybe_update_file_src(&mut self, path: PathBuf) -> bool {
+        let transcode = self.transcode;
+        match self
+            .get_by_path_mut(&path)
+            .map(|file| reload_source(file, transcode))
+        {
+            Some(Ok(changed)) => changed,
+            Some(Err(_)) => {
+                lint_warn!(
                     "failed to reload the source code at `{}`",
                     path.to_string_lossy()
                 );
-            };
-            file.source = src;
-            file.line_starts = File::line_starts(&file.source).collect();
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Reload the source code of every concrete file from disk and return the ids of the files
+    /// which changed, this is used for recovering after renames or bulk changes where reloading
+    /// single files is not enough.
+    pub fn reload_all(&mut self) -> Vec<usize> {
+        let transcode = self.transcode;
+        let mut changed = vec![];
+        for file in self.files.values_mut() {
+            match reload_source(file, transcode) {
+                Ok(true) => changed.push(file.id),
+                Ok(false) => {}
+                Err(err) => lint_warn!(
+                    "failed to reload the source code at `{}`: {}",
+                    file.path
+                        .as_deref()
+                        .unwrap_or_else(|| Path::new(&file.name))
+                        .display(),
+                    err
+                ),
+            }
+        }
+        changed
+    }
+
+    /// Capture the source code of every file, which can be restored with [`FileWalker::restore`].
+    pub fn snapshot(&self) -> WalkerSnapshot {
+        WalkerSnapshot {
+            files: self
+                .files
+                .iter()
+                .map(|(id, file)| (*id, (file.source.clone(), file.kind)))
+                .collect(),
+        }
+    }
+
+    /// Roll the source code of every file back to a snapshot, removing the files which were added
+    /// after it was taken. Files which did not change keep their cached parse.
+    ///
+    /// This only changes the files in memory, the files on disk are never touched.
+    pub fn restore(&mut self, snapshot: WalkerSnapshot) {
+        let added = self
+            .files
+            .keys()
+            .filter(|id| !snapshot.files.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for id in added {
+            self.remove_by_id(id);
+        }
+        for (id, (source, kind)) in snapshot.files {
+            if let Some(file) = self.files.get_mut(&id) {
+                if file.source != source {
+                    file.update_src(source);
+                }
+                file.kind = kind;
+            }
         }
     }
 }
+
+/// The source code of the files of a [`FileWalker`] at some point, see [`FileWalker::snapshot`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalkerSnapshot {
+    files: HashMap<usize, (String, FileKind)>,
+}
+
+/// A builder for a [`FileWalker`] which holds every option of a walk, including the ones which
+/// are otherwise passed to [`FileWalker::load_files_parallel`].
+///
+/// ```ignore
+/// let walker = FileWalker::builder()
+///     .extensions(vec!["js", "cjs"])
+///     .exclude(vec!["**/*.test.js".to_string()])
+///     .use_gitignore(true)
+///     .build_from(vec![PathBuf::from("src")]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileWalkerBuilder {
+    walker: FileWalker,
+    num_threads: usize,
+    no_ignore: bool,
+    ignore_file: Option<PathBuf>,
+    use_gitignore: bool,
+}
+
+impl FileWalkerBuilder {
+    /// The extensions of the files which will be loaded, see [`FileWalker::with_extensions`].
+    pub fn extensions(mut self, extensions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.walker = self.walker.with_extensions(extensions);
+        self
+    }
+
+    /// Follow symbolic links while walking directories, see [`FileWalker::follow_symlinks`].
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.walker = self.walker.follow_symlinks(follow);
+        self
+    }
+
+    /// Skip files larger than this many bytes, see [`FileWalker::max_bytes`].
+    pub fn max_bytes(mut self, max_bytes: Option<u64>) -> Self {
+        self.walker = self.walker.max_bytes(max_bytes);
+        self
+    }
+
+    /// Transcode files which are not valid UTF-8, see [`FileWalker::transcode`].
+    pub fn transcode(mut self, transcode: bool) -> Self {
+        self.walker = self.walker.transcode(transcode);
+        self
+    }
+
+    /// Keep the errors of loading files, see [`FileWalker::collect_errors`].
+    pub fn collect_errors(mut self, collect: bool) -> Self {
+        self.walker = self.walker.collect_errors(collect);
+        self
+    }
+
+    /// The number of threads used to walk and read files, zero uses one thread per CPU core.
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+
+    /// Bound the number of threads used to read files, which takes priority over
+    /// [`FileWalkerBuilder::num_threads`], see [`FileWalker::with_concurrency`].
+    pub fn io_threads(mut self, n: usize) -> Self {
+        self.walker = self.walker.with_concurrency(n);
+        self
+    }
+
+    /// Only load files matching one of these globs, see [`FileWalker::with_patterns`].
+    pub fn include(mut self, include: Vec<String>) -> Self {
+        self.walker.include = include;
+        self
+    }
+
+    /// Never load files matching one of these globs, see [`FileWalker::with_patterns`].
+    pub fn exclude(mut self, exclude: Vec<String>) -> Self {
+        self.walker.exclude = exclude;
+        self
+    }
+
+    /// Replace the directories which are never walked, see [`FileWalker::ignored_dirs`].
+    pub fn ignored_dirs(mut self, dirs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.walker = self.walker.with_ignored_dirs(dirs);
+        self
+    }
+
+    /// Don't respect ignore files and walk the ignored directories, such as `node_modules`.
+    pub fn no_ignore(mut self, no_ignore: bool) -> Self {
+        self.no_ignore = no_ignore;
+        self
+    }
+
+    /// Use this ignore file instead of the `.rslintignore` and `.eslintignore` files.
+    pub fn ignore_file(mut self, ignore_file: Option<PathBuf>) -> Self {
+        self.ignore_file = ignore_file;
+        self
+    }
+
+    /// Also respect the git related ignore files.
+    pub fn use_gitignore(mut self, use_gitignore: bool) -> Self {
+        self.use_gitignore = use_gitignore;
+        self
+    }
+
+    /// Display file names relative to `base`, see [`FileWalker::with_base_dir`].
+    pub fn base_dir(mut self, base: PathBuf) -> Self {
+        self.walker = self.walker.with_base_dir(base);
+        self
+    }
+
+    /// Also load extensionless files with a node shebang, see [`FileWalker::shebang_scripts`].
+    pub fn shebang_scripts(mut self, shebang_scripts: bool) -> Self {
+        self.walker = self.walker.shebang_scripts(shebang_scripts);
+        self
+    }
+
+    /// Display file names with `/` separators, see [`FileWalker::normalize_paths`].
+    pub fn normalize_paths(mut self, normalize_paths: bool) -> Self {
+        self.walker = self.walker.normalize_paths(normalize_paths);
+        self
+    }
+
+    /// Number the files from 1 in the order of their paths, see [`FileWalker::with_local_ids`].
+    pub fn local_ids(mut self, local_ids: bool) -> Self {
+        self.walker.local_ids = local_ids;
+        self
+    }
+
+    /// Skip files which are unchanged since the previous run, see [`FileWalker::with_cache`].
+    pub fn cache(mut self, cache: Option<Manifest>) -> Self {
+        self.walker.cache = cache;
+        self
+    }
+
+    /// Make the walker without loading any file.
+    pub fn build(self) -> FileWalker {
+        self.walker
+    }
+
+    /// Make the walker and load every linted file reachable from `paths`.
+    pub fn build_from(self, paths: Vec<PathBuf>) -> FileWalker {
+        let mut walker = self.walker.clone();
+        self.load_into(&mut walker, paths);
+        walker
+    }
+
+    /// Make the walker and load every linted file reachable from `paths`, returning the paths
+    /// which did not contribute any linted file if there are any.
+    pub fn try_build_from(self, paths: Vec<PathBuf>) -> Result<FileWalker, Vec<PathBuf>> {
+        let mut walker = self.walker.clone();
+        let unmatched = self.load_into(&mut walker, paths);
+        if unmatched.is_empty() {
+            Ok(walker)
+        } else {
+            Err(unmatched)
+        }
+    }
+
+    /// Load the files reachable from `paths` into an existing walker with the walk options of
+    /// this builder, such as the ignore files, returning the paths which did not contribute any
+    /// linted file. This is used to load new files into a walker made by this builder.
+    pub fn load_into(&self, walker: &mut FileWalker, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        walker.load_files_parallel(
+            paths.into_iter(),
+            self.num_threads,
+            self.no_ignore,
+            self.ignore_file.clone(),
+            self.use_gitignore,
+        )
+    }
+}
+
+/// Split a path containing glob metacharacters (such as `src/**/*.js`) into the directory made of
+/// the components before the first one with a metacharacter, and the pattern which the paths
+/// relative to that directory must match. Paths which exist are never treated as globs, so files
+/// such as `pages/[id].js` can still be linted.
+///
+/// Globs are expanded by the walker instead of the shell, so they work the same on every platform
+/// and the files they match go through the same filters as the files of a directory.
+fn glob_parts(path: &Path) -> Option<(PathBuf, String)> {
+    let is_glob = |part: &str| part.contains(['*', '?', '[', '{']);
+    if !is_glob(path.to_str()?) || path.exists() {
+        return None;
+    }
+    let mut components = path.components().peekable();
+    let mut base = PathBuf::new();
+    while let Some(component) =
+        components.next_if(|comp| !is_glob(&comp.as_os_str().to_string_lossy()))
+    {
+        base.push(component);
+    }
+    let pattern = components
+        .map(|comp| comp.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    if base.as_os_str().is_empty() {
+        base.push(".");
+    }
+    Some((base, pattern))
+}
+
+/// The directory which is walked for a path, which is the directory before the first glob
+/// metacharacter for a glob, see [`glob_parts`].
+pub(crate) fn walked_dir(path: &Path) -> PathBuf {
+    glob_parts(path).map_or_else(|| path.to_path_buf(), |(base, _)| base)
+}
+
+/// Whether `path` is `root` or is inside of it, or is matched by `root` if it is a glob.
+fn root_contains(root: &Path, path: &Path) -> bool {
+    let (base, pattern) = match glob_parts(root) {
+        Some((base, pattern)) => (base, Some(pattern)),
+        None => (root.to_path_buf(), None),
+    };
+    let path = canonical_path(path);
+    let relative = match path.strip_prefix(canonical_path(&base)) {
+        Ok(relative) => relative,
+        Err(_) => return false,
+    };
+    pattern.is_none_or(|pattern| {
+        GlobBuilder::new(&pattern)
+            .literal_separator(true)
+            .build()
+            .is_ok_and(|glob| glob.compile_matcher().is_match(relative))
+    })
+}
+
+/// Check if a path matches one of the (lowercased) ignored directories.
+fn is_ignored_dir(path: &Path, is_dir: bool, root: &Path, ignored_dirs: &[String]) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let relative = relative.to_string_lossy().replace('\\', "/").to_lowercase();
+    let name = relative.rsplit('/').next().unwrap_or_default();
+    ignored_dirs.iter().any(|dir| {
+        if dir.contains('/') {
+            relative == *dir
+        } else {
+            is_dir && name == dir
+        }
+    })
+}
+
+/// Read the source code of a concrete file from disk again and return whether it changed, this
+/// does nothing for virtual files.
+///
+/// Only the range of the source which is different is replaced with [`File::apply_edit`], so a
+/// file which was saved without changes keeps its cached parse.
+fn reload_source(file: &mut File, transcode: bool) -> io::Result<bool> {
+    if let Some(path) = file.path.as_deref() {
+        let (src, encoding) = read_source(path, transcode)?;
+        file.transcoded_from = encoding.map(|enc| enc.name());
+        return Ok(file.update_src_incrementally(&src));
+    }
+    Ok(false)
+}
+
+/// Resolve `..` and symlinks in a path, falling back to the path itself if that fails.
+pub(crate) fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Compile a list of glob patterns into a single set, adding an error for invalid patterns.
+fn compile_patterns(patterns: &[String], errors: &mut Vec<WalkError>) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(err) => errors.push(WalkError::InvalidGlob(err.to_string())),
+        }
+    }
+    builder.build().unwrap_or_else(|err| {
+        errors.push(WalkError::InvalidGlob(err.to_string()));
+        GlobSet::empty()
+    })
+}
+
+/// Whether the first line of a file is a shebang running node, such as `#!/usr/bin/env node`.
+/// Only the start of the file is read so large binaries without an extension stay cheap to skip.
+fn has_node_shebang(path: &Path) -> bool {
+    let mut start = [0; 128];
+    match fs::File::open(path).and_then(|mut file| file.read(&mut start)) {
+        Ok(len) => is_node_shebang(&start[..len]),
+        Err(_) => false,
+    }
+}
+
+/// Whether the first line of some source code is a shebang running node.
+fn is_node_shebang(src: &[u8]) -> bool {
+    let line = src.split(|byte| *byte == b'\n').next().unwrap_or_default();
+    line.starts_with(b"#!") && line.windows(4).any(|word| word == b"node")
+}
+
+/// Strip the UTF-8 byte order mark from the start of some source code if there is one.
+fn strip_bom(src: &mut String) {
+    if src.starts_with('\u{FEFF}') {
+        src.drain(..'\u{FEFF}'.len_utf8());
+    }
+}
+
+/// Read the source code of a file, stripping the UTF-8 byte order mark if there is one
+/// so it does not shift every byte offset in the file.
+///
+/// If the file is not valid UTF-8 and `transcode` is enabled it is decoded from either UTF-16
+/// (detected through its byte order mark) or Windows-1252, and the encoding used is returned.
+fn read_source(path: &Path, transcode: bool) -> io::Result<(String, Option<&'static Encoding>)> {
+    decode_source(fs::read(path)?, transcode)
+}
+
+/// Decode the content of a file, see [`read_source`].
+fn decode_source(
+    bytes: Vec<u8>,
+    transcode: bool,
+) -> io::Result<(String, Option<&'static Encoding>)> {
+    match String::from_utf8(bytes) {
+        Ok(mut src) => {
+            strip_bom(&mut src);
+            Ok((src, None))
+        }
+        Err(err) if transcode => {
+            let bytes = err.into_bytes();
+            let encoding = Encoding::for_bom(&bytes).map_or(WINDOWS_1252, |(enc, _)| enc);
+            let (src, encoding, _) = encoding.decode(&bytes);
+            Ok((src.into_owned(), Some(encoding)))
+        }
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the file is not valid UTF-8, re-save it as UTF-8 or rerun with `--transcode`",
+        )),
+    }
+}
+
+/// The paths and contents of the entries of an archive, or their sizes if they are too large.
+type ArchiveEntries = Vec<(String, Result<Vec<u8>, u64>)>;
+
+/// Read the file entries of a `.zip`, `.tar`, `.tar.gz`, or `.tgz` archive whose path in the
+/// archive is accepted by `keep`, returning their paths with `/` separators and their content.
+/// Directories, links, and entries whose path escapes the archive are skipped.
+///
+/// Entries larger than `max_bytes` are not read, their size is returned instead, see [`read_entry`].
+fn read_archive(
+    path: &Path,
+    keep: impl Fn(&str) -> bool,
+    max_bytes: Option<u64>,
+) -> io::Result<ArchiveEntries> {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_ascii_lowercase();
+    let file = fs::File::open(path)?;
+    if name.ends_with(".zip") {
+        let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+        let mut archive = zip::ZipArchive::new(file).map_err(invalid)?;
+        let mut entries = vec![];
+        for idx in 0..archive.len() {
+            let mut entry = archive.by_index(idx).map_err(invalid)?;
+            let name = match entry.enclosed_name() {
+                Some(name) if entry.is_file() => archive_path(name),
+                _ => continue,
+            };
+            if keep(&name) {
+                let size = entry.size();
+                entries.push((name, read_entry(&mut entry, size, max_bytes)?));
+            }
+        }
+        Ok(entries)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        let archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        read_tar(archive, keep, max_bytes)
+    } else if name.ends_with(".tar") {
+        read_tar(tar::Archive::new(file), keep, max_bytes)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "unsupported archive, expected a `.zip`, `.tar`, `.tar.gz`, or `.tgz` file",
+        ))
+    }
+}
+
+/// Read the file entries of a tar archive, see [`read_archive`].
+fn read_tar(
+    mut archive: tar::Archive<impl Read>,
+    keep: impl Fn(&str) -> bool,
+    max_bytes: Option<u64>,
+) -> io::Result<ArchiveEntries> {
+    let mut entries = vec![];
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let escapes = path
+            .components()
+            .any(|comp| !matches!(comp, Component::Normal(_) | Component::CurDir));
+        if !entry.header().entry_type().is_file() || escapes {
+            continue;
+        }
+        let name = archive_path(&path);
+        if keep(&name) {
+            let size = entry.size();
+            entries.push((name, read_entry(&mut entry, size, max_bytes)?));
+        }
+    }
+    Ok(entries)
+}
+
+/// Read an archive entry whose header claims it is `size` bytes, or return its size if it is larger
+/// than `max_bytes`. The header is not trusted, so at most `max_bytes + 1` bytes are ever read.
+fn read_entry(
+    entry: &mut impl Read,
+    size: u64,
+    max_bytes: Option<u64>,
+) -> io::Result<Result<Vec<u8>, u64>> {
+    let mut bytes = vec![];
+    match max_bytes {
+        Some(max) if size > max => return Ok(Err(size)),
+        Some(max) => {
+            entry.take(max.saturating_add(1)).read_to_end(&mut bytes)?;
+            if bytes.len() as u64 > max {
+                return Ok(Err(bytes.len() as u64));
+            }
+        }
+        None => {
+            entry.read_to_end(&mut bytes)?;
+        }
+    }
+    Ok(Ok(bytes))
+}
+
+/// The path of an archive entry with `/` separators and without a leading `./`.
+fn archive_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|comp| match comp {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Build the matcher of the ignore files of an archive, which apply to the paths under the
+/// directory they are in. Rules of deeper ignore files take priority.
+fn archive_ignore(
+    mut ignore_files: Vec<(String, Vec<u8>)>,
+    errors: &mut Vec<WalkError>,
+) -> Gitignore {
+    ignore_files.sort_by_key(|(name, _)| name.matches('/').count());
+    let mut builder = GitignoreBuilder::new("");
+    for (name, bytes) in ignore_files {
+        let dir = name.rsplit_once('/').map_or("", |(dir, _)| dir);
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            let line = line.trim_end();
+            let (negated, pattern) = match line.strip_prefix('!') {
+                Some(pattern) => ("!", pattern),
+                None => ("", line),
+            };
+            if pattern.is_empty() || pattern.starts_with('#') || dir.is_empty() {
+                if let Err(err) = builder.add_line(None, line) {
+                    errors.push(WalkError::InvalidIgnoreFile {
+                        name: Some(name.clone()),
+                        message: err.to_string(),
+                    });
+                }
+                continue;
+            }
+            // anchor the patterns of nested ignore files to their directory
+            let pattern = pattern.trim_start_matches('/');
+            let line = if pattern.trim_end_matches('/').contains('/') {
+                format!("{}/{}/{}", negated, dir, pattern)
+            } else {
+                format!("{}/{}/**/{}", negated, dir, pattern)
+            };
+            if let Err(err) = builder.add_line(None, &line) {
+                errors.push(WalkError::InvalidIgnoreFile {
+                    name: Some(name.clone()),
+                    message: err.to_string(),
+                });
+            }
+        }
+    }
+    builder.build().unwrap_or_else(|err| {
+        errors.push(WalkError::InvalidIgnoreFile {
+            name: None,
+            message: err.to_string(),
+        });
+        Gitignore::empty()
+    })
+}
+
+/// Parse `.js` files as modules if their nearest `package.json` declares `"type": "module"`.
+///
+/// Other extensions such as `.mjs` and `.cjs` always take priority over the `package.json`.
+fn infer_kind_from_package(file: &mut File, cache: &mut HashMap<PathBuf, bool>) {
+    let path = match file.path.as_ref() {
+        Some(path) => path,
+        None => return,
+    };
+    let is_js = path
+        .extension()
+        .unwrap_or_default()
+        .eq_ignore_ascii_case("js");
+
+    // a `// @rslint-script` comment takes priority over the package
+    if is_js && file.kind == FileKind::Script && File::kind_hint(&file.source).is_none() {
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        if is_module_package(&dir, cache) {
+            file.kind = FileKind::Module;
+        }
+    }
+}
+
+/// Check if the nearest `package.json` in `dir` or its ancestors has `"type": "module"`.
+/// The result is cached for every directory visited along the way.
+pub(crate) fn is_module_package(dir: &Path, cache: &mut HashMap<PathBuf, bool>) -> bool {
+    let mut visited = vec![];
+    let mut is_module = false;
+
+    for ancestor in dir.ancestors() {
+        if let Some(cached) = cache.get(ancestor) {
+            is_module = *cached;
+            break;
+        }
+        visited.push(ancestor.to_path_buf());
+
+        if let Ok(src) = fs::read_to_string(ancestor.join("package.json")) {
+            is_module = serde_json::from_str::<serde_json::Value>(&src)
+                .ok()
+                .and_then(|json| Some(json.get("type")?.as_str()? == "module"))
+                .unwrap_or_default();
+            break;
+        }
+    }
+
+    for dir in visited {
+        cache.insert(dir, is_module);
+    }
+    is_module
+}
+
+/// A parsed `.editorconfig` file.
+#[derive(Debug)]
+struct EditorConfigFile {
+    /// Whether the `.editorconfig` files of parent directories are ignored.
+    root: bool,
+    /// The glob of every section and its properties, in the order of the file.
+    sections: Vec<(GlobMatcher, Vec<(String, String)>)>,
+}
+
+impl EditorConfigFile {
+    fn parse(src: &str) -> Self {
+        let mut file = Self {
+            root: false,
+            sections: vec![],
+        };
+        for line in src.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                let pattern = &line[1..line.len() - 1];
+                // patterns without a separator match files with that name in any directory
+                let pattern = match pattern.strip_prefix('/') {
+                    Some(pattern) => pattern.to_string(),
+                    None if pattern.contains('/') => pattern.to_string(),
+                    None => format!("**/{}", pattern),
+                };
+                if let Ok(glob) = GlobBuilder::new(&pattern).literal_separator(true).build() {
+                    file.sections.push((glob.compile_matcher(), vec![]));
+                }
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                let (key, value) = (key.trim().to_string(), value.trim().to_string());
+                match file.sections.last_mut() {
+                    Some((_, properties)) => properties.push((key, value)),
+                    None if key.eq_ignore_ascii_case("root") => {
+                        file.root = value.eq_ignore_ascii_case("true")
+                    }
+                    None => {}
+                }
+            }
+        }
+        file
+    }
+}
+
+/// Set the `.editorconfig` properties of a file from the `.editorconfig` files of its directory
+/// and its ancestors up to the first one with `root = true`, closer files override farther ones.
+/// The parsed files are cached for every directory visited along the way.
+fn apply_editorconfig(file: &mut File, cache: &mut HashMap<PathBuf, Option<EditorConfigFile>>) {
+    let path = match file.path.as_deref() {
+        Some(path) => path,
+        None => return,
+    };
+    let path = std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let path = canonical_path(&path);
+
+    let mut dirs = vec![];
+    for dir in path.ancestors().skip(1) {
+        let config = cache.entry(dir.to_path_buf()).or_insert_with(|| {
+            fs::read_to_string(dir.join(EDITORCONFIG_FILE))
+                .ok()
+                .map(|src| EditorConfigFile::parse(&src))
+        });
+        let root = matches!(config, Some(config) if config.root);
+        dirs.push(dir);
+        if root {
+            break;
+        }
+    }
+
+    for dir in dirs.into_iter().rev() {
+        if let (Some(config), Ok(relative)) = (&cache[dir], path.strip_prefix(dir)) {
+            for (glob, properties) in &config.sections {
+                if glob.is_match(relative) {
+                    for (key, value) in properties {
+                        file.editorconfig.set(key, value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_core::IndentStyle;
+    use tempfile::TempDir;
+
+    /// Create a temporary directory containing `files`, which is removed once it is dropped.
+    /// Every test gets its own directory so tests running in parallel never share files.
+    fn fixture(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::Builder::new()
+            .prefix("rslint_test")
+            .tempdir()
+            .unwrap();
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn restore_snapshots() {
+        let mut walker = FileWalker::empty();
+        let a = walker.add_virtual("let a;".to_string(), "a.js".to_string(), FileKind::Script);
+        let b = walker.add_virtual("let b;".to_string(), "b.js".to_string(), FileKind::Script);
+        let snapshot = walker.snapshot();
+
+        walker
+            .files
+            .get_mut(&a)
+            .unwrap()
+            .update_src("let a;\nlet c;".to_string());
+        walker.files.get_mut(&b).unwrap().kind = FileKind::Module;
+        let c = walker.add_virtual("let c;".to_string(), "c.js".to_string(), FileKind::Script);
+        assert_ne!(walker.snapshot(), snapshot);
+
+        walker.restore(snapshot.clone());
+        assert_eq!(walker.snapshot(), snapshot);
+        assert_eq!(walker.files[&a].source, "let a;");
+        assert_eq!(walker.files[&a].line_starts, [0]);
+        assert_eq!(walker.files[&b].kind, FileKind::Script);
+        assert!(!walker.files.contains_key(&c));
+    }
+
+    #[test]
+    fn files_sorted_is_case_insensitive() {
+        let mut walker = FileWalker::empty();
+        for name in ["b.js", "A.js", "c\\d.js", "a.js", "C/a.js"].iter() {
+            walker.add_virtual(String::new(), name.to_string(), FileKind::Script);
+        }
+        let names = walker
+            .files_sorted()
+            .into_iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["A.js", "a.js", "b.js", "C/a.js", "c\\d.js"]);
+    }
+
+    #[test]
+    fn transcodes_non_utf8() {
+        let dir = fixture(&[]);
+        let path = dir.path().join("latin1.js");
+        // "let café = 5;" in Latin-1
+        std::fs::write(&path, b"let caf\xE9 = 5;").unwrap();
+        let err = read_source(&path, false).unwrap_err();
+        let (src, encoding) = read_source(&path, true).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(src, "let café = 5;");
+        assert_eq!(encoding, Some(WINDOWS_1252));
+
+        let path = dir.path().join("utf16.js");
+        let utf16 = "\u{FEFF}let a;"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes().to_vec());
+        std::fs::write(&path, utf16.collect::<Vec<_>>()).unwrap();
+        let (src, encoding) = read_source(&path, true).unwrap();
+
+        assert_eq!(src, "let a;");
+        assert_eq!(encoding, Some(encoding_rs::UTF_16LE));
+    }
+
+    #[test]
+    fn include_and_exclude_patterns() {
+        let dir = fixture(&[
+            ("src/a.js", ""),
+            ("src/a.test.js", ""),
+            ("src/b.mjs", ""),
+            ("lib/c.js", ""),
+        ]);
+        let root = dir.path().to_path_buf();
+
+        let mut walker = FileWalker::empty().with_patterns(
+            vec!["src/**/*.js".to_string(), "src/**/*.mjs".to_string()],
+            vec!["**/*.test.js".to_string()],
+        );
+        walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+
+        let names = walker
+            .files_sorted()
+            .into_iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a.js", "b.mjs"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_symlinked_directories() {
+        let (dir, shared_dir) = (fixture(&[("src/a.js", "")]), fixture(&[("b.js", "")]));
+        let (root, shared) = (dir.path().to_path_buf(), shared_dir.path().to_path_buf());
+        std::os::unix::fs::symlink(&shared, root.join("shared")).unwrap();
+
+        let mut walker = FileWalker::empty().follow_symlinks(true);
+        walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+        let mut paths = walker
+            .files
+            .values()
+            .map(|file| file.path.clone().unwrap())
+            .collect::<Vec<_>>();
+        paths.sort();
+        let mut expected = vec![
+            root.join("src/a.js"),
+            shared.join("b.js").canonicalize().unwrap(),
+        ];
+        expected.sort();
+
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn include_patterns_keep_the_extension_filter() {
+        let dir = fixture(&[("src/a.js", ""), ("src/notes.md", ""), ("src/b.jsx", "")]);
+        let root = dir.path().to_path_buf();
+
+        let mut walker = FileWalker::empty().with_patterns(vec!["src/**".to_string()], vec![]);
+        walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+
+        let names = walker
+            .files_sorted()
+            .into_iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a.js"]);
+    }
+
+    #[test]
+    fn collect_errors() {
+        let dir = fixture(&[("b.js", &"let a;".repeat(10)), ("c.js", "let a;")]);
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join("a.js"), b"let caf\xE9;").unwrap();
+
+        let mut walker = FileWalker::empty().max_bytes(Some(20)).collect_errors(true);
+        let paths = vec![root.clone(), root.join("missing.js")];
+        walker.load_files_parallel(paths.into_iter(), 1, true, None, false);
+
+        let names = walker
+            .files
+            .values()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["c.js"]);
+        let mut errors = walker.errors;
+        errors.sort_by_key(|err| err.to_string());
+        assert!(matches!(errors.remove(2), WalkError::Walk(_)));
+        assert_eq!(
+            errors,
+            [
+                WalkError::Unmatched(root.join("missing.js")),
+                WalkError::NotUtf8 {
+                    path: root.join("a.js")
+                },
+                WalkError::TooLarge {
+                    path: root.join("b.js"),
+                    len: 60,
+                    max: 20
+                },
+            ]
+        );
+        assert!(errors[1].is_error() && !errors[2].is_error());
+    }
+
+    #[test]
+    fn filter_paths() {
+        let dir = fixture(&[
+            ("src/a.js", ""),
+            ("src/b.md", ""),
+            ("src/c.ts", ""),
+            ("d.js", ""),
+        ]);
+        let root = dir.path().to_path_buf();
+
+        let walker = FileWalker::empty();
+        let paths = ["src/a.js", "src/b.md", "src/c.ts", "d.js"]
+            .iter()
+            .map(|name| root.join(name))
+            .collect::<Vec<_>>();
+        let in_src = walker.filter_paths(paths.clone(), &[root.join("src")]);
+        let globbed =
+            walker.filter_paths(paths.clone(), &[root.join("src/*.ts"), root.join("d.js")]);
+
+        assert_eq!(in_src, [root.join("src/a.js"), root.join("src/c.ts")]);
+        assert_eq!(globbed, [root.join("src/c.ts"), root.join("d.js")]);
+    }
+
+    #[test]
+    fn overlapping_paths_are_loaded_once() {
+        let dir = fixture(&[("a.js", ""), ("sub/b.js", "")]);
+        let root = dir.path().to_path_buf();
+
+        let mut walker = FileWalker::empty();
+        let paths = vec![root.join("sub"), root.clone(), root.join("sub/../a.js")];
+        let unmatched = walker.load_files_parallel(paths.into_iter(), 1, true, None, false);
+
+        assert!(unmatched.is_empty());
+        let paths = walker
+            .files_sorted()
+            .into_iter()
+            .map(|file| file.path.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, [root.join("a.js"), root.join("sub/b.js")]);
+    }
+
+    #[test]
+    fn rslintignore_patterns() {
+        let dir = fixture(&[
+            ("a.js", ""),
+            ("b.js", ""),
+            ("keep.js", ""),
+            ("build/c.js", ""),
+            ("sub/build.js", ""),
+            (
+                RSLINT_IGNORE_FILE,
+                "# generated code\n\n*.js\n!a.js\n!**/build.js\nbuild/\n",
+            ),
+        ]);
+        let root = dir.path().to_path_buf();
+
+        let load = |no_ignore| {
+            let mut walker = FileWalker::empty();
+            walker.load_files_parallel(std::iter::once(root.clone()), 1, no_ignore, None, false);
+            let mut names = walker
+                .files
+                .values()
+                .map(|file| file.name.clone())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        let ignored = load(false);
+        let all = load(true);
+
+        assert_eq!(ignored, ["a.js", "build.js"]);
+        assert_eq!(all, ["a.js", "b.js", "build.js", "c.js", "keep.js"]);
+    }
+
+    #[test]
+    fn summarize() {
+        let mut walker = FileWalker::empty();
+        walker.add_virtual(
+            "let a;\nlet b;".to_string(),
+            "a.js".to_string(),
+            FileKind::Script,
+        );
+        walker.add_virtual(
+            "export {};".to_string(),
+            "b.mjs".to_string(),
+            FileKind::Module,
+        );
+        walker.add_virtual(String::new(), "c.js".to_string(), FileKind::Script);
+
+        assert_eq!(
+            walker.summarize(),
+            WalkSummary {
+                files: 3,
+                bytes: 23,
+                lines: 4,
+                scripts: 2,
+                modules: 1,
+                typescript: 0,
+            }
+        );
+        let names = |kind| {
+            walker
+                .iter_kind(kind)
+                .map(|file| file.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(FileKind::Script), ["a.js", "c.js"]);
+        assert_eq!(names(FileKind::Module), ["b.mjs"]);
+        assert!(names(FileKind::TypeScript).is_empty());
+    }
+
+    #[test]
+    fn empty_files() {
+        let mut walker = FileWalker::empty();
+        for (source, name) in [("", "a.js"), ("let b;", "b.js"), (" \r\n\t", "c.js")].iter() {
+            walker.add_virtual(source.to_string(), name.to_string(), FileKind::Script);
+        }
+        let empty = walker
+            .empty_files()
+            .into_iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(empty, ["a.js", "c.js"]);
+    }
+
+    #[test]
+    fn skip_oversized_archive_entries() {
+        use std::io::Write;
+
+        let dir = fixture(&[]);
+        let zip_path = dir.path().join("src.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.start_file("a.js", Default::default()).unwrap();
+        zip.write_all(b"let a;").unwrap();
+        zip.start_file("big.js", Default::default()).unwrap();
+        zip.write_all("let b;\n".repeat(100).as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let mut walker = FileWalker::empty().max_bytes(Some(20)).collect_errors(true);
+        let loaded = walker.load_archive(&zip_path, false);
+        assert_eq!(loaded.unwrap(), 1);
+        assert_eq!(walker.files_sorted()[0].name, "a.js");
+        assert_eq!(
+            walker.errors,
+            [WalkError::TooLarge {
+                path: PathBuf::from("big.js"),
+                len: 700,
+                max: 20,
+            }]
+        );
+
+        // a header claiming a smaller size than the entry has is not trusted
+        let mut entry = io::Cursor::new(vec![b' '; 100]);
+        assert_eq!(read_entry(&mut entry, 1, Some(10)).unwrap(), Err(11));
+        assert_eq!(entry.position(), 11);
+        let mut entry = io::Cursor::new(vec![b' '; 100]);
+        assert_eq!(read_entry(&mut entry, 100, Some(10)).unwrap(), Err(100));
+        assert_eq!(entry.position(), 0);
+        let mut entry = io::Cursor::new(vec![b' '; 5]);
+        assert_eq!(
+            read_entry(&mut entry, 5, Some(10)).unwrap(),
+            Ok(vec![b' '; 5])
+        );
+    }
+
+    #[test]
+    fn load_archives() {
+        use std::io::Write;
+
+        let dir = fixture(&[]);
+        let dir = dir.path();
+        let entries = [
+            ("src/a.ts", "let a;"),
+            ("src/b.mjs", "let b;"),
+            ("src/c.txt", "let c;"),
+            ("src/gen/d.js", "let d;"),
+            ("src/.rslintignore", "gen/"),
+            ("node_modules/e.js", "let e;"),
+            ("../f.js", "let f;"),
+        ];
+
+        let zip_path = dir.join("src.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.add_directory("src/", Default::default()).unwrap();
+        for (name, src) in entries.iter() {
+            zip.start_file(*name, Default::default()).unwrap();
+            zip.write_all(src.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let tar_path = dir.join("src.tar.gz");
+        let gz = flate2::write::GzEncoder::new(
+            std::fs::File::create(&tar_path).unwrap(),
+            Default::default(),
+        );
+        let mut tar = tar::Builder::new(gz);
+        for (name, src) in entries.iter().filter(|(name, _)| !name.starts_with("..")) {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(src.len() as u64);
+            header.set_cksum();
+            tar.append_data(&mut header, name, src.as_bytes()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let load = |path: &Path, no_ignore: bool| {
+            let mut walker = FileWalker::empty();
+            walker.load_archive(path, no_ignore).unwrap();
+            walker
+                .files_sorted()
+                .into_iter()
+                .map(|file| (file.name.clone(), file.kind, file.path.is_none()))
+                .collect::<Vec<_>>()
+        };
+        let zip_files = load(&zip_path, false);
+        let tar_files = load(&tar_path, false);
+        let everything = load(&zip_path, true);
+        let unsupported = FileWalker::from_archive(dir.join("src.rar"));
+
+        let expected = vec![
+            ("src/a.ts".to_string(), FileKind::TypeScript, true),
+            ("src/b.mjs".to_string(), FileKind::Module, true),
+        ];
+        assert_eq!(zip_files, expected);
+        assert_eq!(tar_files, expected);
+        let names = everything
+            .into_iter()
+            .map(|(name, ..)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["node_modules/e.js", "src/a.ts", "src/b.mjs", "src/gen/d.js"]
+        );
+        assert!(unsupported.is_err());
+    }
+
+    #[test]
+    fn builder_options() {
+        let dir = fixture(&[
+            ("a.js", "let a;"),
+            ("b.cjs", "let a;"),
+            ("c.ts", "let a;"),
+            ("node_modules/d.js", "let a;"),
+        ]);
+        let root = dir.path().to_path_buf();
+
+        let builder = FileWalker::builder()
+            .extensions(vec!["js", "cjs"])
+            .exclude(vec!["a.js".to_string()])
+            .no_ignore(true)
+            .num_threads(1);
+        let walker = builder.clone().build_from(vec![root.clone()]);
+        let unmatched = builder.try_build_from(vec![root.join("c.ts")]);
+
+        let mut names = walker
+            .files
+            .values()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, ["b.cjs", "d.js"]);
+        assert_eq!(walker.extensions, ["js", "cjs"]);
+        assert_eq!(unmatched, Err(vec![root.join("c.ts")]));
+    }
+
+    #[test]
+    fn local_ids() {
+        let walker = |names: &[&str]| {
+            let mut walker = FileWalker::with_local_ids();
+            let ids = names
+                .iter()
+                .map(|name| walker.add_virtual(String::new(), name.to_string(), FileKind::Script))
+                .collect::<Vec<_>>();
+            (walker, ids)
+        };
+        let (a, a_ids) = walker(&["b.js", "c.js", "a.js"]);
+        let (b, b_ids) = walker(&["a.js", "c.js", "b.js"]);
+
+        let ids = |walker: &FileWalker| {
+            let files = walker.files_sorted();
+            files
+                .iter()
+                .map(|file| (file.id, file.name.clone()))
+                .collect::<Vec<_>>()
+        };
+        let expected = vec![
+            (1, "a.js".to_string()),
+            (2, "b.js".to_string()),
+            (3, "c.js".to_string()),
+        ];
+        assert_eq!(ids(&a), expected);
+        assert_eq!(ids(&b), expected);
+        // the returned id is the one of the file right after it was added
+        assert_eq!(a_ids, [1, 2, 1]);
+        assert_eq!(b_ids, [1, 2, 2]);
+
+        // files added at once are only numbered after every one of them was added
+        let mut walker = FileWalker::with_local_ids();
+        let added = walker.add_virtuals(
+            ["b.js", "c.js", "a.js"]
+                .iter()
+                .map(|name| (String::new(), name.to_string(), FileKind::Script)),
+        );
+        assert_eq!(ids(&walker), expected);
+        assert_eq!(added, [2, 3, 1]);
+    }
+
+    #[test]
+    fn expand_globs() {
+        let dir = fixture(&[
+            ("a.js", "let a;"),
+            ("src/b.js", "let a;"),
+            ("src/lib/c.js", "let a;"),
+            ("src/lib/d.txt", "let a;"),
+            ("src/node_modules/e.js", "let a;"),
+            ("pages/[id].js", "let a;"),
+        ]);
+        let root = dir.path().to_path_buf();
+        let load = |path: PathBuf| {
+            let mut walker = FileWalker::empty();
+            walker.load_files_parallel(std::iter::once(path), 1, false, None, false);
+            let mut names = walker
+                .files
+                .values()
+                .map(|file| file.name.clone())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        let recursive = load(root.join("src").join("**").join("*"));
+        let shallow = load(root.join("s*").join("*.js"));
+        let literal = load(root.join("pages").join("[id].js"));
+        let parts = glob_parts(Path::new("*.js"));
+
+        assert_eq!(recursive, ["b.js", "c.js"]);
+        assert_eq!(shallow, ["b.js"]);
+        assert_eq!(literal, ["[id].js"]);
+        assert_eq!(parts, Some((PathBuf::from("."), "*.js".to_string())));
+        assert_eq!(glob_parts(Path::new("src/a.js")), None);
+    }
+
+    #[test]
+    fn stream_files() {
+        let dir = fixture(&[("a.js", "let a;"), ("sub/b.js", "let b;")]);
+        let root = dir.path().to_path_buf();
+
+        let paths = vec![root.join("sub"), root.clone(), root.join("missing")];
+        let mut stream = FileWalker::empty().stream_files(paths, 1, true, None, false);
+        let mut sources = stream.by_ref().map(|file| file.source).collect::<Vec<_>>();
+        let (unmatched, errors) = stream.finish();
+
+        sources.sort();
+        assert_eq!(sources, ["let a;", "let b;"]);
+        assert_eq!(unmatched, [root.join("missing")]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn stream_files_collect_errors() {
+        let dir = fixture(&[("a.js", "let a;"), ("b.js", &"let a;".repeat(10))]);
+        let root = dir.path().to_path_buf();
+
+        let paths = vec![root.clone(), root.join("missing.js")];
+        let stream = FileWalker::empty()
+            .max_bytes(Some(20))
+            .collect_errors(true)
+            .stream_files(paths, 1, true, None, false);
+        let (_, errors) = stream.finish();
+
+        assert!(errors.contains(&WalkError::TooLarge {
+            path: root.join("b.js"),
+            len: 60,
+            max: 20,
+        }));
+        assert!(errors.contains(&WalkError::Unmatched(root.join("missing.js"))));
+    }
+
+    #[test]
+    fn skip_files_over_max_bytes() {
+        let dir = fixture(&[("a.js", "let a = 1;"), ("b.js", "let b = 10;")]);
+        let root = dir.path().to_path_buf();
+
+        let mut walker = FileWalker::empty().max_bytes(Some(10)).collect_errors(true);
+        walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+
+        let names = walker
+            .files
+            .values()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a.js"]);
+        assert_eq!(
+            walker.errors,
+            [WalkError::TooLarge {
+                path: root.join("b.js"),
+                len: 11,
+                max: 10,
+            }]
+        );
+
+        // a root which only matches a file over the limit is not unmatched too
+        let mut walker = FileWalker::empty().max_bytes(Some(10)).collect_errors(true);
+        let unmatched =
+            walker.load_files_parallel(std::iter::once(root.join("b.js")), 1, true, None, false);
+        assert!(unmatched.is_empty());
+        assert!(walker.files.is_empty());
+        assert_eq!(
+            walker.errors,
+            [WalkError::TooLarge {
+                path: root.join("b.js"),
+                len: 11,
+                max: 10,
+            }]
+        );
+    }
+
+    #[test]
+    fn module_packages() {
+        let dir = fixture(&[]);
+        let root = dir.path().to_path_buf();
+        let packages = [
+            ("module", r#"{ "type": "module" }"#),
+            ("commonjs", r#"{ "type": "commonjs" }"#),
+            ("untyped", r#"{ "name": "untyped" }"#),
+            ("malformed", r#"{ "type": "module""#),
+        ];
+        for (dir, package) in packages.iter() {
+            std::fs::create_dir_all(root.join(dir).join("nested")).unwrap();
+            std::fs::write(root.join(dir).join("package.json"), package).unwrap();
+        }
+
+        let mut cache = HashMap::new();
+        let kinds = packages
+            .iter()
+            .map(|(dir, _)| is_module_package(&root.join(dir).join("nested"), &mut cache))
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds, [true, false, false, false]);
+        // the directories visited on the way to the package are cached too
+        assert_eq!(cache.get(&root.join("module").join("nested")), Some(&true));
+        assert_eq!(cache.get(&root.join("module")), Some(&true));
+    }
+
+    #[test]
+    fn infer_kinds_from_packages() {
+        let dir = fixture(&[("package.json", r#"{ "type": "module" }"#)]);
+        let root = dir.path().to_path_buf();
+
+        let mut cache = HashMap::new();
+        let mut kind = |name: &str, source: &str| {
+            let mut file = File::new_concrete(source.to_string(), root.join(name));
+            infer_kind_from_package(&mut file, &mut cache);
+            file.kind
+        };
+        let kinds = [
+            kind("a.js", "let a;"),
+            kind("b.cjs", "let b;"),
+            kind("c.js", "// @rslint-script\nlet c;"),
+            kind("d.ts", "let d;"),
+        ];
+
+        assert_eq!(
+            kinds,
+            [
+                FileKind::Module,
+                FileKind::Script,
+                FileKind::Script,
+                FileKind::TypeScript
+            ]
+        );
+    }
+
+    #[test]
+    fn shebang_scripts() {
+        let dir = fixture(&[
+            ("tool", "#!/usr/bin/env node\nlet a;"),
+            ("run", "#!/bin/sh\necho node"),
+        ]);
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join("data"), [0xff, 0x00, 0x12]).unwrap();
+
+        let names = |shebang_scripts| {
+            let mut walker = FileWalker::empty().shebang_scripts(shebang_scripts);
+            walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+            walker
+                .files
+                .values()
+                .map(|file| (file.name.clone(), file.kind))
+                .collect::<Vec<_>>()
+        };
+        let (enabled, disabled) = (names(true), names(false));
+
+        assert_eq!(enabled, [("tool".to_string(), FileKind::Script)]);
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn editorconfig_properties() {
+        let dir = fixture(&[
+            (
+                ".editorconfig",
+                "root = true\n\n[*]\nindent_style = space\nindent_size = 2\n\n[*.js]\nmax_line_length = 80\n",
+            ),
+            ("lib/.editorconfig", "[*.js]\nindent_size = 4\n"),
+            ("a.js", "let a;"),
+            ("lib/b.js", "let b;"),
+        ]);
+        let root = dir.path().to_path_buf();
+
+        let mut walker = FileWalker::empty();
+        walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+        let config = |name: &str| {
+            walker
+                .files
+                .values()
+                .find(|file| file.path.as_ref().unwrap().ends_with(name))
+                .unwrap()
+                .editorconfig
+        };
+        let (a, b) = (config("a.js"), config("b.js"));
+
+        assert_eq!(a.indent_style, Some(IndentStyle::Space));
+        assert_eq!(a.indent_size, Some(2));
+        assert_eq!(a.max_line_length, Some(80));
+        assert_eq!(b.indent_size, Some(4));
+        assert_eq!(b.max_line_length, Some(80));
+    }
+
+    #[test]
+    fn base_dir_names() {
+        let mut walker = FileWalker::empty().with_base_dir(PathBuf::from("/project"));
+        let inside = File::new_concrete(String::new(), PathBuf::from("/project/src/a.js"));
+        let outside = File::new_concrete(String::new(), PathBuf::from("/other/b.js"));
+        let (inside, outside) = (
+            walker.files.entry(inside.id).or_insert(inside).id,
+            walker.files.entry(outside.id).or_insert(outside).id,
+        );
+        let virtual_file =
+            walker.add_virtual(String::new(), "<stdin>".to_string(), FileKind::Script);
+
+        assert_eq!(
+            walker.name(inside),
+            Some(Path::new("src").join("a.js").to_str().unwrap())
+        );
+        assert_eq!(walker.name(outside), Some("/other/b.js"));
+        assert_eq!(walker.name(virtual_file), Some("<stdin>"));
+    }
+
+    #[test]
+    fn disambiguate_colliding_names() {
+        let mut walker = FileWalker::empty().with_base_dir(PathBuf::from("/project"));
+        let paths = [
+            "/project/src/a/index.js",
+            "src/a/index.js",
+            "/project/lib.js",
+        ];
+        let ids = paths
+            .iter()
+            .map(|path| {
+                let file = File::new_concrete(String::new(), PathBuf::from(path));
+                walker.files.entry(file.id).or_insert(file).id
+            })
+            .collect::<Vec<_>>();
+        let first = walker.add_virtual(String::new(), "<stdin>".to_string(), FileKind::Script);
+        let second = walker.add_virtual(String::new(), "<stdin>".to_string(), FileKind::Script);
+
+        let index = Path::new("src").join("a").join("index.js");
+        let index = index.to_str().unwrap();
+        assert_eq!(
+            walker.name(ids[0]),
+            Some(format!("{} — project/src/a", index).as_str())
+        );
+        assert_eq!(
+            walker.name(ids[1]),
+            Some(format!("{} — src/a", index).as_str())
+        );
+        assert_eq!(walker.name(ids[2]), Some("lib.js"));
+        assert_eq!(walker.name(first), Some("<stdin> (1)"));
+        assert_eq!(walker.name(second), Some("<stdin> (2)"));
+
+        walker.remove_by_id(second);
+        assert_eq!(walker.name(first), Some("<stdin>"));
+    }
+
+    #[test]
+    fn look_up_files_by_path() {
+        let dir = fixture(&[("b.js", "let b;"), ("c.js", "let c;")]);
+        let root = dir.path().to_path_buf();
+
+        let mut walker = FileWalker::with_local_ids();
+        walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+        // renumbering the files keeps their paths pointing to them
+        walker.add_virtual(String::new(), "a.js".to_string(), FileKind::Script);
+        let path_of = |walker: &FileWalker, path: &Path| walker.get_by_path(path)?.path.clone();
+        assert_eq!(
+            path_of(&walker, &root.join("b.js")),
+            Some(root.join("b.js"))
+        );
+        assert_eq!(
+            path_of(&walker, &root.join("./c.js")),
+            Some(root.join("c.js"))
+        );
+
+        let file = File::new_concrete(String::new(), root.join("d.js"));
+        walker.files.insert(file.id, file);
+        assert_eq!(
+            path_of(&walker, &root.join("d.js")),
+            Some(root.join("d.js"))
+        );
+
+        let b = walker.get_by_path(&root.join("b.js")).unwrap().id;
+        walker.remove_by_id(b);
+        assert!(walker.get_by_path(&root.join("b.js")).is_none());
+    }
+
+    #[test]
+    fn reload_by_full_path() {
+        let dir = fixture(&[("a/index.js", "let a;"), ("b/index.js", "let a;")]);
+        let root = dir.path().to_path_buf();
+
+        let mut walker = FileWalker::empty();
+        walker.load_files_parallel(std::iter::once(root.clone()), 1, true, None, false);
+        let source = |walker: &FileWalker, dir| {
+            walker
+                .files
+                .values()
+                .find(|file| {
+                    file.path
+                        .as_ref()
+                        .unwrap()
+                        .ends_with(Path::new(dir).join("index.js"))
+                })
+                .unwrap()
+                .source
+                .clone()
+        };
+
+        std::fs::write(root.join("b/index.js"), "let b;\n").unwrap();
+        assert!(walker.maybe_update_file_src(root.join("a/../b/index.js")));
+        // saving without changes does not update the file
+        assert!(!walker.maybe_update_file_src(root.join("b/index.js")));
+        assert_eq!(source(&walker, "a"), "let a;");
+        assert_eq!(source(&walker, "b"), "let b;\n");
+
+        let file = walker.get_by_path(&root.join("b/./index.js")).unwrap();
+        assert_eq!(
+            file.path.as_deref(),
+            Some(root.join("b/index.js").as_path())
+        );
+        assert!(walker.get_by_path(&root.join("c/index.js")).is_none());
+
+        std::fs::write(root.join("a/index.js"), "let c;").unwrap();
+        let changed = walker.reload_all();
+        assert_eq!(source(&walker, "a"), "let c;");
+        assert_eq!(changed.len(), 1);
+        assert_eq!(walker.files[&changed[0]].source, "let c;");
+        assert_eq!(source(&walker, "b"), "let b;\n");
+
+        let removed = walker
+            .remove_by_path(&root.join("a/../b/index.js"))
+            .unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(walker.source(removed.id), None);
+        assert_eq!(walker.name(removed.id), None);
+        assert_eq!(walker.line_range(removed.id, 0), None);
+        let a = walker.files.keys().copied().next().unwrap();
+        assert_eq!(
+            walker
+                .remove_by_path(&root.join("a/index.js"))
+                .map(|file| file.id),
+            Some(a)
+        );
+        assert!(walker.remove_by_id(a).is_none());
+    }
+
+    #[test]
+    fn ignored_dirs() {
+        let dir = fixture(&[
+            ("a.js", ""),
+            ("node_modules/b.js", ""),
+            ("src/Node_Modules/c.js", ""),
+            ("vendor/bundles/d.js", ""),
+            ("vendor/e.js", ""),
+            ("src/vendor/bundles/f.js", ""),
+        ]);
+        let root = dir.path().to_path_buf();
+
+        let load_with = |walker: FileWalker, path: PathBuf, no_ignore: bool| {
+            let mut walker = walker;
+            walker.load_files_parallel(std::iter::once(path), 1, no_ignore, None, false);
+            let mut names = walker
+                .files
+                .values()
+                .map(|file| file.name.clone())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        let load = |walker, path| load_with(walker, path, false);
+        let default = load(FileWalker::empty(), root.clone());
+        let nested = load(
+            FileWalker::empty().with_ignored_dirs(vec!["node_modules", "Vendor/Bundles/"]),
+            root.clone(),
+        );
+        let explicit = load(FileWalker::empty(), root.join("node_modules"));
+        let no_ignore = load_with(FileWalker::empty(), root.clone(), true);
+
+        assert_eq!(default, ["a.js", "d.js", "e.js", "f.js"]);
+        assert_eq!(nested, ["a.js", "e.js", "f.js"]);
+        assert_eq!(explicit, ["b.js"]);
+        assert_eq!(no_ignore, ["a.js", "b.js", "c.js", "d.js", "e.js", "f.js"]);
+    }
+
+    #[test]
+    fn strips_byte_order_mark() {
+        let dir = fixture(&[("bom.js", "\u{FEFF}let a = 5;")]);
+        let path = dir.path().join("bom.js");
+
+        let mut walker = FileWalker::empty();
+        walker.load_files_parallel(std::iter::once(path.clone()), 1, true, None, false);
+
+        let file = walker.files.values().next().unwrap();
+        assert_eq!(file.source, "let a = 5;");
+        let first_token = file.parse().first_token().unwrap();
+        assert_eq!(usize::from(first_token.text_range().start()), 0);
+        assert_eq!(first_token.text(), "let");
+    }
+}
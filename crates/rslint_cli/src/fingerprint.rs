@@ -0,0 +1,78 @@
+//! Stable fingerprints of diagnostics, which identify a diagnostic across edits which only move it.
+//!
+//! The fingerprint of a diagnostic is a hash of its rule, its message, and the tokens of the line
+//! its primary label starts on. Whitespace and comments are not part of it, and neither is the
+//! position of the diagnostic, so it stays the same when lines are inserted or removed above the
+//! diagnostic, when the line is indented or reformatted, or when the file is renamed. It changes
+//! whenever any other token of the line changes, or when the message of the rule changes.
+//!
+//! Identical diagnostics on identical lines have the same fingerprint, so a fingerprint identifies
+//! a kind of problem rather than a single occurrence of it.
+
+use crate::cache::hash_source;
+use rslint_errors::{file::Files, Diagnostic};
+use rslint_lexer::{Lexer, SyntaxKind};
+
+/// The fingerprint of a diagnostic as 16 hex digits, see the [module docs](self).
+pub fn fingerprint(diagnostic: &Diagnostic, files: &dyn Files) -> String {
+    let line = diagnostic
+        .primary
+        .as_ref()
+        .and_then(|primary| {
+            let file = diagnostic.file_id;
+            let line = files.line_index(file, primary.span.range.start)?;
+            files.source(file)?.get(files.line_range(file, line)?)
+        })
+        .unwrap_or_default();
+    fingerprint_of(diagnostic.code.as_deref(), &diagnostic.title, line)
+}
+
+/// The fingerprint of a diagnostic of `rule` with `message` on a line with the text `line`.
+pub(crate) fn fingerprint_of(rule: Option<&str>, message: &str, line: &str) -> String {
+    let mut key = format!("{}\0{}", rule.unwrap_or_default(), message);
+    let mut offset = 0;
+    for (token, _) in Lexer::from_str(line, 0) {
+        let text = &line[offset..offset + token.len];
+        offset += token.len;
+        if !matches!(
+            token.kind,
+            SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::EOF
+        ) {
+            key.push('\0');
+            key.push_str(text);
+        }
+    }
+    format!("{:016x}", hash_source(&key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileWalker;
+    use rslint_core::{lint_file, CstRuleStore};
+    use rslint_parser::FileKind;
+
+    #[test]
+    fn stable_fingerprints() {
+        let store = CstRuleStore::new().builtins();
+        let fingerprints = |source: &str| {
+            let mut walker = FileWalker::empty();
+            let id = walker.add_virtual(source.to_string(), "a.js".to_string(), FileKind::Script);
+            let result = lint_file(&walker.files[&id], &store, false);
+            result
+                .diagnostics()
+                .map(|d| fingerprint(d, &walker))
+                .collect::<Vec<_>>()
+        };
+
+        let original = fingerprints("if (a) {}\n");
+        assert_eq!(original.len(), 1);
+        assert_eq!(original[0].len(), 16);
+        assert_eq!(fingerprints("let b;\n\n  if(a){} // empty\n"), original);
+        assert_ne!(fingerprints("if (b) {}\n"), original);
+        assert_eq!(
+            fingerprint_of(Some("no-empty"), "empty block", " if (a)  {}"),
+            fingerprint_of(Some("no-empty"), "empty block", "if (a) {}")
+        );
+    }
+}
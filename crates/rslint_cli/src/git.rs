@@ -0,0 +1,43 @@
+//! The files changed since a git ref, used to only lint the files changed by a branch.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Run git with `args` and return its stdout, or its stderr if it fails.
+fn git(args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|err| format!("failed to run git: {}", err))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// The files which were added, copied, modified, or renamed since `reference`, relative to the
+/// working directory. Deleted files and files outside of the working directory are skipped.
+///
+/// The files are compared against the merge base of `reference` and `HEAD`, so commits made to
+/// `reference` after the current branch was created are not considered changes. Uncommitted
+/// changes to tracked files are included, untracked files are not.
+pub fn changed_files(reference: &str) -> Result<Vec<PathBuf>, String> {
+    let base = git(&["merge-base", reference, "HEAD"])?;
+    let base = String::from_utf8_lossy(&base).trim().to_string();
+    let names = git(&[
+        "diff",
+        "--name-only",
+        "--relative",
+        "--diff-filter=ACMR",
+        "-z",
+        &base,
+        "--",
+    ])?;
+    Ok(names
+        .split(|byte| *byte == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| PathBuf::from(String::from_utf8_lossy(name).into_owned()))
+        .filter(|path| path.is_file())
+        .collect())
+}
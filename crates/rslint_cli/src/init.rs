@@ -0,0 +1,290 @@
+//! Scaffolding the config of a new project, see [`init`].
+
+use crate::{config, lint_err, lint_note};
+use rslint_core::{CstRuleStore, RuleRegistry};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// The patterns ignored by default, `node_modules` is always ignored.
+const DEFAULT_IGNORE: [&str; 3] = ["dist/", "build/", "coverage/"];
+
+/// The choices the scaffolded config is made from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InitOptions {
+    /// Enable every rule instead of only the recommended ones.
+    pub all_rules: bool,
+    /// The patterns written to the `.rslintignore`, which is not written if there are none.
+    pub ignore: Vec<String>,
+}
+
+/// Write a starter `rslintrc.toml` and `.rslintignore` to the working directory, asking which
+/// rules to enable and which paths to ignore if stdin is a terminal, and using the defaults
+/// otherwise. Returns whether the config was written.
+///
+/// An existing config is only replaced if `force` is set.
+pub fn init(force: bool) -> bool {
+    let dir = Path::new(".");
+    if let Some(existing) = config::config_file(dir).filter(|_| !force) {
+        lint_err!(
+            "`{}` already exists, pass `--force` to overwrite it",
+            existing.display()
+        );
+        return false;
+    }
+    let mut options = InitOptions {
+        all_rules: false,
+        ignore: DEFAULT_IGNORE.iter().map(|s| s.to_string()).collect(),
+    };
+    if atty::is(atty::Stream::Stdin) {
+        let stdin = io::stdin();
+        options = ask(&mut stdin.lock(), options);
+    }
+
+    match scaffold(dir, &options, force) {
+        Ok(written) => {
+            for path in written {
+                lint_note!("wrote `{}`", path.display());
+            }
+            true
+        }
+        Err(err) => {
+            lint_err!("failed to write the config: {}", err);
+            false
+        }
+    }
+}
+
+/// Ask for every option on stdout, keeping the default of the options which are not answered.
+fn ask(input: &mut impl BufRead, defaults: InitOptions) -> InitOptions {
+    let mut prompt = |question: &str, default: &str| {
+        print!("{} ({}) ", question, default);
+        io::stdout().flush().ok();
+        let mut answer = String::new();
+        input.read_line(&mut answer).ok();
+        answer.trim().to_ascii_lowercase()
+    };
+    let yes_no = |default| if default { "Y/n" } else { "y/N" };
+
+    let all_rules = prompt(
+        "Enable every rule instead of only the recommended ones?",
+        yes_no(defaults.all_rules),
+    );
+    let ignore = prompt(
+        "Which paths should be ignored? Separate them with commas, or answer `none`",
+        &defaults.ignore.join(", "),
+    );
+    let answer = |answer: &str, default| match answer {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => default,
+    };
+    InitOptions {
+        all_rules: answer(&all_rules, defaults.all_rules),
+        ignore: match ignore.as_str() {
+            "" => defaults.ignore,
+            "none" => vec![],
+            _ => ignore
+                .split(',')
+                .map(|pattern| pattern.trim().to_string())
+                .filter(|pattern| !pattern.is_empty())
+                .collect(),
+        },
+    }
+}
+
+/// Write the config and the ignore file to `dir`, returning the paths of the written files.
+///
+/// With `force`, an existing config is replaced, including an `rslintrc.json` which would take
+/// priority over the new `rslintrc.toml`. An existing `.rslintignore` is never overwritten.
+pub(crate) fn scaffold(
+    dir: &Path,
+    options: &InitOptions,
+    force: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let existing = config::config_file(dir);
+    if let Some(existing) = existing.as_ref().filter(|_| !force) {
+        return Err(format!("`{}` already exists", existing.display()));
+    }
+
+    let mut written = vec![];
+    let path = dir.join("rslintrc.toml");
+    fs::write(&path, config_source(options)).map_err(|err| err.to_string())?;
+    written.push(path);
+    if let Some(json) = existing.filter(|existing| existing.ends_with("rslintrc.json")) {
+        fs::remove_file(&json).map_err(|err| err.to_string())?;
+    }
+
+    let ignore = dir.join(".rslintignore");
+    if !options.ignore.is_empty() && !ignore.exists() {
+        let mut source = options.ignore.join("\n");
+        source.push('\n');
+        fs::write(&ignore, source).map_err(|err| err.to_string())?;
+        written.push(ignore);
+    }
+    Ok(written)
+}
+
+/// The source of the scaffolded `rslintrc.toml`.
+fn config_source(options: &InitOptions) -> String {
+    let registry = RuleRegistry::builtins();
+    let mut source = String::from(
+        "# The config of RSLint, see https://rslint.org/guide/config.html\n\
+         # The configs of the parent directories are not merged into this one.\n\
+         root = true\n\n",
+    );
+    if options.all_rules {
+        let mut groups = registry
+            .all_rules()
+            .iter()
+            .map(|rule| rule.group)
+            .collect::<Vec<_>>();
+        groups.sort_unstable();
+        groups.dedup();
+        let groups = groups
+            .iter()
+            .map(|group| format!("{:?}", group))
+            .collect::<Vec<_>>();
+        source.push_str("[rules]\n# every rule of these groups is an error\n");
+        source.push_str(&format!("groups = [{}]\n", groups.join(", ")));
+    } else {
+        source.push_str("# the recommended rules with their default options\n[rules.errors]\n");
+        for rule in CstRuleStore::new().recommended().rules {
+            // rules are serialized as `{ "RuleName": { ..options } }`
+            let options = match serde_json::to_value(&rule) {
+                Ok(Value::Object(map)) => map.into_iter().next().map(|(_, options)| options),
+                _ => None,
+            };
+            let options = options.as_ref().and_then(inline_toml);
+            source.push_str(&format!(
+                "{} = {}\n",
+                rule.name(),
+                options.as_deref().unwrap_or("{}")
+            ));
+        }
+    }
+    source
+}
+
+/// Format JSON as inline TOML, leaving out `null` values.
+fn inline_toml(value: &Value) -> Option<String> {
+    let join = |items: Vec<String>| items.join(", ");
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::String(string) => toml::Value::String(string.clone()).to_string(),
+        Value::Array(items) => {
+            format!("[{}]", join(items.iter().filter_map(inline_toml).collect()))
+        }
+        Value::Object(map) if map.is_empty() => "{}".to_string(),
+        Value::Object(map) => {
+            let entries = map.iter().filter_map(|(key, value)| {
+                let bare = key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+                let key = if bare {
+                    key.clone()
+                } else {
+                    toml::Value::String(key.clone()).to_string()
+                };
+                Some(format!("{} = {}", key, inline_toml(value)?))
+            });
+            format!("{{ {} }}", join(entries.collect()))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn rule_names(dir: &Path) -> Vec<&'static str> {
+        let mut resolver = config::ConfigResolver::new(true);
+        let config = resolver.resolve(dir);
+        let (store, warnings) = config.rules_store();
+        assert!(resolver.errors.is_empty());
+        assert!(warnings.is_empty());
+        let mut names = store
+            .rules
+            .iter()
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn scaffold_configs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let mut options = InitOptions {
+            all_rules: false,
+            ignore: vec!["dist/".to_string(), "*.min.js".to_string()],
+        };
+
+        let written = scaffold(dir, &options, false).unwrap();
+        assert_eq!(
+            written,
+            [dir.join("rslintrc.toml"), dir.join(".rslintignore")]
+        );
+        let ignore = fs::read_to_string(dir.join(".rslintignore")).unwrap();
+        assert_eq!(ignore, "dist/\n*.min.js\n");
+        let mut recommended = CstRuleStore::new()
+            .recommended()
+            .rules
+            .iter()
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+        recommended.sort_unstable();
+        assert_eq!(rule_names(dir), recommended);
+
+        options.all_rules = true;
+        assert!(scaffold(dir, &options, false).is_err());
+        fs::write(dir.join("rslintrc.json"), "{}").unwrap();
+        // the ignore file already exists so only the config is written
+        let written = scaffold(dir, &options, true).unwrap();
+        assert_eq!(written, [dir.join("rslintrc.toml")]);
+        assert!(!dir.join("rslintrc.json").exists());
+        let builtins = CstRuleStore::new().builtins().rules.len();
+        assert_eq!(rule_names(dir).len(), builtins);
+    }
+
+    #[test]
+    fn list_every_group_once() {
+        let source = config_source(&InitOptions {
+            all_rules: true,
+            ignore: vec![],
+        });
+        let line = source
+            .lines()
+            .find(|line| line.starts_with("groups = "))
+            .unwrap();
+        let groups = line.split(", ").collect::<Vec<_>>();
+        let mut unique = groups.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(groups.len(), unique.len());
+    }
+
+    #[test]
+    fn ask_options() {
+        let defaults = InitOptions {
+            all_rules: false,
+            ignore: vec!["dist/".to_string()],
+        };
+        let options = ask(&mut Cursor::new("yes\nlib/, ,out\n"), defaults.clone());
+        assert_eq!(
+            options,
+            InitOptions {
+                all_rules: true,
+                ignore: vec!["lib/".to_string(), "out".to_string()],
+            }
+        );
+        let options = ask(&mut Cursor::new("maybe\nnone\n"), defaults.clone());
+        assert!(!options.all_rules);
+        assert!(options.ignore.is_empty());
+        assert_eq!(ask(&mut Cursor::new(""), defaults.clone()), defaults);
+    }
+}
@@ -1,44 +1,166 @@
+mod baseline;
+mod cache;
 mod cli;
+mod color;
 mod files;
+mod fingerprint;
+mod git;
 mod infer;
+mod init;
+mod output;
 mod panic_hook;
+mod progress;
+mod report;
+mod server;
+mod watch;
 
 pub use self::{
-    cli::{show_all_rules, ExplanationRunner},
+    baseline::{Baseline, BaselineEntry},
+    cache::{Manifest, DEFAULT_CACHE_LOCATION},
+    cli::{print_config, show_all_rules, ExplanationRunner},
+    color::{color_enabled, set_color, ColorMode},
     files::*,
+    fingerprint::fingerprint,
     infer::infer,
+    init::init,
+    output::Output,
     panic_hook::*,
+    report::{
+        diagnostics_by_file, emit_compact, emit_grouped, emit_json, emit_json_summary, emit_sarif,
+        fix_diff, group_diagnostics, normalize_separators, DiagnosticGroup, NormalizedNames,
+    },
+    watch::watch,
 };
 pub use rslint_config as config;
-pub use rslint_core::Outcome;
+pub use rslint_core::{autofix::FixType, Outcome};
 pub use rslint_errors::{
     file, file::Files, Diagnostic, Emitter, Formatter, LongFormatter, Severity, ShortFormatter,
 };
 
 use colored::*;
-use rslint_core::{autofix::recursively_apply_fixes, File};
-use rslint_core::{lint_file, util::find_best_match_for_name, LintResult, RuleLevel};
+use output::Stream;
+use progress::Progress;
+use rslint_core::{autofix::recursively_apply_fixes_of_types, File};
+use rslint_core::{
+    get_rule_by_name, get_rule_suggestion, lint_file, plugin::WasmRule,
+    util::find_best_match_for_name, CstRule, CstRuleStore, LintResult, Rule, RuleLevel,
+    RuleRegistry,
+};
+use rslint_errors::file::FileId;
 use rslint_lexer::Lexer;
-#[allow(unused_imports)]
-use std::process;
-use std::{fs::write, path::PathBuf};
+use rslint_parser::FileKind;
+use std::{
+    collections::HashMap,
+    fs::write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    sync::Arc,
+    time::Duration,
+};
 use yastl::Pool;
 
+/// The names of the formatters which can be used for emitting diagnostics.
+const FORMATTERS: [&str; 5] = ["short", "long", "compact", "json", "sarif"];
+
+/// The options of a [`run`], which are the flags of the command line.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// The glob patterns of the files to lint, or `@<file>` to lint the paths listed in a file.
+    pub globs: Vec<String>,
+    /// Include potentially spammy details in rule diagnostics.
+    pub verbose: bool,
+    /// Apply the fixes of the problems which can be fixed automatically.
+    pub fix: bool,
+    /// Apply fixes even if a file has syntax errors.
+    pub dirty: bool,
+    /// The name of the formatter of the diagnostics, the formatter of the config is used if this is `None`.
+    pub formatter: Option<String>,
+    /// Ignore the global config in the config directory of the user.
+    pub no_global_config: bool,
+    /// The number of threads used for linting, `0` uses one thread per CPU core.
+    pub num_threads: usize,
+    /// Ignore the ignore files, and lint the files in `node_modules`.
+    pub no_ignore: bool,
+    /// The name of the ignore file, `.rslintignore` if this is `None`.
+    pub ignore_file: Option<PathBuf>,
+    /// Also respect the ignore files of git.
+    pub use_gitignore: bool,
+    /// The extensions of the linted files, the default extensions are used if this is empty.
+    pub extensions: Vec<String>,
+    /// Follow symbolic links while walking directories.
+    pub follow_symlinks: bool,
+    /// Skip the files larger than this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Transcode the files which are not valid UTF-8 instead of skipping them.
+    pub transcode: bool,
+    /// Lint the source code read from stdin instead of the files.
+    pub stdin: bool,
+    /// The number of threads used for reading files, `0` uses `num_threads`.
+    pub io_threads: usize,
+    /// Only lint the files matching one of these globs.
+    pub include: Vec<String>,
+    /// Never lint the files matching one of these globs.
+    pub exclude: Vec<String>,
+    /// Print the files which would be linted instead of linting them.
+    pub list_files: bool,
+    /// The location of the cache, files which did not change since they last passed are skipped.
+    pub cache: Option<PathBuf>,
+    /// Also lint the files without an extension which start with a node shebang.
+    pub shebang_scripts: bool,
+    /// Only report errors, warnings are still counted in the summary but only fail the run if there
+    /// are more than `max_warnings`.
+    pub quiet: bool,
+    /// Fail the run if there are more warnings than this.
+    pub max_warnings: Option<usize>,
+    /// Print a diff of the fixes instead of writing them.
+    pub fix_dry_run: bool,
+    /// The path of the source code read with `stdin`.
+    pub stdin_filename: Option<PathBuf>,
+    /// Print the time spent in every rule.
+    pub timings: bool,
+    /// Only run these rules regardless of the config.
+    pub rules: Vec<String>,
+    /// Print every rule and message once with the number of their occurrences.
+    pub group_by_rule: bool,
+    /// Exit with [`EXIT_SUCCESS`] instead of [`EXIT_WARNINGS`] if there were only warnings.
+    pub pass_on_warnings: bool,
+    /// Keep running and lint files again when they change.
+    pub watch: bool,
+    /// Only report the diagnostics which are not in this baseline.
+    pub baseline: Option<PathBuf>,
+    /// Record the diagnostics to this baseline.
+    pub baseline_write: Option<PathBuf>,
+    /// Display file names with `/` separators on every platform.
+    pub normalize_paths: bool,
+    /// Override the severities of these rules regardless of the config.
+    pub severities: Vec<RuleSeverity>,
+    /// Don't warn about empty files.
+    pub allow_empty_files: bool,
+    /// Show the progress of linting on stderr if it is a terminal.
+    pub progress: bool,
+    /// Stop linting as soon as a file cannot be parsed.
+    pub bail: bool,
+    /// Write the diagnostics to this file instead of stdout or stderr.
+    pub output_file: Option<PathBuf>,
+    /// The paths of WebAssembly plugins whose rules run on every file.
+    pub plugins: Vec<PathBuf>,
+    /// Only apply the fixes of these types, every type if this is empty.
+    pub fix_types: Vec<FixType>,
+    /// Only lint the files which changed since the merge base of this git ref and `HEAD`.
+    pub changed_since: Option<String>,
+    /// Lint the sources sent to stdin as requests instead of the files, see [`server`].
+    pub server: bool,
+    /// Include the source lines of every diagnostic in the output of the json formatter.
+    pub json_snippets: bool,
+    /// Stop linting once more than this many problems were found.
+    pub max_problems: Option<usize>,
+}
+
+/// Lint the files matched by `options.globs` and print the results, returning the statistics of
+/// the run or `None` if nothing could be linted.
 #[allow(unused_must_use, unused_variables)]
-#[allow(clippy::too_many_arguments)] // this is temporary, and should be refactored soon
-pub fn run(
-    globs: Vec<String>,
-    verbose: bool,
-    fix: bool,
-    dirty: bool,
-    formatter: Option<String>,
-    no_global_config: bool,
-    num_threads: usize,
-    no_ignore: bool,
-    ignore_file: Option<PathBuf>,
-    use_gitignore: bool,
-) {
-    let exit_code = run_inner(
+pub fn run(options: RunOptions) -> Option<RunStats> {
+    let RunOptions {
         globs,
         verbose,
         fix,
@@ -49,96 +171,780 @@ pub fn run(
         no_ignore,
         ignore_file,
         use_gitignore,
-    );
-    #[cfg(not(debug_assertions))]
-    process::exit(exit_code);
-}
+        extensions,
+        follow_symlinks,
+        max_bytes,
+        transcode,
+        stdin,
+        io_threads,
+        include,
+        exclude,
+        list_files,
+        cache,
+        shebang_scripts,
+        quiet,
+        max_warnings,
+        fix_dry_run,
+        stdin_filename,
+        timings,
+        rules,
+        group_by_rule,
+        pass_on_warnings,
+        watch,
+        baseline,
+        baseline_write,
+        normalize_paths,
+        severities,
+        allow_empty_files,
+        progress,
+        bail,
+        output_file,
+        plugins,
+        fix_types,
+        changed_since,
+        server,
+        json_snippets,
+        max_problems,
+    } = options;
+    let num_threads = if num_threads == 0 {
+        num_cpus::get()
+    } else {
+        num_threads
+    };
+    let plugins = load_plugins(&plugins)?;
+    let named = rules
+        .iter()
+        .chain(severities.iter().map(|severity| &severity.rule));
+    if !verify_rules(&named.cloned().collect::<Vec<_>>(), &plugins) {
+        return None;
+    }
+    let baseline = match baseline.as_deref().map(|path| (path, Baseline::load(path))) {
+        Some((_, Ok(baseline))) => Some(baseline),
+        Some((path, Err(err))) => {
+            lint_err!("failed to load the baseline `{}`: {}", path.display(), err);
+            return None;
+        }
+        None => None,
+    };
+    let output = match output_file.as_deref().map(Output::create) {
+        Some(Ok(output)) => output,
+        Some(Err(err)) => {
+            lint_err!(
+                "failed to create the output file `{}`: {}",
+                output_file.unwrap().display(),
+                err
+            );
+            return None;
+        }
+        None => Output::default(),
+    };
+    if !fix_types.is_empty() && !fix && !fix_dry_run {
+        lint_warn!("`--fix-type` has no effect without `--fix` or `--fix-dry-run`");
+    }
+    let fix_types = if fix_types.is_empty() {
+        FixType::ALL.to_vec()
+    } else {
+        fix_types
+    };
+    let mut resolver = config::ConfigResolver::new(no_global_config);
+    let config = resolver.resolve(Path::new(""));
+    let (mut store, warnings) = config.rules_store();
+    restrict_rules(&mut store, &rules);
+    add_plugins(&mut store, &plugins, &config);
+    override_severities(&mut store, &rules, &severities);
 
-/// The inner function for run to call destructors before we call [`process::exit`]
-#[allow(clippy::too_many_arguments)] // this is temporary, and should be refactored soon
-fn run_inner(
-    globs: Vec<String>,
-    verbose: bool,
-    fix: bool,
-    dirty: bool,
-    formatter: Option<String>,
-    no_global_config: bool,
-    num_threads: usize,
-    no_ignore: bool,
-    ignore_file: Option<PathBuf>,
-    use_gitignore: bool,
-) -> i32 {
-    let mut walker = FileWalker::empty();
-    walker.load_files_parallel(
-        collect_globs(globs).into_iter(),
-        num_threads,
-        no_ignore,
-        ignore_file,
-        use_gitignore,
-    );
+    if server {
+        if watch || stdin {
+            lint_warn!("`--watch` and `--stdin` are ignored by `--server`");
+        }
+        return serve(
+            config,
+            store,
+            warnings,
+            resolver,
+            &rules,
+            &severities,
+            &plugins,
+            verbose,
+        );
+    }
+    if watch && stdin {
+        lint_warn!("`--watch` is ignored when linting stdin");
+    }
+    let roots = if stdin { vec![] } else { collect_globs(globs) };
 
-    let config = match config::Config::new(no_global_config) {
-        Ok(cfg) => cfg,
-        Err((file, d)) => {
-            emit_diagnostic(&d, &file);
-            config::Config::default()
+    // the results of unchanged files only stay the same if the rules and their options are the same
+    let manifest = cache.filter(|_| !stdin).map(|location| {
+        let fingerprint = cache::hash_source(format!("{:?}{:?}", store, baseline));
+        Manifest::load(location, fingerprint)
+    });
+    let mut walk = FileWalker::builder()
+        .follow_symlinks(follow_symlinks)
+        .max_bytes(max_bytes)
+        .transcode(transcode)
+        .shebang_scripts(shebang_scripts)
+        .normalize_paths(normalize_paths)
+        .num_threads(num_threads)
+        .io_threads(io_threads)
+        .include(include)
+        .exclude(exclude)
+        .cache(manifest)
+        .no_ignore(no_ignore)
+        .ignore_file(ignore_file)
+        .use_gitignore(use_gitignore);
+    if !extensions.is_empty() {
+        walk = walk.extensions(extensions);
+    }
+    // only the changed files inside of the linted paths are loaded, as if they were listed with `@`
+    let roots = match changed_since.as_deref().filter(|_| !stdin) {
+        Some(reference) => match git::changed_files(reference) {
+            Ok(changed) => {
+                let roots = walk.clone().build().filter_paths(changed, &roots);
+                if roots.is_empty() {
+                    lint_note!("no linted files changed since `{}`", reference);
+                    return Some(RunStats::default());
+                }
+                roots
+            }
+            Err(err) => {
+                lint_err!(
+                    "failed to get the files changed since `{}`: {}",
+                    reference,
+                    err
+                );
+                return None;
+            }
+        },
+        None => roots,
+    };
+
+    let mut walker = if stdin {
+        let walker = match stdin_filename {
+            Some(path) => FileWalker::from_stdin_with_path(path),
+            None => FileWalker::from_stdin("<stdin>".to_string(), FileKind::Script),
+        };
+        match walker {
+            Ok(walker) => walker.normalize_paths(normalize_paths),
+            Err(err) => {
+                lint_err!("failed to read from stdin: {}", err);
+                return None;
+            }
         }
+    } else {
+        walk.clone().build_from(roots.clone())
     };
 
+    if list_files {
+        list_walked_files(&walker);
+        return Some(RunStats {
+            files: walker.files.len(),
+            ..RunStats::default()
+        });
+    }
+
+    // files in directories with their own config files use the merged config of their directory,
+    // every other file shares the config of the working directory
+    let mut stores = vec![(config.clone(), store, warnings)];
+    let mut file_stores = HashMap::new();
+    for file in walker.files.values() {
+        let idx = store_index(
+            &mut stores,
+            &mut resolver,
+            &rules,
+            &severities,
+            &plugins,
+            file,
+        );
+        file_stores.insert(file.id, idx);
+    }
+    for (file, d) in &resolver.errors {
+        emit_diagnostic(d, file);
+    }
+
     let mut formatter = formatter.unwrap_or_else(|| config.formatter());
-    let (store, warnings) = config.rules_store();
-    emit_diagnostics("long", &warnings, &walker);
+    // config warnings are not part of the report, so they are never written to the output file
+    for diagnostic in stores.iter().flat_map(|(_, _, warnings)| warnings) {
+        emit_diagnostic(diagnostic, &walker);
+    }
 
     verify_formatter(&mut formatter);
 
-    if walker.files.is_empty() {
+    if walker.files.is_empty() && walker.cached == 0 {
         lint_err!("No matching files found");
-        return 2;
+        return None;
+    }
+    if walker.cached > 0 {
+        lint_note!(
+            "skipping {} unchanged files without problems, run without `--cache` to lint them",
+            walker.cached
+        );
+    }
+    // neither the JavaScript nor the TypeScript parser supports JSX
+    let jsx_files = walker
+        .files
+        .values()
+        .filter_map(|file| file.path.as_deref()?.extension())
+        .filter(|ext| ext.eq_ignore_ascii_case("jsx") || ext.eq_ignore_ascii_case("tsx"))
+        .count();
+    if jsx_files > 0 {
+        lint_warn!(
+            "JSX is not supported yet, {} `.jsx` or `.tsx` file{} will be parsed without JSX support and likely report syntax errors",
+            jsx_files,
+            if jsx_files == 1 { "" } else { "s" }
+        );
+    }
+    // empty files are usually truncated writes or broken code generators
+    if !allow_empty_files {
+        for file in walker.empty_files() {
+            let name = walker.name(file.id).unwrap_or_default();
+            lint_warn!(
+                "`{}` is empty or only contains whitespace, pass `--allow-empty-files` if this is intended",
+                name
+            );
+        }
+    }
+
+    // the files which did not start yet are only skipped if every diagnostic found is reported
+    let stop_after = max_problems.filter(|_| {
+        !quiet && !fix && !fix_dry_run && baseline.is_none() && baseline_write.is_none()
+    });
+    let mut results = lint_walker(
+        &mut walker,
+        num_threads,
+        |file_id| &stores[file_stores[&file_id]].1,
+        verbose,
+        progress,
+        bail,
+        stop_after,
+    );
+    if bail {
+        if let Some(stats) =
+            bail_on_parse_errors(&results, &walker, &formatter, json_snippets, &output)
+        {
+            return Some(stats);
+        }
     }
 
+    let fix_count = if fix || fix_dry_run {
+        apply_fixes(
+            &mut results,
+            &mut walker,
+            dirty,
+            fix_dry_run,
+            &fix_types,
+            &output,
+        )
+    } else {
+        0
+    };
+    // the diagnostics of a new baseline are suppressed right away, so the run passes
+    let baseline = match baseline_write {
+        Some(path) => {
+            let new = Baseline::new(&results, &walker);
+            match new.save(&path) {
+                Ok(()) => lint_note!(
+                    "wrote {} diagnostics to the baseline `{}`",
+                    new.diagnostics.len(),
+                    path.display()
+                ),
+                Err(err) => lint_err!("failed to write the baseline `{}`: {}", path.display(), err),
+            }
+            Some(new)
+        }
+        None => baseline,
+    };
+    if let Some(baseline) = &baseline {
+        suppress_baseline(baseline, &mut results, &walker);
+    }
+    let stats = RunStats {
+        max_warnings,
+        // the warnings of a quiet run are not reported, so they cannot fail it either
+        pass_on_warnings: pass_on_warnings || quiet,
+        ..print_results(
+            &mut results,
+            &walker,
+            |file_id, rule| rule_level(&stores[file_stores[&file_id]].0, &severities, rule),
+            fix_count,
+            &formatter,
+            json_snippets,
+            &output,
+            quiet,
+            group_by_rule,
+            max_problems,
+        )
+    };
+    report_truncation(&stats, max_problems, results.len() == walker.files.len());
+    report_skipped_rules(&results);
+    if timings {
+        print_timings(&results, &walker);
+    }
+    // the cache is only valid for the config it was made with, which is the config of the working directory
+    update_cache(&mut walker, &results, |file_id| file_stores[&file_id] == 0);
+    if stats.exceeds_max_warnings() {
+        lint_err!(
+            "found {} warnings, maximum allowed is {}",
+            stats.warnings,
+            max_warnings.unwrap()
+        );
+    }
+
+    if watch && !stdin {
+        let load = |walker: &mut FileWalker, root: &Path| {
+            walk.load_into(walker, vec![root.to_path_buf()]);
+        };
+        let relint = |walker: &mut FileWalker, ids: &[FileId]| {
+            for id in ids {
+                if !file_stores.contains_key(id) {
+                    let idx = store_index(
+                        &mut stores,
+                        &mut resolver,
+                        &rules,
+                        &severities,
+                        &plugins,
+                        &walker.files[id],
+                    );
+                    file_stores.insert(*id, idx);
+                }
+            }
+            lint_note!(
+                "re-linting {} changed file{}",
+                ids.len(),
+                if ids.len() == 1 { "" } else { "s" }
+            );
+            let mut results = ids
+                .iter()
+                .map(|id| lint_file(&walker.files[id], &stores[file_stores[id]].1, verbose))
+                .collect::<Vec<_>>();
+            let fix_count = if fix || fix_dry_run {
+                apply_fixes(
+                    &mut results,
+                    walker,
+                    dirty,
+                    fix_dry_run,
+                    &fix_types,
+                    &output,
+                )
+            } else {
+                0
+            };
+            if let Some(baseline) = &baseline {
+                suppress_baseline(baseline, &mut results, walker);
+            }
+            let stats = print_results(
+                &mut results,
+                walker,
+                |file_id, rule| rule_level(&stores[file_stores[&file_id]].0, &severities, rule),
+                fix_count,
+                &formatter,
+                json_snippets,
+                &output,
+                quiet,
+                group_by_rule,
+                max_problems,
+            );
+            report_truncation(&stats, max_problems, true);
+        };
+        watch::watch(&mut walker, &roots, load, relint);
+    }
+    Some(stats)
+}
+
+/// Lint the sources sent to stdin until it ends and write the diagnostics to stdout, see
+/// [`server`] for the protocol.
+///
+/// Every source uses the config of its directory like a linted file, the warnings and errors of
+/// configs are printed to stderr when they are first loaded.
+#[allow(clippy::too_many_arguments)]
+fn serve(
+    config: Arc<config::Config>,
+    store: CstRuleStore,
+    warnings: Vec<Diagnostic>,
+    mut resolver: config::ConfigResolver,
+    rules: &[String],
+    severities: &[RuleSeverity],
+    plugins: &[WasmRule],
+    verbose: bool,
+) -> Option<RunStats> {
+    for (file, d) in resolver.errors.drain(..) {
+        emit_diagnostic(&d, &file);
+    }
+    for diagnostic in &warnings {
+        emit_diagnostic(diagnostic, &FileWalker::default());
+    }
+    let mut stores = vec![(config, store, warnings)];
+    let lint = |walker: &FileWalker, file_id: FileId| {
+        let file = &walker.files[&file_id];
+        let loaded = stores.len();
+        let idx = store_index(&mut stores, &mut resolver, rules, severities, plugins, file);
+        for (file, d) in resolver.errors.drain(..) {
+            emit_diagnostic(&d, &file);
+        }
+        for diagnostic in stores[loaded..]
+            .iter()
+            .flat_map(|(_, _, warnings)| warnings)
+        {
+            emit_diagnostic(diagnostic, walker);
+        }
+
+        let (config, store, _) = &stores[idx];
+        let mut result = lint_file(file, store, verbose);
+        remap_result_to_levels(&mut result, |rule| rule_level(config, severities, rule));
+        result.diagnostics().cloned().collect()
+    };
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    match server::serve(stdin.lock(), stdout.lock(), lint) {
+        Ok(()) => Some(RunStats::default()),
+        Err(err) => {
+            lint_err!("failed to communicate with the client: {}", err);
+            None
+        }
+    }
+}
+
+/// Report that only the first `--max-problems` diagnostics were printed, `complete` is whether
+/// every file was linted, otherwise the number of problems found is a lower bound.
+fn report_truncation(stats: &RunStats, max_problems: Option<usize>, complete: bool) {
+    if let (true, Some(max)) = (stats.truncated, max_problems) {
+        lint_err!(
+            "truncated the output to the first {} problems, {}{} problems were found",
+            max,
+            stats.errors + stats.warnings,
+            if complete { "" } else { "+" }
+        );
+    }
+}
+
+/// Note the rules which were not run on some files because they do not support the kind of those
+/// files, such as the rules which only run on JavaScript on TypeScript files.
+fn report_skipped_rules(results: &[LintResult]) {
+    let mut skipped = results
+        .iter()
+        .flat_map(|result| result.skipped.iter().copied())
+        .collect::<Vec<_>>();
+    skipped.sort_unstable();
+    skipped.dedup();
+    if !skipped.is_empty() {
+        let names = skipped
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .collect::<Vec<_>>();
+        lint_note!(
+            "skipped {} on the files whose language they do not support",
+            names.join(", ")
+        );
+    }
+}
+
+/// Remove the diagnostics which are in the baseline from the results and note how many there were.
+fn suppress_baseline(baseline: &Baseline, results: &mut [LintResult], walker: &FileWalker) {
+    let suppressed = baseline.suppress(results, walker);
+    if suppressed > 0 {
+        lint_note!(
+            "suppressed {} diagnostic{} which are in the baseline",
+            suppressed,
+            if suppressed == 1 { "" } else { "s" }
+        );
+    }
+}
+
+/// Get the index of the rule store of a file in `stores`, resolving the config of its directory
+/// and adding a store for it if no other file uses the same config.
+fn store_index(
+    stores: &mut Vec<(Arc<config::Config>, CstRuleStore, Vec<Diagnostic>)>,
+    resolver: &mut config::ConfigResolver,
+    rules: &[String],
+    severities: &[RuleSeverity],
+    plugins: &[WasmRule],
+    file: &File,
+) -> usize {
+    let path = file
+        .path
+        .as_deref()
+        .unwrap_or_else(|| Path::new(&file.name));
+    let file_config = resolver.resolve(path.parent().unwrap_or_else(|| Path::new("")));
+    match stores
+        .iter()
+        .position(|(other, ..)| Arc::ptr_eq(other, &file_config))
+    {
+        Some(idx) => idx,
+        None => {
+            let (mut store, warnings) = file_config.rules_store();
+            restrict_rules(&mut store, rules);
+            add_plugins(&mut store, plugins, &file_config);
+            override_severities(&mut store, rules, severities);
+            stores.push((file_config, store, warnings));
+            stores.len() - 1
+        }
+    }
+}
+
+/// Lint every file of the walker with the rules of `config` without printing anything, returning
+/// the diagnostics of each file in the order of [`FileWalker::files_sorted`].
+///
+/// The diagnostics are mapped to the level configured for their rule. Warnings about the config
+/// itself, such as unknown rule groups, are not included, see [`config::Config::rules_store`].
+/// The cached syntax trees of the files are dropped after they are linted.
+pub fn lint_files(
+    walker: &mut FileWalker,
+    config: &config::Config,
+) -> Vec<(FileId, Vec<Diagnostic>)> {
+    let (store, _) = config.rules_store();
+    lint_walker(
+        walker,
+        num_cpus::get(),
+        |_| &store,
+        false,
+        false,
+        false,
+        None,
+    )
+    .into_iter()
+    .map(|mut result| {
+        remap_result_to_levels(&mut result, |rule| config.rule_level_by_name(rule));
+        (result.file_id, result.diagnostics().cloned().collect())
+    })
+    .collect()
+}
+
+/// Lint a single string of source code parsed as `kind` with the rules of `config`, returning
+/// its diagnostics sorted like the diagnostics of [`lint_files`].
+///
+/// The spans of the diagnostics are byte ranges of `source`, and their file id refers to a
+/// virtual file which is dropped once this returns.
+pub fn lint_str(source: &str, kind: FileKind, config: &config::Config) -> Vec<Diagnostic> {
+    let mut walker = FileWalker::empty();
+    walker.add_virtual(source.to_string(), "<string>".to_string(), kind);
+    lint_files(&mut walker, config)
+        .into_iter()
+        .flat_map(|(_, diagnostics)| diagnostics)
+        .collect()
+}
+
+/// Lint every file of the walker on a pool of `num_threads` threads with the store of each file,
+/// returning the results in the order of [`FileWalker::files_sorted`].
+///
+/// Idle workers take the next file from a shared queue, which starts with the largest files so
+/// a single huge file does not finish long after every other one. The syntax tree of every file
+/// is released as soon as it is linted, so the trees of all files are never in memory at once.
+fn lint_walker<'s>(
+    walker: &mut FileWalker,
+    num_threads: usize,
+    store_of: impl Fn(FileId) -> &'s CstRuleStore,
+    verbose: bool,
+    progress: bool,
+    bail: bool,
+    max_problems: Option<usize>,
+) -> Vec<LintResult<'s>> {
     let pool = Pool::with_config(
         num_threads,
         yastl::ThreadConfig::new().prefix("rslint-worker"),
     );
 
+    let mut files = walker.files.values_mut().collect::<Vec<_>>();
+    files.sort_by_key(|file| std::cmp::Reverse(file.byte_len()));
+    let progress = if progress {
+        Some(Progress::new(files.len()))
+    } else {
+        None
+    };
+    let progress = progress.as_ref();
+    let failed = &AtomicBool::new(false);
+    let found = &AtomicUsize::new(0);
     let (tx, rx) = std::sync::mpsc::channel();
     pool.scoped(|scope| {
-        let store = &store;
-
-        for file in walker.files.values() {
+        for file in files {
             let tx = tx.clone();
+            let store = store_of(file.id);
             scope.recurse(move |_scope| {
-                tx.send(lint_file(file, store, verbose)).unwrap();
+                // skip the files which did not start yet once a file failed to parse, or once
+                // more problems than the maximum were found
+                let too_many = max_problems.is_some_and(|max| found.load(Ordering::Relaxed) > max);
+                if failed.load(Ordering::Relaxed) || too_many {
+                    return;
+                }
+                let mut result = lint_file(file, store, verbose);
+                if bail && has_syntax_errors(&result) {
+                    failed.store(true, Ordering::Relaxed);
+                }
+                found.fetch_add(result.diagnostics().count(), Ordering::Relaxed);
+                result.release_tree();
+                file.clear_parse();
+                tx.send(result).unwrap();
+                if let Some(progress) = progress {
+                    progress.tick();
+                }
             });
         }
     });
     drop(tx);
+    if let Some(progress) = progress {
+        progress.finish();
+    }
     let mut results = rx.into_iter().collect::<Vec<_>>();
+    // results arrive in whatever order the workers finish, report them in a stable order
+    let order = walker
+        .files_sorted()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, file)| (file.id, idx))
+        .collect::<HashMap<_, _>>();
+    results.sort_by_key(|res| order.get(&res.file_id).copied());
+    results
+}
+
+/// Whether the parser reported any error for the file of a result.
+fn has_syntax_errors(result: &LintResult) -> bool {
+    result
+        .parser_diagnostics
+        .iter()
+        .any(|d| matches!(d.severity, Severity::Bug | Severity::Error))
+}
 
-    let fix_count = if fix {
-        apply_fixes(&mut results, &mut walker, dirty)
+/// Report the syntax errors of the first file which could not be parsed for `--bail`, and return
+/// the stats of the failed run. Nothing is reported if every file was parsed without errors.
+fn bail_on_parse_errors(
+    results: &[LintResult],
+    walker: &FileWalker,
+    formatter: &str,
+    json_snippets: bool,
+    output: &Output,
+) -> Option<RunStats> {
+    let failed = results.iter().find(|res| has_syntax_errors(res))?;
+    let normalized = if walker.normalize_paths {
+        Some(NormalizedNames::new(walker, &failed.parser_diagnostics))
     } else {
-        0
+        None
     };
-    print_results(&mut results, &walker, &config, fix_count, &formatter);
+    let files = normalized
+        .as_ref()
+        .map_or(walker as &dyn Files, |names| names);
+    emit_diagnostics(
+        formatter,
+        &failed.parser_diagnostics,
+        files,
+        json_snippets,
+        output,
+    );
 
-    // print_results remaps the result to the appropriate severity
-    // so these diagnostic severities should be accurate
-    if results
+    let name = files.name(failed.file_id).unwrap_or_default();
+    let location = failed
+        .parser_diagnostics
         .iter()
-        .flat_map(|res| res.diagnostics())
-        .any(|d| matches!(d.severity, Severity::Bug | Severity::Error))
-    {
-        1
-    } else {
-        0
+        .filter_map(|d| d.primary.as_ref())
+        .find_map(|primary| report::line_col(files, failed.file_id, primary.span.range.start));
+    match location {
+        Some((line, column)) => lint_err!(
+            "stopping because `{}:{}:{}` could not be parsed",
+            name,
+            line,
+            column
+        ),
+        None => lint_err!("stopping because `{}` could not be parsed", name),
     }
+    Some(RunStats {
+        errors: failed
+            .parser_diagnostics
+            .iter()
+            .filter(|d| matches!(d.severity, Severity::Bug | Severity::Error))
+            .count(),
+        files: results.len(),
+        ..RunStats::default()
+    })
 }
 
-pub fn apply_fixes(results: &mut Vec<LintResult>, walker: &mut FileWalker, dirty: bool) -> usize {
-    let mut fix_count = 0;
+/// The time spent in a rule across all files, along with how many files and bytes of source code
+/// it linted, which tells rules which are slow on huge files from rules which are slow per byte.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct RuleTiming {
+    time: Duration,
+    files: usize,
+    bytes: usize,
+}
+
+/// Sum the timings of every rule across all files, from the slowest to the fastest rule.
+fn rule_timings(results: &[LintResult], walker: &FileWalker) -> Vec<(&'static str, RuleTiming)> {
+    let mut totals = HashMap::<&str, RuleTiming>::new();
+    for result in results {
+        let bytes = walker
+            .files
+            .get(&result.file_id)
+            .map_or(0, |file| file.byte_len());
+        for (rule, time) in &result.timings {
+            let total = totals.entry(rule).or_default();
+            total.time += *time;
+            total.files += 1;
+            total.bytes += bytes;
+        }
+    }
+    let mut totals = totals.into_iter().collect::<Vec<_>>();
+    totals.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(a.0.cmp(b.0)));
+    totals
+}
+
+/// Print the total time spent in every rule across all files, from the slowest to the fastest rule.
+fn print_timings(results: &[LintResult], walker: &FileWalker) {
+    let totals = rule_timings(results, walker);
+    let width = totals
+        .iter()
+        .map(|(rule, _)| rule.len())
+        .max()
+        .unwrap_or_default()
+        .max("Rule".len());
+    println!(
+        "\n{:width$} | {:>10} | {:>5} | {:>10}",
+        "Rule",
+        "Time (ms)",
+        "Files",
+        "Bytes",
+        width = width
+    );
+    println!(
+        "{:-<width$}-+-{:-<10}-+-{:-<5}-+-{:-<10}",
+        "",
+        "",
+        "",
+        "",
+        width = width
+    );
+    for (rule, timing) in totals {
+        println!(
+            "{:width$} | {:>10.3} | {:>5} | {:>10}",
+            rule,
+            timing.time.as_secs_f64() * 1000.0,
+            timing.files,
+            timing.bytes,
+            width = width
+        );
+    }
+}
+
+/// Apply the fixes of every result and write them to the files, returning the number of fixed issues.
+///
+/// With `dry_run` a diff of the fixes is written to `output` instead, and the files and results are
+/// left untouched.
+/// Only the fixes whose type is in `fix_types` are applied.
+///
+/// The files are only written once the fixes of every file were applied in memory. If any file
+/// cannot be written, the files which were already written are restored and the walker is rolled
+/// back with [`FileWalker::restore`], so either every fix is applied or none are.
+///
+/// The fixes of a file which had no syntax errors are discarded if the fixed file has any, warning
+/// about the rules whose fixes likely caused them.
+pub fn apply_fixes(
+    results: &mut Vec<LintResult>,
+    walker: &mut FileWalker,
+    dirty: bool,
+    dry_run: bool,
+    fix_types: &[FixType],
+    output: &Output,
+) -> usize {
+    // every fix is applied in memory before any file is written, so a file which cannot be written
+    // rolls back all of them instead of leaving some files fixed and others not
+    let snapshot = walker.snapshot();
+    let mut fixed_files = vec![];
     // TODO: should we aquire a file lock if we know we need to run autofix?
-    for res in results {
+    for (idx, res) in results.iter().enumerate() {
         let file = walker.files.get_mut(&res.file_id).unwrap();
         // skip virtual files
         if file.path.is_none() {
@@ -162,21 +968,114 @@ pub fn apply_fixes(results: &mut Vec<LintResult>, walker: &mut FileWalker, dirty
             .filter(|(_, x)| x.outcome() == Outcome::Warning || x.outcome() == Outcome::Failure)
             .map(|(_, res)| res.diagnostics.len())
             .sum::<usize>();
-        let fixed = recursively_apply_fixes(res, file);
-        let new_problem_num = res
+        let mut fixed_res = res.clone();
+        let fixed = recursively_apply_fixes_of_types(&mut fixed_res, file, fix_types);
+        let new_problem_num = fixed_res
             .rule_results
             .iter()
             .filter(|(_, x)| x.outcome() == Outcome::Warning || x.outcome() == Outcome::Failure)
             .map(|(_, res)| res.diagnostics.len())
             .sum::<usize>();
         let path = file.path.as_ref().unwrap();
-        if let Err(err) = write(path, fixed.clone()) {
-            lint_err!("failed to write to `{:#?}`: {}", path, err.to_string());
-        } else {
-            file.update_src(fixed);
-            fix_count += original_problem_num.saturating_sub(new_problem_num);
+        // fixes which still overlap with others after the last iteration are never applied
+        let unapplied = fixed_res
+            .rule_results
+            .values()
+            .filter(|res| {
+                matches!(&res.fixer, Some(fixer) if !fixer.indels.is_empty() && fix_types.contains(&fixer.fix_type))
+            })
+            .map(|res| res.diagnostics.len())
+            .sum::<usize>();
+        if unapplied > 0 {
+            lint_warn!(
+                "skipped {} fixes in `{}` because they overlap with other fixes",
+                unapplied,
+                path.display()
+            );
+        }
+        // don't touch files which did not change so their modification time stays the same
+        if fixed == file.source {
+            continue;
+        }
+        // a broken fix must never be written over a file which had no syntax errors
+        let mut fixed_file = file.clone();
+        fixed_file.update_src(fixed);
+        if file.syntax_errors().is_empty() && !fixed_file.parse_with_errors().0.is_empty() {
+            let rules = fixed_res
+                .breaking_fixes
+                .iter()
+                .map(|rule| format!("`{}`", rule))
+                .collect::<Vec<_>>();
+            if rules.is_empty() {
+                lint_warn!(
+                    "discarded the fixes of `{}` because they cause syntax errors",
+                    path.display()
+                );
+            } else {
+                lint_warn!(
+                    "discarded the fixes of `{}` because they cause syntax errors, likely because of the fix{} of {}",
+                    path.display(),
+                    if rules.len() == 1 { "" } else { "es" },
+                    rules.join(", ")
+                );
+            }
+            continue;
+        }
+        if dry_run {
+            let name = walker.name(res.file_id).unwrap_or_default();
+            let diff = fix_diff(name, &walker.files[&res.file_id].source, &fixed_file.source);
+            if let Err(err) =
+                output.with_writer(Stream::Stdout, |writer| write!(writer, "{}", diff))
+            {
+                lint_err!("failed to emit the diff of `{}`: {}", name, err);
+            }
+            continue;
+        }
+        let file_fix_count = original_problem_num.saturating_sub(new_problem_num);
+        *file = fixed_file;
+        fixed_files.push((idx, fixed_res, file_fix_count));
+    }
+
+    // the files as they were on disk, to undo the writes which succeeded if a later one fails
+    let mut written = vec![];
+    for (idx, _, _) in &fixed_files {
+        let file = &walker.files[&results[*idx].file_id];
+        let path = file.path.as_ref().unwrap();
+        let original = std::fs::read(path).and_then(|original| {
+            write(path, &file.source)?;
+            Ok(original)
+        });
+        match original {
+            Ok(original) => written.push((path.clone(), original)),
+            Err(err) => {
+                lint_err!(
+                    "failed to write to `{}`: {}, no fixes were applied",
+                    path.display(),
+                    err
+                );
+                for (path, original) in written {
+                    if let Err(err) = write(&path, original) {
+                        lint_err!("failed to restore `{}`: {}", path.display(), err);
+                    }
+                }
+                walker.restore(snapshot);
+                return 0;
+            }
         }
     }
+
+    let mut fix_count = 0;
+    for (idx, fixed_res, file_fix_count) in fixed_files {
+        let path = walker.files[&fixed_res.file_id].path.as_ref().unwrap();
+        lint_note!(
+            "fixed {} issue{} in `{}`",
+            file_fix_count,
+            if file_fix_count == 1 { "" } else { "s" },
+            path.display()
+        );
+        results[idx] = fixed_res;
+        fix_count += file_fix_count;
+    }
     fix_count
 }
 
@@ -271,43 +1170,166 @@ pub fn tokenize(globs: Vec<String>) {
     )
 }
 
+/// Read the paths listed in a file passed as `@<file>`, one per line. Blank lines and lines
+/// starting with `#` are skipped, relative paths are relative to the working directory.
+fn read_file_list(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Expand the glob patterns to the paths they match, `@<file>` arguments are expanded to the
+/// paths listed in the file without treating them as globs.
 fn collect_globs(globs: Vec<String>) -> Vec<PathBuf> {
     globs
         .into_iter()
-        .map(|pat| glob::glob(&pat))
-        .flat_map(|path| {
-            if let Err(err) = path {
-                lint_err!("Invalid glob pattern: {}", err);
-                None
-            } else {
-                path.ok()
-            }
+        .flat_map(|pat| match pat.strip_prefix('@') {
+            Some(list) => match read_file_list(Path::new(list)) {
+                Ok(paths) => paths
+                    .into_iter()
+                    .filter(|path| {
+                        let exists = path.exists();
+                        if !exists {
+                            lint_warn!("`{}` listed in `{}` does not exist", path.display(), list);
+                        }
+                        exists
+                    })
+                    .collect(),
+                Err(err) => {
+                    lint_err!("failed to read the file list `{}`: {}", list, err);
+                    vec![]
+                }
+            },
+            // globs are expanded while walking, see `FileWalker::load_files_parallel`
+            None => vec![PathBuf::from(pat)],
         })
-        .flat_map(|path| path.filter_map(Result::ok))
         .collect()
 }
 
 fn for_each_file(globs: Vec<String>, action: impl Fn(&FileWalker, &File)) {
     let walker = FileWalker::from_glob_parallel(collect_globs(globs), 1);
-    walker.files.values().for_each(|file| action(&walker, file))
+    walker
+        .files_sorted()
+        .into_iter()
+        .for_each(|file| action(&walker, file))
+}
+
+/// Record the files without any problems in the cache of the walker and save it, so they are skipped
+/// on the next run if they do not change.
+fn update_cache(
+    walker: &mut FileWalker,
+    results: &[LintResult],
+    cacheable: impl Fn(usize) -> bool,
+) {
+    let FileWalker { files, cache, .. } = walker;
+    if let Some(cache) = cache {
+        for result in results.iter().filter(|res| cacheable(res.file_id)) {
+            let file = &files[&result.file_id];
+            if let Some(path) = file.path.as_deref() {
+                let key = canonical_path(path);
+                if result.diagnostics().next().is_none() {
+                    cache.insert(key, &file.source);
+                } else {
+                    cache.remove(&key);
+                }
+            }
+        }
+        cache.save();
+    }
+}
+
+/// The exit code of a run without errors or warnings.
+pub const EXIT_SUCCESS: i32 = 0;
+/// The exit code of a run with errors, or with more warnings than `--max-warnings`.
+pub const EXIT_ERRORS: i32 = 1;
+/// The exit code of a run with warnings but no errors.
+pub const EXIT_WARNINGS: i32 = 2;
+/// The exit code of a run which could not lint anything, such as if no files matched.
+pub const EXIT_FAILURE: i32 = 3;
+
+/// The number of diagnostics produced by a lint run, counted after remapping them to the configured rule levels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunStats {
+    /// The number of error (and bug) diagnostics.
+    pub errors: usize,
+    /// The number of warning diagnostics.
+    pub warnings: usize,
+    /// The number of files which were linted.
+    pub files: usize,
+    /// The number of diagnostics from rules which can automatically fix them.
+    pub fixable: usize,
+    /// The number of issues which were fixed.
+    pub fixed: usize,
+    /// The number of warnings allowed before the run fails, any number is allowed if this is `None`.
+    pub max_warnings: Option<usize>,
+    /// Whether a run with only warnings exits with [`EXIT_SUCCESS`] instead of [`EXIT_WARNINGS`].
+    pub pass_on_warnings: bool,
+    /// Whether some diagnostics were not reported because there were more than `--max-problems`.
+    pub truncated: bool,
 }
 
+impl RunStats {
+    pub fn new(results: &[LintResult], fixed: usize) -> Self {
+        let mut stats = Self {
+            files: results.len(),
+            fixed,
+            ..Self::default()
+        };
+        for diagnostic in results.iter().flat_map(|res| res.diagnostics()) {
+            match diagnostic.severity {
+                Severity::Bug | Severity::Error => stats.errors += 1,
+                Severity::Warning => stats.warnings += 1,
+                _ => {}
+            }
+        }
+        stats.fixable = results
+            .iter()
+            .flat_map(|res| res.rule_results.values())
+            .filter(|res| res.fixer.is_some())
+            .map(|res| res.diagnostics.len())
+            .sum();
+        stats
+    }
+
+    /// Whether there were more warnings than [`RunStats::max_warnings`].
+    pub fn exceeds_max_warnings(&self) -> bool {
+        matches!(self.max_warnings, Some(max) if self.warnings > max)
+    }
+
+    /// The exit code of the process, which is [`EXIT_ERRORS`] if there were any errors, too many warnings,
+    /// or too many problems to report, [`EXIT_WARNINGS`] if there were only warnings (unless
+    /// [`RunStats::pass_on_warnings`]), and [`EXIT_SUCCESS`] otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.errors > 0 || self.exceeds_max_warnings() || self.truncated {
+            EXIT_ERRORS
+        } else if self.warnings > 0 && !self.pass_on_warnings {
+            EXIT_WARNINGS
+        } else {
+            EXIT_SUCCESS
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn print_results(
     results: &mut Vec<LintResult>,
     walker: &FileWalker,
-    config: &config::Config,
+    level_of: impl Fn(usize, &str) -> RuleLevel,
     fix_count: usize,
     formatter: &str,
-) {
+    json_snippets: bool,
+    output: &Output,
+    quiet: bool,
+    group_by_rule: bool,
+    max_problems: Option<usize>,
+) -> RunStats {
     // Map each diagnostic to the correct level according to configured rule level
     for result in results.iter_mut() {
-        for (rule_name, diagnostics) in result
-            .rule_results
-            .iter_mut()
-            .map(|x| (x.0, &mut x.1.diagnostics))
-        {
-            remap_diagnostics_to_level(diagnostics, config.rule_level_by_name(rule_name));
-        }
+        let file_id = result.file_id;
+        remap_result_to_levels(result, |rule| level_of(file_id, rule));
     }
 
     let failures = results
@@ -325,24 +1347,253 @@ pub(crate) fn print_results(
 
     let overall = Outcome::merge(results.iter().map(|res| res.outcome()));
 
-    for result in results.iter_mut() {
+    // quiet mode only renders errors, warnings are still counted in the stats
+    let shown = results
+        .iter()
+        .flat_map(|res| res.diagnostics())
+        .filter(|d| !quiet || matches!(d.severity, Severity::Bug | Severity::Error));
+    let mut diagnostics = diagnostics_by_file(shown, walker)
+        .into_iter()
+        .map(|(_, diagnostics)| diagnostics.into_iter().cloned().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let truncated = max_problems.is_some_and(|max| truncate_diagnostics(&mut diagnostics, max));
+    let normalized = walker
+        .normalize_paths
+        .then(|| NormalizedNames::new(walker, diagnostics.iter().flatten()));
+    let files = normalized
+        .as_ref()
+        .map_or(walker as &dyn Files, |names| names);
+    // sarif is a single document so it must be emitted all at once
+    if formatter == "sarif" {
         emit_diagnostics(
             formatter,
-            &result.diagnostics().cloned().collect::<Vec<_>>(),
-            walker,
+            &diagnostics.concat(),
+            files,
+            json_snippets,
+            output,
         );
+    } else if group_by_rule && formatter != "json" {
+        let diagnostics = diagnostics.concat();
+        let res = output.with_writer(Stream::Stderr, |writer| {
+            emit_grouped(&diagnostics, files, writer)
+        });
+        if let Err(err) = res {
+            lint_err!("failed to emit diagnostic: {}", err);
+        }
+    } else {
+        for diagnostics in &diagnostics {
+            emit_diagnostics(formatter, diagnostics, files, json_snippets, output);
+        }
     }
 
-    output_overall(failures, warnings, successes, fix_count);
-    if overall == Outcome::Failure {
-        println!("\nhelp: for more information about the errors try the explain command: `rslint explain <rules>`");
+    let stats = RunStats {
+        truncated,
+        ..RunStats::new(results, fix_count)
+    };
+    match formatter {
+        "json" => {
+            let res =
+                output.with_writer(Stream::Stdout, |writer| emit_json_summary(&stats, writer));
+            if let Err(err) = res {
+                lint_err!("failed to emit summary: {}", err);
+            }
+        }
+        // the summary would corrupt the sarif document
+        "sarif" => {}
+        // the summary is not part of the report, so it is not written to the output file or mixed
+        // with the lines of the compact formatter either
+        _ => {
+            let stream = if output.is_file() || formatter == "compact" {
+                Stream::Stderr
+            } else {
+                Stream::Stdout
+            };
+            output_overall(failures, warnings, successes, &stats, stream);
+            if overall == Outcome::Failure {
+                stream.println("\nhelp: for more information about the errors try the explain command: `rslint explain <rules>`");
+            }
+        }
+    }
+    stats
+}
+
+/// Only keep the first `max` diagnostics of the diagnostics grouped by file, returning whether any
+/// diagnostic was removed.
+fn truncate_diagnostics(diagnostics: &mut Vec<Vec<Diagnostic>>, max: usize) -> bool {
+    let mut left = max;
+    let mut truncated = false;
+    for file in diagnostics.iter_mut() {
+        truncated |= file.len() > left;
+        file.truncate(left);
+        left -= file.len();
+    }
+    diagnostics.retain(|file| !file.is_empty());
+    truncated
+}
+
+/// Check that every rule passed to `--rule` exists, reporting the ones which don't along with
+/// the available rules.
+fn verify_rules(rules: &[String], plugins: &[WasmRule]) -> bool {
+    let registry = RuleRegistry::builtins();
+    let unknown = rules
+        .iter()
+        .filter(|rule| {
+            registry.get(rule).is_none() && !plugins.iter().any(|plugin| plugin.name() == *rule)
+        })
+        .collect::<Vec<_>>();
+    for rule in &unknown {
+        if let Some(suggestion) = get_rule_suggestion(rule) {
+            lint_err!("unknown rule `{}`, did you mean `{}`?", rule, suggestion);
+        } else {
+            lint_err!("unknown rule `{}`", rule);
+        }
     }
+    if !unknown.is_empty() {
+        let available = registry
+            .all_rules()
+            .iter()
+            .map(|rule| rule.name)
+            .chain(plugins.iter().map(|plugin| plugin.name()))
+            .collect::<Vec<_>>();
+        lint_note!("available rules are: {}", available.join(", "));
+    }
+    unknown.is_empty()
+}
+
+/// Only keep the rules passed to `--rule` in a store regardless of the config, rules which are
+/// configured keep their options while the others are added with their default options.
+fn restrict_rules(store: &mut CstRuleStore, rules: &[String]) {
+    if rules.is_empty() {
+        return;
+    }
+    store.rules = rules
+        .iter()
+        .enumerate()
+        .filter(|(idx, rule)| !rules[..*idx].contains(rule))
+        .filter_map(|(_, rule)| store.get(rule).or_else(|| get_rule_by_name(rule)))
+        .collect();
+}
+
+/// The severity of a rule passed to `--rule-severity`, ex: `no-empty=warn`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSeverity {
+    pub rule: String,
+    /// The level of the rule, or `None` if it is turned off.
+    pub level: Option<RuleLevel>,
+}
+
+impl std::str::FromStr for RuleSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (rule, level) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected `<rule>=<severity>`, found `{}`", s))?;
+        let level = match level.trim() {
+            "error" => Some(RuleLevel::Error),
+            "warn" | "warning" => Some(RuleLevel::Warning),
+            "off" => None,
+            other => {
+                return Err(format!(
+                    "unknown severity `{}`, expected `error`, `warn`, or `off`",
+                    other
+                ))
+            }
+        };
+        Ok(Self {
+            rule: rule.trim().to_string(),
+            level,
+        })
+    }
+}
+
+/// The severity passed to `--rule-severity` for a rule, the last one wins if a rule is passed
+/// multiple times.
+fn severity_override<'a>(severities: &'a [RuleSeverity], rule: &str) -> Option<&'a RuleSeverity> {
+    severities
+        .iter()
+        .rev()
+        .find(|severity| severity.rule == rule)
+}
+
+/// Load the plugins passed to `--plugin`, reporting the ones which cannot be loaded.
+///
+/// The results of a file are keyed by the names of the rules, so plugins whose name is the name
+/// of a builtin rule or of another plugin are rejected.
+fn load_plugins(paths: &[PathBuf]) -> Option<Vec<WasmRule>> {
+    let registry = RuleRegistry::builtins();
+    let mut plugins: Vec<WasmRule> = vec![];
+    for path in paths {
+        let plugin = match WasmRule::load(path) {
+            Ok(plugin) => plugin,
+            Err(err) => {
+                lint_err!("failed to load the plugin `{}`: {}", path.display(), err);
+                return None;
+            }
+        };
+        let name = plugin.name();
+        if registry.get(name).is_some() {
+            lint_err!(
+                "the plugin `{}` has the same name as the builtin rule `{}`, rename the plugin file",
+                path.display(),
+                name
+            );
+            return None;
+        }
+        if let Some(other) = plugins.iter().find(|other| other.name() == name) {
+            lint_err!(
+                "the plugins `{}` and `{}` have the same name `{}`, rename one of the plugin files",
+                other.path().display(),
+                path.display(),
+                name
+            );
+            return None;
+        }
+        plugins.push(plugin);
+    }
+    Some(plugins)
+}
+
+/// Run the rules of plugins in a store regardless of `--rule`, unless they are allowed by `config`.
+fn add_plugins(store: &mut CstRuleStore, plugins: &[WasmRule], config: &config::Config) {
+    store.rules.extend(
+        plugins
+            .iter()
+            .filter(|plugin| !config.is_allowed(plugin.name()))
+            .map(|plugin| Box::new(plugin.clone()) as Box<dyn CstRule>),
+    );
+}
+
+/// Apply the severities passed to `--rule-severity` to a store regardless of the config, rules
+/// which are turned off are removed and other rules are added with their default options if they
+/// are not configured (unless `--rule` does not include them).
+fn override_severities(store: &mut CstRuleStore, rules: &[String], severities: &[RuleSeverity]) {
+    let last = severities
+        .iter()
+        .filter(|severity| severity_override(severities, &severity.rule) == Some(*severity));
+    for severity in last {
+        let restricted = !rules.is_empty() && !rules.contains(&severity.rule);
+        match severity.level {
+            None => store.rules.retain(|rule| rule.name() != severity.rule),
+            Some(_) if !restricted && store.get(&severity.rule).is_none() => {
+                store.rules.extend(get_rule_by_name(&severity.rule))
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// The level of a rule in a file with `config`, which is overridden by `--rule-severity`.
+fn rule_level(config: &config::Config, severities: &[RuleSeverity], rule: &str) -> RuleLevel {
+    severity_override(severities, rule)
+        .and_then(|severity| severity.level)
+        .unwrap_or_else(|| config.rule_level_by_name(rule))
 }
 
 pub fn verify_formatter(formatter: &mut String) {
-    if !matches!(formatter.as_str(), "short" | "long") {
+    if !FORMATTERS.contains(&formatter.as_str()) {
         if let Some(suggestion) =
-            find_best_match_for_name(vec!["short", "long"].into_iter(), formatter, None)
+            find_best_match_for_name(FORMATTERS.iter().copied(), formatter, None)
         {
             lint_err!(
                 "unknown formatter `{}`, using default formatter, did you mean `{}`?",
@@ -356,48 +1607,117 @@ pub fn verify_formatter(formatter: &mut String) {
     }
 }
 
-pub fn emit_diagnostics(formatter: &str, diagnostics: &[Diagnostic], files: &dyn Files) {
-    match formatter {
-        "short" => {
-            if let Err(err) = ShortFormatter.emit_stderr(diagnostics, files) {
-                lint_err!("failed to emit diagnostic: {}", err);
-            }
-        }
-        "long" => {
-            if let Err(err) = LongFormatter.emit_stderr(diagnostics, files) {
-                lint_err!("failed to emit diagnostic: {}", err);
-            }
-        }
+/// Emit the diagnostics with the formatter named `formatter` to `output`, `json_snippets` includes
+/// the source lines of every diagnostic in the output of the json formatter.
+pub fn emit_diagnostics(
+    formatter: &str,
+    diagnostics: &[Diagnostic],
+    files: &dyn Files,
+    json_snippets: bool,
+    output: &Output,
+) {
+    let res = match formatter {
+        "short" => output.with_writer(Stream::Stderr, |writer| {
+            ShortFormatter.emit_with_writer(diagnostics, files, writer)
+        }),
+        "long" => output.with_writer(Stream::Stderr, |writer| {
+            LongFormatter.emit_with_writer(diagnostics, files, writer)
+        }),
+        "compact" => output.with_writer(Stream::Stdout, |writer| {
+            emit_compact(diagnostics, files, writer)
+        }),
+        "json" => output.with_writer(Stream::Stdout, |writer| {
+            emit_json(diagnostics, files, json_snippets, writer)
+        }),
+        "sarif" => output.with_writer(Stream::Stdout, |writer| {
+            emit_sarif(diagnostics, files, writer)
+        }),
         f => {
-            if let Some(suggestion) =
-                find_best_match_for_name(vec!["short", "long"].into_iter(), f, None)
+            if let Some(suggestion) = find_best_match_for_name(FORMATTERS.iter().copied(), f, None)
             {
                 lint_err!("unknown formatter `{}`, did you mean `{}`?", f, suggestion);
             } else {
                 lint_err!("unknown formatter `{}`", f);
             }
+            return;
         }
+    };
+    if let Err(err) = res {
+        lint_err!("failed to emit diagnostic: {}", err);
     }
 }
 
-#[allow(unused_must_use)]
-fn output_overall(failures: usize, warnings: usize, successes: usize, fix_count: usize) {
+/// Print every file which would be linted along with a summary of them, without linting anything.
+fn list_walked_files(walker: &FileWalker) {
+    for file in walker.files_sorted() {
+        println!(
+            "{} ({:?}, {} bytes, {} lines)",
+            walker.name(file.id).unwrap_or_default(),
+            file.kind,
+            file.byte_len(),
+            file.line_count()
+        );
+    }
+
+    let summary = walker.summarize();
     println!(
+        "\n{}: {} files ({} scripts, {} modules, {} typescript), {} bytes, {} lines",
+        "Files".white(),
+        summary.files,
+        summary.scripts,
+        summary.modules,
+        summary.typescript,
+        summary.bytes,
+        summary.lines
+    );
+}
+
+/// Print the outcome of a run and the number of problems found to `stream`.
+fn output_overall(
+    failures: usize,
+    warnings: usize,
+    successes: usize,
+    stats: &RunStats,
+    stream: Stream,
+) {
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    stream.println(&format!(
         "{}: {} fail, {} warn, {} success{}",
         "Outcome".white(),
         failures.to_string().red(),
         warnings.to_string().yellow(),
         successes.to_string().green(),
-        if fix_count > 0 {
+        if stats.fixed > 0 {
             format!(
                 ", {} issue{} fixed",
-                fix_count.to_string().green(),
-                if fix_count == 1 { "" } else { "s" }
+                stats.fixed.to_string().green(),
+                plural(stats.fixed)
             )
         } else {
             "".to_string()
         }
-    );
+    ));
+    stream.println(&format!(
+        "{} error{}, {} warning{} across {} file{}{}",
+        stats.errors.to_string().red(),
+        plural(stats.errors),
+        stats.warnings.to_string().yellow(),
+        plural(stats.warnings),
+        stats.files,
+        plural(stats.files),
+        if stats.fixable > 0 {
+            format!(" ({} fixable with `--fix`)", stats.fixable)
+        } else {
+            "".to_string()
+        }
+    ));
+}
+
+/// Map each diagnostic of a result to the level configured for its rule.
+fn remap_result_to_levels(result: &mut LintResult, level_of: impl Fn(&str) -> RuleLevel) {
+    for (rule_name, rule_result) in result.rule_results.iter_mut() {
+        remap_diagnostics_to_level(&mut rule_result.diagnostics, level_of(rule_name));
+    }
 }
 
 /// Remap each error diagnostic to a warning diagnostic based on the rule's level.
@@ -417,7 +1737,7 @@ pub fn remap_diagnostics_to_level(diagnostics: &mut Vec<Diagnostic>, level: Rule
 pub fn emit_diagnostic(diagnostic: &Diagnostic, walker: &dyn file::Files) {
     let mut emitter = Emitter::new(walker);
     emitter
-        .emit_stderr(diagnostic, true)
+        .emit_stderr(diagnostic, color_enabled())
         .expect("failed to throw linter diagnostic")
 }
 
@@ -431,7 +1751,7 @@ macro_rules! lint_diagnostic {
     let file = rslint_errors::file::SimpleFile::new("".into(), "".into());
     let mut emitter = Emitter::new(&file);
     emitter
-        .emit_stderr(&diag, true)
+        .emit_stderr(&diag, $crate::color_enabled())
         .expect("failed to throw linter diagnostic")
     }
 }
@@ -459,3 +1779,373 @@ macro_rules! lint_note {
         $crate::lint_diagnostic!(note, $($format_args)*);
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_files_groups_diagnostics_by_file() {
+        let mut walker = FileWalker::empty();
+        let bad = walker.add_virtual(
+            "if (a) {}".to_string(),
+            "a.js".to_string(),
+            FileKind::Script,
+        );
+        let good = walker.add_virtual("let b;".to_string(), "b.js".to_string(), FileKind::Script);
+
+        let results = lint_files(&mut walker, &config::Config::default());
+        let codes = results
+            .iter()
+            .map(|(id, diagnostics)| {
+                let codes = diagnostics.iter().map(|d| d.code.clone().unwrap());
+                (*id, codes.collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(codes, [(bad, vec!["no-empty".to_string()]), (good, vec![])]);
+    }
+
+    #[test]
+    fn sum_rule_timings() {
+        let mut walker = FileWalker::empty();
+        let store = CstRuleStore::new().builtins();
+        let ids = [
+            walker.add_virtual("let a;".to_string(), "a.js".to_string(), FileKind::Script),
+            walker.add_virtual(
+                "let b = 1;".to_string(),
+                "b.js".to_string(),
+                FileKind::Script,
+            ),
+        ];
+        let results = ids
+            .iter()
+            .map(|id| rslint_core::lint_file(&walker.files[id], &store, false))
+            .collect::<Vec<_>>();
+
+        let timings = rule_timings(&results, &walker);
+        assert_eq!(timings.len(), store.rules.len());
+        for (_, timing) in timings {
+            assert_eq!(timing.files, 2);
+            assert_eq!(timing.bytes, 16);
+        }
+    }
+
+    #[test]
+    fn lint_a_string() {
+        let config = config::Config::default();
+        let diagnostics = lint_str("let a;\nif (a) {}", FileKind::Script, &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("no-empty"));
+        assert_eq!(diagnostics[0].primary.as_ref().unwrap().span.range, 14..16);
+
+        let module = lint_str("import a from 'a';", FileKind::Module, &config);
+        let script = lint_str("import a from 'a';", FileKind::Script, &config);
+        assert!(module.is_empty());
+        assert!(!script.is_empty());
+    }
+
+    #[test]
+    fn quiet_runs_pass_on_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.js");
+        write(&path, "if (a) {}").unwrap();
+        let options = RunOptions {
+            globs: vec![path.to_string_lossy().into_owned()],
+            no_global_config: true,
+            num_threads: 1,
+            rules: vec!["no-empty".to_string()],
+            severities: vec!["no-empty=warn".parse().unwrap()],
+            ..RunOptions::default()
+        };
+
+        let loud = run(options.clone());
+        let quiet = run(RunOptions {
+            quiet: true,
+            ..options.clone()
+        });
+        let too_many = run(RunOptions {
+            quiet: true,
+            max_warnings: Some(0),
+            ..options
+        });
+        assert_eq!(loud.unwrap().exit_code(), EXIT_WARNINGS);
+        let quiet = quiet.unwrap();
+        assert_eq!(quiet.warnings, 1);
+        assert_eq!(quiet.exit_code(), EXIT_SUCCESS);
+        assert_eq!(too_many.unwrap().exit_code(), EXIT_ERRORS);
+    }
+
+    #[test]
+    fn exit_codes() {
+        let stats = |errors, warnings| RunStats {
+            errors,
+            warnings,
+            ..RunStats::default()
+        };
+        assert_eq!(stats(0, 0).exit_code(), EXIT_SUCCESS);
+        assert_eq!(stats(1, 3).exit_code(), EXIT_ERRORS);
+        assert_eq!(stats(0, 3).exit_code(), EXIT_WARNINGS);
+        let passing = RunStats {
+            pass_on_warnings: true,
+            ..stats(0, 3)
+        };
+        assert_eq!(passing.exit_code(), EXIT_SUCCESS);
+        let too_many = RunStats {
+            max_warnings: Some(2),
+            ..passing
+        };
+        assert_eq!(too_many.exit_code(), EXIT_ERRORS);
+        let truncated = RunStats {
+            truncated: true,
+            ..passing
+        };
+        assert_eq!(truncated.exit_code(), EXIT_ERRORS);
+    }
+
+    #[test]
+    fn truncate_to_max_problems() {
+        let file = |id, len| vec![Diagnostic::error(id, "", ""); len];
+        let mut diagnostics = vec![file(0, 2), file(1, 2), file(2, 1)];
+        assert!(truncate_diagnostics(&mut diagnostics, 3));
+        assert_eq!(diagnostics.iter().map(Vec::len).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!(diagnostics[1][0].file_id, 1);
+
+        assert!(!truncate_diagnostics(&mut diagnostics, 3));
+        assert!(truncate_diagnostics(&mut diagnostics, 0));
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn restrict_rules_to_named_rules() {
+        let mut store = CstRuleStore::new().recommended();
+        let rules = ["no-extra-semi", "simplify-regex", "no-extra-semi"].map(String::from);
+        restrict_rules(&mut store, &rules);
+        let names = store
+            .rules
+            .iter()
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["no-extra-semi", "simplify-regex"]);
+        assert!(verify_rules(&rules, &[]));
+        assert!(!verify_rules(&["no-extra-semis".to_string()], &[]));
+    }
+
+    #[test]
+    fn directives_name_rules_filtered_by_rule() {
+        let mut store = CstRuleStore::new().recommended();
+        restrict_rules(&mut store, &["no-empty".to_string()]);
+        let file = File::from_string(
+            "// rslint-ignore-next-line no-debugger\ndebugger;\nif (a) {}",
+            FileKind::Script,
+            "a.js",
+        );
+        let result = lint_file(&file, &store, false);
+
+        let invalid = result
+            .directive_diagnostics
+            .iter()
+            .filter(|d| d.diagnostic.title.starts_with("invalid rule"))
+            .count();
+        assert_eq!(invalid, 0);
+        assert_eq!(result.rule_results["no-empty"].diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn reject_plugins_with_taken_names() {
+        let example =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../rslint_core/plugins/no-tabs.wat");
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("other")).unwrap();
+        for name in ["no-empty.wat", "no-tabs.wat", "other/no-tabs.wat"].iter() {
+            std::fs::copy(&example, dir.join(name)).unwrap();
+        }
+
+        let builtin = load_plugins(&[dir.join("no-empty.wat")]);
+        let twice = load_plugins(&[dir.join("no-tabs.wat"), dir.join("other/no-tabs.wat")]);
+        let plugins = load_plugins(&[dir.join("no-tabs.wat")]);
+        assert!(builtin.is_none());
+        assert!(twice.is_none());
+        let plugins = plugins.unwrap();
+        assert!(verify_rules(&["no-tabs".to_string()], &plugins));
+        assert!(!verify_rules(&["no-tabs".to_string()], &[]));
+
+        let mut store = CstRuleStore::new().recommended();
+        add_plugins(&mut store, &plugins, &config::Config::default());
+        let severities = ["no-tabs=off".parse().unwrap()];
+        assert!(store.get("no-tabs").is_some());
+        override_severities(&mut store, &[], &severities);
+        assert!(store.get("no-tabs").is_none());
+    }
+
+    #[test]
+    fn override_rule_severities() {
+        let severities = ["no-empty=off", "getter-return = warn", "no-debugger=error"]
+            .iter()
+            .map(|s| s.parse::<RuleSeverity>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(severities[1].rule, "getter-return");
+        assert_eq!(severities[1].level, Some(RuleLevel::Warning));
+        assert!("no-empty".parse::<RuleSeverity>().is_err());
+        assert!("no-empty=fatal".parse::<RuleSeverity>().is_err());
+
+        let recommended = CstRuleStore::new().recommended();
+        let mut store = recommended.clone();
+        store.rules.retain(|rule| rule.name() != "no-debugger");
+        override_severities(&mut store, &[], &severities);
+        let names = store
+            .rules
+            .iter()
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+        assert!(!names.contains(&"no-empty"));
+        assert!(names.contains(&"no-debugger"));
+        assert!(recommended.get("no-empty").is_some());
+        assert_eq!(names.len(), recommended.rules.len() - 1);
+
+        // rules not passed to `--rule` are not added
+        let mut store = CstRuleStore::new();
+        override_severities(&mut store, &["no-empty".to_string()], &severities);
+        assert!(store.rules.is_empty());
+
+        let severities = ["no-empty=off", "no-empty=error"].map(|s| s.parse().unwrap());
+        let mut store = CstRuleStore::new();
+        override_severities(&mut store, &[], &severities);
+        assert_eq!(store.rules[0].name(), "no-empty");
+    }
+
+    #[test]
+    fn expand_file_lists() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let file = dir.join("[a].js");
+        std::fs::write(&file, "").unwrap();
+        let list = dir.join("changed.txt");
+        let listed = format!(
+            "# changed files\n\n  {}\n{}\n",
+            file.display(),
+            dir.join("removed.js").display()
+        );
+        std::fs::write(&list, listed).unwrap();
+
+        let paths = collect_globs(vec![format!("@{}", list.display())]);
+        let missing = collect_globs(vec![format!("@{}", dir.join("missing.txt").display())]);
+
+        assert_eq!(paths, [file]);
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn roll_back_fixes_if_a_write_fails() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().to_path_buf();
+        let (a, b) = (dir.join("a.js"), dir.join("b.js"));
+        write(&a, "let a;;\n").unwrap();
+        write(&b, "let b;;\n").unwrap();
+
+        let store = CstRuleStore::new().builtins();
+        let mut walker = FileWalker::from_glob_parallel(vec![dir.clone()], 1);
+        let mut results = walker
+            .files_sorted()
+            .iter()
+            .map(|file| rslint_core::lint_file(file, &store, false))
+            .collect::<Vec<_>>();
+        let snapshot = walker.snapshot();
+
+        // `b.js` can no longer be written after `a.js` was
+        std::fs::remove_file(&b).unwrap();
+        std::fs::create_dir(&b).unwrap();
+        assert_eq!(
+            apply_fixes(
+                &mut results,
+                &mut walker,
+                false,
+                false,
+                &FixType::ALL,
+                &Output::default()
+            ),
+            0
+        );
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "let a;;\n");
+        assert_eq!(walker.snapshot(), snapshot);
+
+        std::fs::remove_dir(&b).unwrap();
+        write(&b, "let b;;\n").unwrap();
+        assert_eq!(
+            apply_fixes(
+                &mut results,
+                &mut walker,
+                false,
+                false,
+                &FixType::ALL,
+                &Output::default()
+            ),
+            2
+        );
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "let a;\n");
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), "let b;\n");
+    }
+
+    #[test]
+    fn discard_fixes_which_cause_syntax_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.js");
+        write(&path, "let a = 1;;").unwrap();
+
+        let store = CstRuleStore::new().builtins();
+        let mut walker = FileWalker::from_glob_parallel(vec![path.clone()], 1);
+        let file = walker.files.values().next().unwrap();
+        let mut results = vec![rslint_core::lint_file(file, &store, false)];
+        // break the fix by also inserting an unclosed paren
+        results[0]
+            .rule_results
+            .get_mut("no-extra-semi")
+            .and_then(|res| res.fixer.as_mut())
+            .unwrap()
+            .insert(0, "(");
+
+        assert_eq!(
+            apply_fixes(
+                &mut results,
+                &mut walker,
+                false,
+                false,
+                &FixType::ALL,
+                &Output::default()
+            ),
+            0
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "let a = 1;;");
+        assert_eq!(walker.files_sorted()[0].source, "let a = 1;;");
+    }
+
+    #[test]
+    fn write_dry_run_diffs_to_the_output_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.js");
+        write(&path, "let a = 1;;\n").unwrap();
+        let report = dir.path().join("out").join("report.txt");
+
+        let store = CstRuleStore::new().builtins();
+        let mut walker = FileWalker::from_glob_parallel(vec![path.clone()], 1);
+        let file = walker.files.values().next().unwrap();
+        let mut results = vec![rslint_core::lint_file(file, &store, false)];
+        let output = Output::create(&report).unwrap();
+
+        assert_eq!(
+            apply_fixes(
+                &mut results,
+                &mut walker,
+                false,
+                true,
+                &FixType::ALL,
+                &output
+            ),
+            0
+        );
+        let diff = std::fs::read_to_string(&report).unwrap();
+        assert!(diff.contains("-let a = 1;;\n+let a = 1;\n"), "{}", diff);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "let a = 1;;\n");
+    }
+}
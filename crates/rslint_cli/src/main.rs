@@ -20,21 +20,39 @@ pub(crate) struct Options {
     /// Whether to include potentially spammy details in rule diagnostics.
     #[structopt(short, long)]
     verbose: bool,
-    /// A glob pattern to lint.
+    /// A glob pattern to lint, or `@<file>` to lint the paths listed in a file (one per line).
     #[structopt(default_value = "./")]
     files: Vec<String>,
     #[structopt(subcommand)]
     cmd: Option<SubCommand>,
+    /// Only report errors, warnings are still counted in the summary but only fail the run with `--max-warnings`.
+    #[structopt(short, long)]
+    quiet: bool,
+    /// Fail the run if there are more than this many warnings, `0` makes any warning fail it.
+    #[structopt(long)]
+    max_warnings: Option<usize>,
+    /// Stop linting once more than this many problems were found and only report the first ones, which fails the run.
+    #[structopt(long)]
+    max_problems: Option<usize>,
+    /// Exit with `0` instead of `2` if there were warnings but no errors.
+    #[structopt(long)]
+    pass_on_warnings: bool,
     /// Automatically attempt to fix any issues which can be fixed
     #[structopt(short, long)]
     fix: bool,
+    /// Print a diff of the fixes `--fix` would apply without writing them.
+    #[structopt(long)]
+    fix_dry_run: bool,
+    /// Only apply fixes of this type, either `problem`, `suggestion`, or `layout`, can be repeated, ex: `--fix-type layout`
+    #[structopt(long = "fix-type", number_of_values = 1)]
+    fix_types: Vec<rslint_cli::FixType>,
     /// Attempt to run autofixes even if the code contains syntax errors (may produce weird fixes or more errors)
     #[structopt(short = "D", long)]
     dirty: bool,
     /// Disables the global config that is located in your global config directory.
     #[structopt(long)]
     no_global_config: bool,
-    /// Don't respect the '.rslintignore' file.
+    /// Don't respect the '.rslintignore' and '.eslintignore' files, and lint the files in 'node_modules'.
     #[structopt(long)]
     no_ignore: bool,
     /// Use git related ignore files in addition to the '.rslintignore'.
@@ -43,12 +61,111 @@ pub(crate) struct Options {
     /// Overwrite the name of the rslint ignore file (default: .rslintignore)
     #[structopt(long)]
     ignore_file: Option<PathBuf>,
+    /// The extensions of the files to lint, ex: `--ext js,cjs` (default: js, mjs, ts)
+    #[structopt(long = "ext", use_delimiter = true, number_of_values = 1)]
+    extensions: Vec<String>,
+    /// Only lint files matching this glob, relative to the linted path, ex: `--include 'src/**/*.js'`
+    #[structopt(long, number_of_values = 1)]
+    include: Vec<String>,
+    /// Don't lint files matching this glob, relative to the linted path, ex: `--exclude '**/*.test.js'`
+    #[structopt(long, number_of_values = 1)]
+    exclude: Vec<String>,
+    /// Follow symbolic links when walking directories.
+    #[structopt(long)]
+    follow_symlinks: bool,
+    /// Skip files larger than this many bytes, such as minified bundles.
+    #[structopt(long)]
+    max_bytes: Option<u64>,
+    /// Transcode files which are not valid UTF-8 (UTF-16 with a byte order mark, or Latin-1) instead of skipping them.
+    #[structopt(long)]
+    transcode: bool,
+    /// Also lint files without an extension which start with a node shebang, such as `#!/usr/bin/env node`.
+    #[structopt(long)]
+    shebang_scripts: bool,
+    /// Don't warn about files which are empty or only contain whitespace.
+    #[structopt(long)]
+    allow_empty_files: bool,
+    /// Lint source code read from stdin instead of the files.
+    #[structopt(long)]
+    stdin: bool,
+    /// The path of the source code read with `--stdin`, which decides how it is parsed and is displayed in diagnostics.
+    #[structopt(long)]
+    stdin_filename: Option<PathBuf>,
+    /// Only lint the files which changed since the merge base of this git ref and `HEAD`, ex: `--changed-since origin/main`
+    #[structopt(long)]
+    changed_since: Option<String>,
+    /// Keep running and lint the sources sent to stdin as length prefixed JSON requests, writing the diagnostics to stdout as JSON.
+    #[structopt(long)]
+    server: bool,
+    /// Print the files which would be linted and a summary of them without linting anything.
+    #[structopt(long, alias = "stats")]
+    list_files: bool,
+    /// Only lint files which changed or had problems since the last run with `--cache`.
+    #[structopt(long)]
+    cache: bool,
+    /// The path of the cache file used by `--cache` (default: .rslintcache)
+    #[structopt(long)]
+    cache_location: Option<PathBuf>,
+    /// Only run this rule regardless of the config, can be repeated, ex: `--rule no-empty --rule no-debugger`
+    #[structopt(long = "rule", number_of_values = 1)]
+    rules: Vec<String>,
+    /// Override the severity of a rule regardless of the config, either `error`, `warn`, or `off`, can be repeated, ex: `--rule-severity no-empty=error`
+    #[structopt(long = "rule-severity", number_of_values = 1)]
+    severities: Vec<rslint_cli::RuleSeverity>,
+    /// Print every rule and message once with its number of occurrences and the files they occur in, instead of every diagnostic (ignored by the json and sarif formatters).
+    #[structopt(long)]
+    group_by_rule: bool,
+    /// Write a starter `rslintrc.toml` and `.rslintignore` to the working directory, asking for the rules and ignored paths to use if stdin is a terminal.
+    #[structopt(long)]
+    init: bool,
+    /// Overwrite the existing config of the working directory with `--init`.
+    #[structopt(long)]
+    force: bool,
+    /// Print the config which applies to this file as JSON without linting anything.
+    #[structopt(long)]
+    print_config: Option<PathBuf>,
+    /// Keep running after linting and re-lint files as they are changed, added, or removed.
+    #[structopt(short, long)]
+    watch: bool,
+    /// Only report the diagnostics which are not in this baseline, see `--baseline-write`.
+    #[structopt(long)]
+    baseline: Option<PathBuf>,
+    /// Record the current diagnostics to this file, which makes `--baseline` only report new diagnostics.
+    #[structopt(long)]
+    baseline_write: Option<PathBuf>,
+    /// Display file names with `/` separators on every platform, the json and sarif formatters always do.
+    #[structopt(long)]
+    normalize_paths: bool,
+    /// Show how many files were linted out of the total while linting, only if stderr is a terminal.
+    #[structopt(long)]
+    progress: bool,
+    /// Stop linting as soon as a file cannot be parsed, and only report the syntax errors of that file.
+    #[structopt(long, alias = "error-on-parse-fail")]
+    bail: bool,
+    /// Run the rule of a WebAssembly plugin (a `.wasm` or `.wat` file) on every file, can be repeated.
+    #[structopt(long = "plugin", number_of_values = 1)]
+    plugins: Vec<PathBuf>,
+    /// Print the time spent in every rule across all files, along with how many files and bytes it linted.
+    #[structopt(long)]
+    timings: bool,
     /// Maximum number of threads that will be spawned by RSLint. (default: number of cpu cores)
     #[structopt(long)]
     max_threads: Option<usize>,
-    /// The error formatter to use, either "short" or "long" (default)
-    #[structopt(short = "F", long)]
+    /// Maximum number of threads used to read files, useful for limiting IO (default: --max-threads)
+    #[structopt(long)]
+    io_threads: Option<usize>,
+    /// Write the diagnostics to this file instead of stdout or stderr, creating its parent directories if needed.
+    #[structopt(short, long)]
+    output_file: Option<PathBuf>,
+    /// Include the source lines of every diagnostic and its range in them in the output of the json formatter.
+    #[structopt(long)]
+    json_snippets: bool,
+    /// The error formatter to use, either "short", "long" (default), "compact" (one line per diagnostic), "json" (one JSON object per line), or "sarif"
+    #[structopt(short = "F", long, alias = "format")]
     formatter: Option<String>,
+    /// When to color the output, either "auto" (default, only if stdout and stderr are terminals), "always", or "never"
+    #[structopt(long, default_value = "auto")]
+    color: rslint_cli::ColorMode,
     /// Developer only flags. See `-Z help` for more information.
     #[structopt(name = "FLAG", short = "Z")]
     dev_flag: Option<DevFlag>,
@@ -79,6 +196,7 @@ fn main() {
     std::panic::set_hook(Box::new(rslint_cli::panic_hook));
 
     let opt = Options::from_args();
+    rslint_cli::set_color(opt.color);
 
     execute(opt);
 }
@@ -92,17 +210,69 @@ fn execute(opt: Options) {
         (_, Some(SubCommand::Explain { rules })) => ExplanationRunner::new(rules).print(),
         (_, Some(SubCommand::Rules)) => rslint_cli::show_all_rules(),
         (_, Some(SubCommand::Infer { files })) => rslint_cli::infer(files),
-        (_, None) => rslint_cli::run(
-            opt.files,
-            opt.verbose,
-            opt.fix,
-            opt.dirty,
-            opt.formatter,
-            opt.no_global_config,
-            opt.max_threads.unwrap_or_else(num_cpus::get),
-            opt.no_ignore,
-            opt.ignore_file,
-            opt.use_gitignore,
-        ),
+        (_, None) if opt.init => {
+            if !rslint_cli::init(opt.force) {
+                std::process::exit(rslint_cli::EXIT_FAILURE);
+            }
+        }
+        (_, None) if opt.print_config.is_some() => {
+            rslint_cli::print_config(&opt.print_config.unwrap(), opt.no_global_config)
+        }
+        (_, None) => {
+            let cache_location = opt.cache_location;
+            let cache = opt.cache.then(|| {
+                cache_location.unwrap_or_else(|| PathBuf::from(rslint_cli::DEFAULT_CACHE_LOCATION))
+            });
+            let stats = rslint_cli::run(rslint_cli::RunOptions {
+                globs: opt.files,
+                verbose: opt.verbose,
+                fix: opt.fix,
+                dirty: opt.dirty,
+                formatter: opt.formatter,
+                no_global_config: opt.no_global_config,
+                num_threads: opt.max_threads.unwrap_or_default(),
+                no_ignore: opt.no_ignore,
+                ignore_file: opt.ignore_file,
+                use_gitignore: opt.use_gitignore,
+                extensions: opt.extensions,
+                follow_symlinks: opt.follow_symlinks,
+                max_bytes: opt.max_bytes,
+                transcode: opt.transcode,
+                stdin: opt.stdin,
+                io_threads: opt.io_threads.unwrap_or_default(),
+                include: opt.include,
+                exclude: opt.exclude,
+                list_files: opt.list_files,
+                cache,
+                shebang_scripts: opt.shebang_scripts,
+                quiet: opt.quiet,
+                max_warnings: opt.max_warnings,
+                fix_dry_run: opt.fix_dry_run,
+                stdin_filename: opt.stdin_filename,
+                timings: opt.timings,
+                rules: opt.rules,
+                group_by_rule: opt.group_by_rule,
+                pass_on_warnings: opt.pass_on_warnings,
+                watch: opt.watch,
+                baseline: opt.baseline,
+                baseline_write: opt.baseline_write,
+                normalize_paths: opt.normalize_paths,
+                severities: opt.severities,
+                allow_empty_files: opt.allow_empty_files,
+                progress: opt.progress,
+                bail: opt.bail,
+                output_file: opt.output_file,
+                plugins: opt.plugins,
+                fix_types: opt.fix_types,
+                changed_since: opt.changed_since,
+                server: opt.server,
+                json_snippets: opt.json_snippets,
+                max_problems: opt.max_problems,
+            });
+            #[cfg_attr(debug_assertions, allow(unused_variables))]
+            let exit_code = stats.map_or(rslint_cli::EXIT_FAILURE, |stats| stats.exit_code());
+            #[cfg(not(debug_assertions))]
+            std::process::exit(exit_code);
+        }
     }
 }
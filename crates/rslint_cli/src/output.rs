@@ -0,0 +1,72 @@
+//! Where the report of a run is written, see [`Output`].
+
+use crate::color::color_choice;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use termcolor::{NoColor, StandardStream, WriteColor};
+
+/// The stream a formatter writes its report to if there is no output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    /// Print `text` followed by a newline to the stream.
+    pub(crate) fn println(self, text: &str) {
+        match self {
+            Stream::Stdout => println!("{}", text),
+            Stream::Stderr => eprintln!("{}", text),
+        }
+    }
+}
+
+/// Where the report of a run is written, which is the file of `--output-file` if there is one and
+/// stdout or stderr otherwise.
+///
+/// Reports written to a file are never colored, notes and the summary of a run are still printed.
+#[derive(Debug, Default)]
+pub struct Output {
+    file: Option<File>,
+}
+
+impl Output {
+    /// Write the report to the file at `path` instead of stdout or stderr, creating its parent
+    /// directories if needed.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(Self {
+            file: Some(File::create(path)?),
+        })
+    }
+
+    /// Whether the report is written to a file instead of stdout or stderr.
+    pub fn is_file(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// Call `f` with the writer of the report, which is the output file if there is one and
+    /// `stream` otherwise.
+    pub(crate) fn with_writer<T>(
+        &self,
+        stream: Stream,
+        f: impl FnOnce(&mut dyn WriteColor) -> T,
+    ) -> T {
+        if let Some(mut file) = self.file.as_ref() {
+            return f(&mut NoColor::new(&mut file));
+        }
+        let stream = match stream {
+            Stream::Stdout => StandardStream::stdout(color_choice()),
+            Stream::Stderr => StandardStream::stderr(color_choice()),
+        };
+        let mut stream = stream.lock();
+        f(&mut stream)
+    }
+}
@@ -0,0 +1,65 @@
+//! A counter of the linted files drawn on stderr during long runs, see [`Progress`].
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often the counter is redrawn at most, so huge runs do not spend their time writing to stderr.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The number of files linted out of the total, drawn on a single line of stderr which is
+/// cleared once linting is done so diagnostics start on a clean line.
+///
+/// Nothing is drawn if stderr is not a terminal, so logs and other tools never see the counter.
+#[derive(Debug)]
+pub struct Progress {
+    total: usize,
+    done: AtomicUsize,
+    /// Whether stderr is a terminal.
+    enabled: bool,
+    /// When the counter was last drawn, if it was drawn yet.
+    last_draw: Mutex<Option<Instant>>,
+}
+
+impl Progress {
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            done: AtomicUsize::new(0),
+            enabled: atty::is(atty::Stream::Stderr),
+            last_draw: Mutex::new(None),
+        }
+    }
+
+    /// Count a linted file, this is called by the workers concurrently.
+    pub fn tick(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if !self.enabled {
+            return;
+        }
+        let mut last_draw = self.last_draw.lock().unwrap();
+        let due = match *last_draw {
+            Some(last) => last.elapsed() >= REDRAW_INTERVAL,
+            None => true,
+        };
+        if done == self.total || due {
+            *last_draw = Some(Instant::now());
+            let mut stderr = io::stderr();
+            let _ = write!(stderr, "\r{}", self.line(done));
+            let _ = stderr.flush();
+        }
+    }
+
+    /// Clear the counter from stderr.
+    pub fn finish(&self) {
+        if self.enabled && self.last_draw.lock().unwrap().is_some() {
+            let width = self.line(self.total).len();
+            eprint!("\r{:width$}\r", "", width = width);
+        }
+    }
+
+    fn line(&self, done: usize) -> String {
+        format!("linting... {}/{} files", done, self.total)
+    }
+}
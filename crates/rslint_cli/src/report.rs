@@ -0,0 +1,782 @@
+//! Machine readable output formats for diagnostics.
+
+use crate::{fingerprint, FileWalker, RunStats};
+use colored::*;
+use rslint_errors::{
+    file::{FileId, Files},
+    Applicability, CodeSuggestion, Diagnostic, Severity,
+};
+use serde_json::{json, Value};
+use similar::TextDiff;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::Path;
+
+/// Replace the `\\` separators of a file name with `/`, so the same file is displayed the same
+/// way on every platform.
+pub fn normalize_separators(name: &str) -> Cow<'_, str> {
+    if name.contains('\\') {
+        Cow::Owned(name.replace('\\', "/"))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// A view of files which displays every file name with `/` separators, see [`normalize_separators`].
+/// The files themselves keep their native paths for IO.
+pub struct NormalizedNames<'a> {
+    files: &'a dyn Files,
+    names: HashMap<FileId, String>,
+}
+
+impl<'a> NormalizedNames<'a> {
+    /// Normalize the names of every file the diagnostics refer to.
+    pub fn new<'d>(
+        files: &'a dyn Files,
+        diagnostics: impl IntoIterator<Item = &'d Diagnostic>,
+    ) -> Self {
+        let mut names = HashMap::new();
+        let ids = diagnostics.into_iter().flat_map(|diagnostic| {
+            let spans = diagnostic.primary.iter().chain(&diagnostic.children);
+            std::iter::once(diagnostic.file_id).chain(spans.map(|sub| sub.span.file))
+        });
+        for id in ids {
+            if let (false, Some(name)) = (names.contains_key(&id), files.name(id)) {
+                names.insert(id, normalize_separators(name).into_owned());
+            }
+        }
+        Self { files, names }
+    }
+}
+
+impl Files for NormalizedNames<'_> {
+    fn name(&self, id: FileId) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+
+    fn source(&self, id: FileId) -> Option<&str> {
+        self.files.source(id)
+    }
+
+    fn line_index(&self, id: FileId, byte_index: usize) -> Option<usize> {
+        self.files.line_index(id, byte_index)
+    }
+
+    fn line_range(&self, id: FileId, line_index: usize) -> Option<Range<usize>> {
+        self.files.line_range(id, line_index)
+    }
+}
+
+/// Resolve a byte index in a file to a 1-based line and column, the column is counted in characters.
+pub(crate) fn line_col(
+    files: &dyn Files,
+    file_id: usize,
+    byte_index: usize,
+) -> Option<(usize, usize)> {
+    let line = files.line_index(file_id, byte_index)?;
+    let start = files.line_range(file_id, line)?.start;
+    let column = files
+        .source(file_id)?
+        .get(start..byte_index)?
+        .chars()
+        .count();
+    Some((line + 1, column + 1))
+}
+
+/// Convert a range of a file into a JSON object with its byte range and its lines and columns.
+fn location_to_json(files: &dyn Files, file_id: FileId, range: &Range<usize>) -> Value {
+    let position = |idx| {
+        line_col(files, file_id, idx)
+            .map(|(line, column)| json!({ "line": line, "column": column }))
+    };
+    json!({
+        "range": { "start": range.start, "end": range.end },
+        "start": position(range.start),
+        "end": position(range.end),
+    })
+}
+
+/// Convert the full lines a range touches into a JSON object with their text, the 1-based number
+/// of their first line, and the byte range relative to the start of the text, so the range can be
+/// underlined without reading the file. The line break of the last line is left out.
+fn snippet_to_json(files: &dyn Files, file_id: FileId, range: &Range<usize>) -> Option<Value> {
+    let first = files.line_index(file_id, range.start)?;
+    // a range ending at the start of a line does not touch that line
+    let last = files.line_index(file_id, range.end.saturating_sub(1).max(range.start))?;
+    let start = files.line_range(file_id, first)?.start;
+    let end = files.line_range(file_id, last)?.end;
+    let text = files
+        .source(file_id)?
+        .get(start..end)?
+        .trim_end_matches(&['\n', '\r', '\u{2028}', '\u{2029}'][..]);
+    Some(json!({
+        "text": text,
+        "line": first + 1,
+        "range": {
+            "start": (range.start - start).min(text.len()),
+            "end": (range.end - start).min(text.len()),
+        },
+    }))
+}
+
+/// Convert a suggestion into a JSON object with its label, the text replacing its location, and
+/// whether it can be applied without being reviewed.
+fn suggestion_to_json(suggestion: &CodeSuggestion, files: &dyn Files) -> Value {
+    let applicability = match suggestion.applicability {
+        Applicability::Always => "always",
+        Applicability::MaybeIncorrect => "maybe-incorrect",
+        Applicability::HasPlaceholders => "has-placeholders",
+        Applicability::Unspecified => "unspecified",
+    };
+    json!({
+        "message": suggestion.msg,
+        "applicability": applicability,
+        "location": location_to_json(files, suggestion.span.file, &suggestion.span.range),
+        "replacement": suggestion.replacement(files),
+    })
+}
+
+/// Convert a diagnostic into a JSON object with its file, severity, rule, message, location, and
+/// suggestions if it has any, and the snippet of its location if `snippets` is set.
+pub(crate) fn diagnostic_to_json(
+    diagnostic: &Diagnostic,
+    files: &dyn Files,
+    snippets: bool,
+) -> Value {
+    let location = diagnostic
+        .primary
+        .as_ref()
+        .map(|primary| location_to_json(files, diagnostic.file_id, &primary.span.range));
+
+    let mut value = json!({
+        "file": files.name(diagnostic.file_id).map(normalize_separators),
+        "severity": format!("{:?}", diagnostic.severity).to_ascii_lowercase(),
+        "rule": diagnostic.code,
+        "message": diagnostic.title,
+        "location": location,
+        "fingerprint": fingerprint(diagnostic, files),
+    });
+    if let Some(snippet) = diagnostic
+        .primary
+        .as_ref()
+        .filter(|_| snippets)
+        .and_then(|primary| snippet_to_json(files, diagnostic.file_id, &primary.span.range))
+    {
+        value["snippet"] = snippet;
+    }
+    if !diagnostic.suggestions.is_empty() {
+        value["suggestions"] = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| suggestion_to_json(suggestion, files))
+            .collect();
+    }
+    value
+}
+
+/// Write every diagnostic as a single line of JSON (JSON Lines), so each record can be parsed
+/// on its own as soon as it is written. File names always use `/` separators.
+///
+/// If `snippets` is set, the lines of the location of every diagnostic are included as
+/// `snippet`, see `--json-snippets`.
+pub fn emit_json(
+    diagnostics: &[Diagnostic],
+    files: &dyn Files,
+    snippets: bool,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    for diagnostic in diagnostics {
+        writeln!(
+            writer,
+            "{}",
+            diagnostic_to_json(diagnostic, files, snippets)
+        )?;
+    }
+    writer.flush()
+}
+
+/// Write every diagnostic on a single line as `path:line:column: severity rule message`, which
+/// can be parsed by editors (such as vim's `errorformat`) and filtered with grep. The location
+/// is left out for diagnostics without one, and so is the rule for diagnostics without a rule.
+pub fn emit_compact(
+    diagnostics: &[Diagnostic],
+    files: &dyn Files,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    for diagnostic in diagnostics {
+        write!(
+            writer,
+            "{}",
+            files.name(diagnostic.file_id).unwrap_or_default()
+        )?;
+        let position = diagnostic
+            .primary
+            .as_ref()
+            .and_then(|primary| line_col(files, diagnostic.file_id, primary.span.range.start));
+        if let Some((line, column)) = position {
+            write!(writer, ":{}:{}", line, column)?;
+        }
+        write!(
+            writer,
+            ": {}",
+            format!("{:?}", diagnostic.severity).to_ascii_lowercase()
+        )?;
+        if let Some(code) = &diagnostic.code {
+            write!(writer, " {}", code)?;
+        }
+        // multi-line messages would break the one line per diagnostic
+        let message = diagnostic.title.split_whitespace().collect::<Vec<_>>();
+        writeln!(writer, " {}", message.join(" "))?;
+    }
+    writer.flush()
+}
+
+/// Write the statistics of a run as a trailing `{ "summary": { .. } }` line of JSON.
+pub fn emit_json_summary(stats: &RunStats, writer: &mut dyn Write) -> io::Result<()> {
+    let summary = json!({
+        "summary": {
+            "errors": stats.errors,
+            "warnings": stats.warnings,
+            "files": stats.files,
+            "fixable": stats.fixable,
+            "fixed": stats.fixed,
+            "truncated": stats.truncated,
+        }
+    });
+    writeln!(writer, "{}", summary)?;
+    writer.flush()
+}
+
+/// Group diagnostics by their file in the order of [`FileWalker::files_sorted`], files without
+/// diagnostics are left out and files which are not in the walker come last.
+///
+/// The diagnostics of every file are sorted by the start of their primary label (diagnostics
+/// without one come first), then by their rule and message, so the output is the same on every
+/// run regardless of the order the files and rules were linted in.
+pub fn diagnostics_by_file<'a>(
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    walker: &FileWalker,
+) -> Vec<(FileId, Vec<&'a Diagnostic>)> {
+    let order = walker
+        .files_sorted()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, file)| (file.id, idx))
+        .collect::<HashMap<_, _>>();
+
+    let mut by_file = HashMap::<FileId, Vec<&Diagnostic>>::new();
+    for diagnostic in diagnostics {
+        by_file
+            .entry(diagnostic.file_id)
+            .or_default()
+            .push(diagnostic);
+    }
+    let mut by_file = by_file.into_iter().collect::<Vec<_>>();
+    by_file.sort_by_key(|(id, _)| (order.get(id).copied().unwrap_or(usize::MAX), *id));
+    for (_, diagnostics) in &mut by_file {
+        diagnostics.sort_by(|a, b| {
+            let start = |d: &Diagnostic| d.primary.as_ref().map(|primary| primary.span.range.start);
+            start(a)
+                .cmp(&start(b))
+                .then_with(|| a.code.cmp(&b.code))
+                .then_with(|| a.title.cmp(&b.title))
+        });
+    }
+    by_file
+}
+
+/// The occurrences of diagnostics with the same rule and message, see [`group_diagnostics`].
+#[derive(Debug, Clone)]
+pub struct DiagnosticGroup<'a> {
+    pub rule: Option<&'a str>,
+    pub message: &'a str,
+    /// The highest severity of the occurrences, which can differ if files use different configs.
+    pub severity: Severity,
+    pub occurrences: Vec<&'a Diagnostic>,
+}
+
+/// Group diagnostics by their rule and message, the groups with the most occurrences come first.
+pub fn group_diagnostics(diagnostics: &[Diagnostic]) -> Vec<DiagnosticGroup<'_>> {
+    let mut groups: Vec<DiagnosticGroup<'_>> = vec![];
+    for diagnostic in diagnostics {
+        let (rule, message) = (diagnostic.code.as_deref(), diagnostic.title.as_str());
+        match groups
+            .iter_mut()
+            .find(|group| group.rule == rule && group.message == message)
+        {
+            Some(group) => {
+                if diagnostic.severity > group.severity {
+                    group.severity = diagnostic.severity;
+                }
+                group.occurrences.push(diagnostic);
+            }
+            None => groups.push(DiagnosticGroup {
+                rule,
+                message,
+                severity: diagnostic.severity,
+                occurrences: vec![diagnostic],
+            }),
+        }
+    }
+    groups.sort_by_key(|group| std::cmp::Reverse(group.occurrences.len()));
+    groups
+}
+
+/// The maximum number of locations listed for every group of [`emit_grouped`].
+const MAX_GROUP_LOCATIONS: usize = 10;
+
+/// Write every group of diagnostics once with its number of occurrences and the first few files
+/// and locations of its occurrences.
+pub fn emit_grouped(
+    diagnostics: &[Diagnostic],
+    files: &dyn Files,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    for group in group_diagnostics(diagnostics) {
+        let severity = format!("{:?}", group.severity).to_ascii_lowercase();
+        let severity = match group.rule {
+            Some(rule) => format!("{}[{}]", severity, rule),
+            None => severity,
+        };
+        let severity = match group.severity {
+            Severity::Bug | Severity::Error => severity.red(),
+            Severity::Warning => severity.yellow(),
+            Severity::Note | Severity::Help => severity.green(),
+        };
+        let count = group.occurrences.len();
+        writeln!(
+            writer,
+            "{}: {} ({} occurrence{})",
+            severity.bold(),
+            group.message.bold(),
+            count,
+            if count == 1 { "" } else { "s" }
+        )?;
+
+        for diagnostic in group.occurrences.iter().take(MAX_GROUP_LOCATIONS) {
+            let name = files.name(diagnostic.file_id).unwrap_or_default();
+            let position = diagnostic
+                .primary
+                .as_ref()
+                .and_then(|primary| line_col(files, diagnostic.file_id, primary.span.range.start));
+            match position {
+                Some((line, column)) => writeln!(writer, "  {}:{}:{}", name, line, column)?,
+                None => writeln!(writer, "  {}", name)?,
+            }
+        }
+        if count > MAX_GROUP_LOCATIONS {
+            writeln!(writer, "  ... and {} more", count - MAX_GROUP_LOCATIONS)?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// Render a unified diff between the original and the fixed source of a file, with three lines of
+/// context around every hunk.
+pub fn fix_diff(name: &str, original: &str, fixed: &str) -> String {
+    let name = name.trim_start_matches("./");
+    TextDiff::from_lines(original, fixed)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", name), &format!("b/{}", name))
+        .to_string()
+}
+
+/// Get the URI of a file relative to the working directory with `/` separators, which is what
+/// code scanning tools expect so the results match the layout of the repository.
+fn relative_uri(name: &str) -> String {
+    let path = Path::new(name);
+    let path = env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path);
+    let uri = normalize_separators(&path.to_string_lossy()).into_owned();
+    uri.trim_start_matches("./").to_string()
+}
+
+/// Write the diagnostics as a single SARIF 2.1.0 log, which is used by GitHub code scanning.
+pub fn emit_sarif(
+    diagnostics: &[Diagnostic],
+    files: &dyn Files,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    let mut rules = Vec::<&str>::new();
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let level = match diagnostic.severity {
+                Severity::Bug | Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Note | Severity::Help => "note",
+            };
+            let mut result = json!({
+                "level": level,
+                "message": { "text": diagnostic.title },
+                "partialFingerprints": { "rslint/v1": fingerprint(diagnostic, files) },
+            });
+
+            if let Some(code) = diagnostic.code.as_deref() {
+                let index = rules
+                    .iter()
+                    .position(|rule| *rule == code)
+                    .unwrap_or_else(|| {
+                        rules.push(code);
+                        rules.len() - 1
+                    });
+                result["ruleId"] = json!(code);
+                result["ruleIndex"] = json!(index);
+            }
+
+            let name = files.name(diagnostic.file_id);
+            if let (Some(primary), Some(name)) = (diagnostic.primary.as_ref(), name) {
+                let range = &primary.span.range;
+                let mut region = json!({});
+                if let Some((line, column)) = line_col(files, diagnostic.file_id, range.start) {
+                    region["startLine"] = json!(line);
+                    region["startColumn"] = json!(column);
+                }
+                if let Some((line, column)) = line_col(files, diagnostic.file_id, range.end) {
+                    region["endLine"] = json!(line);
+                    region["endColumn"] = json!(column);
+                }
+                result["locations"] = json!([{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": relative_uri(name) },
+                        "region": region,
+                    }
+                }]);
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rslint",
+                    "informationUri": "https://rslint.org",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|rule| json!({ "id": rule })).collect::<Vec<_>>(),
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    });
+    serde_json::to_writer_pretty(&mut *writer, &log)?;
+    writeln!(writer)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileWalker;
+    use rslint_parser::FileKind;
+
+    #[test]
+    fn json_lines() {
+        let mut walker = FileWalker::empty();
+        let id = walker.add_virtual(
+            "let a;\nlet é = 5;".to_string(),
+            "test.js".to_string(),
+            FileKind::Script,
+        );
+        let diagnostics = vec![
+            Diagnostic::error(id, "no-foo", "foo is not allowed").primary(14usize..15, ""),
+            Diagnostic::warning(id, "no-bar", "bar"),
+        ];
+
+        let mut out = vec![];
+        emit_json(&diagnostics, &walker, false, &mut out).unwrap();
+        let lines = String::from_utf8(out).unwrap();
+        let records = lines
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records[0],
+            json!({
+                "file": "test.js",
+                "severity": "error",
+                "rule": "no-foo",
+                "message": "foo is not allowed",
+                "location": {
+                    "range": { "start": 14, "end": 15 },
+                    "start": { "line": 2, "column": 7 },
+                    "end": { "line": 2, "column": 8 },
+                },
+                "fingerprint": fingerprint(&diagnostics[0], &walker),
+            })
+        );
+        assert_eq!(records[1]["location"], Value::Null);
+        assert_eq!(records[0].get("suggestions"), None);
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn json_snippets() {
+        let mut walker = FileWalker::empty();
+        let id = walker.add_virtual(
+            "let a;\nif (é == b) {\n}\nfoo();\n".to_string(),
+            "test.js".to_string(),
+            FileKind::Script,
+        );
+        let diagnostics = vec![
+            Diagnostic::error(id, "eqeqeq", "").primary(14usize..16, ""),
+            Diagnostic::error(id, "no-empty", "").primary(20usize..23, ""),
+            Diagnostic::error(id, "no-foo", "").primary(24usize..31, ""),
+            Diagnostic::error(id, "no-bar", ""),
+        ];
+
+        let mut out = vec![];
+        emit_json(&diagnostics, &walker, true, &mut out).unwrap();
+        let records = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records[0]["snippet"],
+            json!({ "text": "if (é == b) {", "line": 2, "range": { "start": 7, "end": 9 } })
+        );
+        assert_eq!(
+            records[1]["snippet"],
+            json!({ "text": "if (é == b) {\n}", "line": 2, "range": { "start": 13, "end": 16 } })
+        );
+        assert_eq!(
+            records[2]["snippet"],
+            json!({ "text": "foo();", "line": 4, "range": { "start": 0, "end": 6 } })
+        );
+        assert_eq!(records[3].get("snippet"), None);
+
+        let mut out = vec![];
+        emit_json(&diagnostics, &walker, false, &mut out).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("snippet"));
+    }
+
+    #[test]
+    fn json_suggestions() {
+        let mut walker = FileWalker::empty();
+        let id = walker.add_virtual(
+            "if (a == b) {}".to_string(),
+            "test.js".to_string(),
+            FileKind::Script,
+        );
+        let diagnostic = Diagnostic::error(id, "eqeqeq", "use strict equality")
+            .primary(4usize..10, "")
+            .suggestion(
+                6usize..8,
+                "replace `==` with `===`",
+                "===",
+                Applicability::Always,
+            )
+            .suggestion(
+                4usize..10,
+                "wrap in parentheses",
+                "(a == b)",
+                Applicability::MaybeIncorrect,
+            );
+
+        let mut out = vec![];
+        emit_json(&[diagnostic], &walker, false, &mut out).unwrap();
+        let record = serde_json::from_slice::<Value>(&out).unwrap();
+
+        assert_eq!(
+            record["suggestions"][0],
+            json!({
+                "message": "replace `==` with `===`",
+                "applicability": "always",
+                "location": {
+                    "range": { "start": 6, "end": 8 },
+                    "start": { "line": 1, "column": 7 },
+                    "end": { "line": 1, "column": 9 },
+                },
+                "replacement": "===",
+            })
+        );
+        assert_eq!(record["suggestions"][1]["message"], "wrap in parentheses");
+        assert_eq!(record["suggestions"][1]["applicability"], "maybe-incorrect");
+    }
+
+    #[test]
+    fn compact_lines() {
+        let mut walker = FileWalker::empty();
+        let id = walker.add_virtual(
+            "let a;\nlet é = 5;".to_string(),
+            "test.js".to_string(),
+            FileKind::Script,
+        );
+        let diagnostics = vec![
+            Diagnostic::error(id, "no-foo", "foo is\nnot allowed").primary(14usize..15, ""),
+            Diagnostic::warning(id, "no-bar", "bar"),
+            Diagnostic::new(id, Severity::Error, "unexpected token").primary(0usize..3, ""),
+        ];
+
+        let mut out = vec![];
+        emit_compact(&diagnostics, &walker, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "test.js:2:7: error no-foo foo is not allowed\n\
+             test.js: warning no-bar bar\n\
+             test.js:1:1: error unexpected token\n"
+        );
+    }
+
+    #[test]
+    fn forward_slash_names() {
+        let mut walker = FileWalker::empty();
+        let id = walker.add_virtual(
+            "let a;".to_string(),
+            "src\\lib\\a.js".to_string(),
+            FileKind::Script,
+        );
+        let diagnostics = vec![Diagnostic::error(id, "no-foo", "foo").primary(4usize..5, "")];
+
+        let mut out = vec![];
+        emit_json(&diagnostics, &walker, false, &mut out).unwrap();
+        let record = serde_json::from_slice::<Value>(&out).unwrap();
+        assert_eq!(record["file"], "src/lib/a.js");
+
+        let mut out = vec![];
+        emit_sarif(&diagnostics, &walker, &mut out).unwrap();
+        let log = serde_json::from_slice::<Value>(&out).unwrap();
+        let location = &log["runs"][0]["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/lib/a.js");
+
+        let names = NormalizedNames::new(&walker, &diagnostics);
+        assert_eq!(names.name(id), Some("src/lib/a.js"));
+        assert_eq!(names.source(id), Some("let a;"));
+    }
+
+    #[test]
+    fn sort_diagnostics_by_file() {
+        let mut walker = FileWalker::empty();
+        let b = walker.add_virtual("let b;".to_string(), "b.js".to_string(), FileKind::Script);
+        let a = walker.add_virtual("let a;".to_string(), "a.js".to_string(), FileKind::Script);
+        let diagnostics = vec![
+            Diagnostic::error(b, "no-foo", "foo").primary(4usize..5, ""),
+            Diagnostic::error(a, "no-foo", "foo").primary(4usize..5, ""),
+            Diagnostic::error(a, "no-bar", "bar").primary(4usize..5, ""),
+            Diagnostic::error(a, "no-baz", "baz").primary(0usize..3, ""),
+            Diagnostic::warning(a, "no-qux", "qux"),
+            Diagnostic::error(0, "no-foo", "foo"),
+        ];
+
+        let by_file = diagnostics_by_file(&diagnostics, &walker)
+            .into_iter()
+            .map(|(id, diagnostics)| {
+                let codes = diagnostics.iter().map(|d| d.code.as_deref().unwrap());
+                (id, codes.collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            by_file,
+            [
+                (a, vec!["no-qux", "no-baz", "no-bar", "no-foo"]),
+                (b, vec!["no-foo"]),
+                (0, vec!["no-foo"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn group_identical_diagnostics() {
+        let mut walker = FileWalker::empty();
+        let a = walker.add_virtual(
+            "if (a) {}".to_string(),
+            "a.js".to_string(),
+            FileKind::Script,
+        );
+        let b = walker.add_virtual(
+            "if (b) {}".to_string(),
+            "b.js".to_string(),
+            FileKind::Script,
+        );
+        let diagnostics = vec![
+            Diagnostic::warning(a, "no-bar", "bar"),
+            Diagnostic::warning(a, "no-empty", "empty").primary(7usize..9, ""),
+            Diagnostic::error(b, "no-empty", "empty").primary(7usize..9, ""),
+            Diagnostic::warning(b, "no-empty", "other"),
+        ];
+
+        let groups = group_diagnostics(&diagnostics);
+        let summary = groups
+            .iter()
+            .map(|group| {
+                (
+                    group.rule,
+                    group.message,
+                    group.severity,
+                    group.occurrences.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (Some("no-empty"), "empty", Severity::Error, 2),
+                (Some("no-bar"), "bar", Severity::Warning, 1),
+                (Some("no-empty"), "other", Severity::Warning, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn unified_fix_diff() {
+        let diff = fix_diff(
+            "test.js",
+            "let a;\nlet b;;\nlet c;\n",
+            "let a;\nlet b;\nlet c;\n",
+        );
+        assert_eq!(
+            diff,
+            "--- a/test.js\n+++ b/test.js\n@@ -1,3 +1,3 @@\n let a;\n-let b;;\n+let b;\n let c;\n"
+        );
+    }
+
+    #[test]
+    fn sarif_log() {
+        let mut walker = FileWalker::empty();
+        let id = walker.add_virtual(
+            "let a;\nlet b = 5;".to_string(),
+            "./src/test.js".to_string(),
+            FileKind::Script,
+        );
+        let diagnostics = vec![
+            Diagnostic::error(id, "no-foo", "foo").primary(7usize..17, ""),
+            Diagnostic::warning(id, "no-bar", "bar").primary(0usize..3, ""),
+            Diagnostic::warning(id, "no-foo", "foo again"),
+        ];
+
+        let mut out = vec![];
+        emit_sarif(&diagnostics, &walker, &mut out).unwrap();
+        let log = serde_json::from_slice::<Value>(&out).unwrap();
+        let run = &log["runs"][0];
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{ "id": "no-foo" }, { "id": "no-bar" }])
+        );
+        assert_eq!(run["results"][0]["level"], "error");
+        assert_eq!(run["results"][2]["ruleIndex"], 0);
+        assert_eq!(run["results"][2]["locations"], Value::Null);
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "src/test.js" },
+                "region": { "startLine": 2, "startColumn": 1, "endLine": 2, "endColumn": 11 },
+            })
+        );
+    }
+}
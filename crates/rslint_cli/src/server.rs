@@ -0,0 +1,199 @@
+//! A long running server which lints the sources it is sent, see [`serve`].
+//!
+//! Every message is a line with the length of its body in bytes followed by a JSON body of
+//! exactly that length, which may be followed by a newline that is not counted. A request looks
+//! like `{"id": 1, "filename": "src/index.js", "content": "debugger;", "kind": "module"}`, where
+//! `id` is optional and is sent back with the response, and `kind` is either `script`, `module`,
+//! or `typescript` and is inferred from `filename` if it is left out.
+//!
+//! The response is `{"id": 1, "diagnostics": [...]}` with the diagnostics in the format of the
+//! json formatter, or `{"id": 1, "error": "..."}` if the request could not be linted.
+
+use crate::report::diagnostic_to_json;
+use crate::FileWalker;
+use rslint_errors::{file::FileId, Diagnostic};
+use rslint_parser::FileKind;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+
+/// A source to lint.
+#[derive(Debug, Deserialize)]
+struct Request {
+    filename: PathBuf,
+    content: String,
+    #[serde(default)]
+    kind: Option<String>,
+}
+
+/// Answer every request of `input` on `output` until `input` ends, linting the file of a request
+/// with `lint`.
+///
+/// Malformed requests are answered with an error instead of stopping the server. Since the length
+/// of the body of a request is needed to know where the next one starts, the server only stops
+/// early if `input` ends in the middle of a body.
+pub(crate) fn serve(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    mut lint: impl FnMut(&FileWalker, FileId) -> Vec<Diagnostic>,
+) -> io::Result<()> {
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(());
+        }
+        // the newline after the body of the previous request
+        if header.trim().is_empty() {
+            continue;
+        }
+        let len = match header.trim().parse::<u64>() {
+            Ok(len) => len,
+            Err(_) => {
+                let error = format!(
+                    "expected the length of a request, found `{}`",
+                    header.trim()
+                );
+                respond(&mut output, &error_response(Value::Null, error))?;
+                continue;
+            }
+        };
+
+        let mut body = vec![];
+        (&mut input).take(len).read_to_end(&mut body)?;
+        if (body.len() as u64) < len {
+            let error = format!("expected {} bytes, found {}", len, body.len());
+            return respond(&mut output, &error_response(Value::Null, error));
+        }
+        respond(&mut output, &handle(&body, &mut lint))?;
+    }
+}
+
+/// Lint the file of the body of a request, returning the response.
+fn handle(body: &[u8], lint: &mut impl FnMut(&FileWalker, FileId) -> Vec<Diagnostic>) -> Value {
+    let value = match serde_json::from_slice::<Value>(body) {
+        Ok(value) => value,
+        Err(err) => return error_response(Value::Null, format!("invalid request: {}", err)),
+    };
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let request = match serde_json::from_value::<Request>(value) {
+        Ok(request) => request,
+        Err(err) => return error_response(id, format!("invalid request: {}", err)),
+    };
+    let kind = match request.kind.as_deref().map(parse_kind).transpose() {
+        Ok(kind) => kind,
+        Err(err) => return error_response(id, err),
+    };
+
+    let mut walker = FileWalker::from_source_with_path(request.content, request.filename);
+    let file_id = *walker.files.keys().next().unwrap();
+    if let Some(kind) = kind {
+        walker.files.get_mut(&file_id).unwrap().kind = kind;
+    }
+    let mut diagnostics = lint(&walker, file_id);
+    diagnostics.sort_by_key(|d| d.primary.as_ref().map(|primary| primary.span.range.start));
+    json!({
+        "id": id,
+        "diagnostics": diagnostics
+            .iter()
+            .map(|d| diagnostic_to_json(d, &walker, false))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn parse_kind(kind: &str) -> Result<FileKind, String> {
+    match kind.to_ascii_lowercase().as_str() {
+        "script" => Ok(FileKind::Script),
+        "module" => Ok(FileKind::Module),
+        "typescript" => Ok(FileKind::TypeScript),
+        _ => Err(format!(
+            "unknown file kind `{}`, expected `script`, `module`, or `typescript`",
+            kind
+        )),
+    }
+}
+
+fn error_response(id: Value, error: String) -> Value {
+    json!({ "id": id, "error": error })
+}
+
+/// Write a response with its length, followed by a newline so it can also be read as a line.
+fn respond(output: &mut impl Write, response: &Value) -> io::Result<()> {
+    let body = response.to_string();
+    write!(output, "{}\n{}\n", body.len(), body)?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_core::{lint_file, CstRuleStore};
+    use std::io::Cursor;
+
+    fn frame(body: &str) -> String {
+        format!("{}\n{}\n", body.len(), body)
+    }
+
+    #[test]
+    fn answer_requests() {
+        let store = CstRuleStore::new().builtins();
+        let input = [
+            frame(r#"{"id": 1, "filename": "a.js", "content": "debugger;"}"#),
+            "not a length\n".to_string(),
+            frame(r#"{"id": 2, "filename": "b.js", "content": "x", "kind": "jsx"}"#),
+            frame(r#"{"id": 3, "content": "let a;"}"#),
+            frame("{"),
+            frame(r#"{"filename": "c.ts", "content": "let a: number = 1;"}"#),
+        ]
+        .concat();
+
+        let mut output = vec![];
+        serve(Cursor::new(input), &mut output, |walker, id| {
+            lint_file(&walker.files[&id], &store, false)
+                .diagnostics()
+                .cloned()
+                .collect()
+        })
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let responses = output
+            .lines()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|message| {
+                assert_eq!(message[0].parse::<usize>().unwrap(), message[1].len());
+                serde_json::from_str::<Value>(message[1]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(responses.len(), 6);
+
+        assert_eq!(responses[0]["id"], 1);
+        let diagnostics = responses[0]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["rule"], "no-debugger");
+        assert_eq!(diagnostics[0]["file"], "a.js");
+
+        assert_eq!(responses[1]["id"], Value::Null);
+        assert!(responses[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("not a length"));
+        assert_eq!(responses[2]["id"], 2);
+        assert!(responses[2]["error"].as_str().unwrap().contains("jsx"));
+        assert_eq!(responses[3]["id"], 3);
+        assert!(responses[3]["error"].as_str().unwrap().contains("filename"));
+        assert_eq!(responses[4]["id"], Value::Null);
+        assert!(responses[4]["error"].is_string());
+        assert_eq!(responses[5]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn stop_on_truncated_requests() {
+        let mut output = vec![];
+        serve(Cursor::new("100\n{}"), &mut output, |_, _| vec![]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("expected 100 bytes, found 2"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Re-linting files as they change on disk, see [`watch`].
+
+use crate::{canonical_path, lint_err, lint_note, lint_warn, walked_dir, FileWalker};
+use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
+use rslint_errors::file::FileId;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+/// How long the watcher waits for more events of a path before reporting it, so a single save
+/// only triggers one re-lint even if the editor writes the file multiple times.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How long to wait for the events of other paths after an event, so changes to many files at
+/// once (such as switching branches) are re-linted together.
+const BATCH_WINDOW: Duration = Duration::from_millis(50);
+
+/// Watch `roots` for changes after the initial lint, keeping the files of the walker in sync with
+/// the files on disk and calling `relint` with the files which were added or changed.
+///
+/// New files are loaded by calling `load` with the root they were created in, which should walk
+/// the root again so new files go through the same filters as the initial walk.
+/// This only returns if the roots cannot be watched.
+pub fn watch(
+    walker: &mut FileWalker,
+    roots: &[PathBuf],
+    mut load: impl FnMut(&mut FileWalker, &Path),
+    mut relint: impl FnMut(&mut FileWalker, &[FileId]),
+) {
+    let (tx, rx) = channel();
+    let mut watcher = match watcher(tx, DEBOUNCE) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            lint_err!("failed to start watching files: {}", err);
+            return;
+        }
+    };
+    for root in roots {
+        if let Err(err) = watcher.watch(walked_dir(root), RecursiveMode::Recursive) {
+            lint_err!("failed to watch `{}`: {}", root.display(), err);
+            return;
+        }
+    }
+
+    let mut tracked = TrackedFiles::new(walker, roots);
+    lint_note!("watching for changes, press Ctrl+C to stop");
+    while let Some(events) = next_batch(&rx) {
+        let changed = tracked.apply(walker, events, &mut load);
+        if !changed.is_empty() {
+            relint(walker, &changed);
+        }
+    }
+}
+
+/// Wait for the next event and collect every event which follows it closely.
+fn next_batch(rx: &Receiver<DebouncedEvent>) -> Option<Vec<DebouncedEvent>> {
+    let mut events = vec![rx.recv().ok()?];
+    while let Ok(event) = rx.recv_timeout(BATCH_WINDOW) {
+        events.push(event);
+    }
+    Some(events)
+}
+
+/// The canonical path of an event, the path may not exist anymore if it was removed.
+fn event_path(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !path.exists() => canonical_path(parent).join(name),
+        _ => canonical_path(path),
+    }
+}
+
+/// The files of a walker by their canonical path, used to match events to files.
+#[derive(Debug)]
+struct TrackedFiles {
+    /// The canonical path of the directory walked for every root along with the path it was given
+    /// as, which may be a glob.
+    roots: Vec<(PathBuf, PathBuf)>,
+    paths: HashMap<PathBuf, FileId>,
+}
+
+impl TrackedFiles {
+    fn new(walker: &FileWalker, roots: &[PathBuf]) -> Self {
+        let mut tracked = Self {
+            roots: roots
+                .iter()
+                .map(|root| (canonical_path(&walked_dir(root)), root.clone()))
+                .collect(),
+            paths: HashMap::new(),
+        };
+        tracked.track_new(walker);
+        tracked
+    }
+
+    /// Start tracking every file of the walker which is not tracked yet and return their ids.
+    fn track_new(&mut self, walker: &FileWalker) -> Vec<FileId> {
+        let mut ids = self.paths.values().copied().collect::<Vec<_>>();
+        ids.sort_unstable();
+        let mut new = vec![];
+        for file in walker.files.values() {
+            if let (Some(path), Err(_)) = (file.path.as_deref(), ids.binary_search(&file.id)) {
+                self.paths.insert(canonical_path(path), file.id);
+                new.push(file.id);
+            }
+        }
+        new
+    }
+
+    /// Apply a batch of events to the walker, returning the files which were added or whose
+    /// source code changed.
+    fn apply(
+        &mut self,
+        walker: &mut FileWalker,
+        events: Vec<DebouncedEvent>,
+        load: &mut impl FnMut(&mut FileWalker, &Path),
+    ) -> Vec<FileId> {
+        let mut changed = vec![];
+        let mut created = vec![];
+        for event in events {
+            match event {
+                DebouncedEvent::Write(path) => match self.paths.get(&event_path(&path)) {
+                    Some(&id) => {
+                        if walker.maybe_update_file_src(path) {
+                            changed.push(id);
+                        }
+                    }
+                    None => created.push(path),
+                },
+                DebouncedEvent::Create(path) => created.push(path),
+                DebouncedEvent::Remove(path) => self.remove(walker, &path),
+                DebouncedEvent::Rename(from, to) => {
+                    self.remove(walker, &from);
+                    created.push(to);
+                }
+                // events were dropped so anything may have changed
+                DebouncedEvent::Rescan => {
+                    changed.extend(walker.reload_all());
+                    created.extend(self.roots.iter().map(|(root, _)| root.clone()));
+                }
+                DebouncedEvent::Error(err, path) => match path {
+                    Some(path) => lint_warn!("failed to watch `{}`: {}", path.display(), err),
+                    None => lint_warn!("failed to watch files: {}", err),
+                },
+                _ => {}
+            }
+        }
+
+        // walking a root again only reads the files which are not loaded yet
+        let mut roots = created
+            .iter()
+            .filter_map(|path| {
+                let path = event_path(path);
+                self.roots
+                    .iter()
+                    .position(|(root, _)| path.starts_with(root))
+            })
+            .collect::<Vec<_>>();
+        roots.sort_unstable();
+        roots.dedup();
+        for idx in roots {
+            load(walker, &self.roots[idx].1);
+        }
+        changed.extend(self.track_new(walker));
+
+        changed.sort_unstable();
+        changed.dedup();
+        changed
+    }
+
+    /// Remove the file at `path` from the walker, or every file under it if it is a directory.
+    fn remove(&mut self, walker: &mut FileWalker, path: &Path) {
+        let path = event_path(path);
+        self.paths.retain(|file_path, id| {
+            if file_path.starts_with(&path) {
+                walker.remove_by_id(*id);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn apply_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(root.join("a.js"), "let a;").unwrap();
+        fs::write(root.join("lib").join("b.js"), "let b;").unwrap();
+
+        let mut walker = FileWalker::empty();
+        let mut load = |walker: &mut FileWalker, root: &Path| {
+            walker.load_files_parallel(std::iter::once(root.to_path_buf()), 1, true, None, false);
+        };
+        load(&mut walker, &root);
+        let mut tracked = TrackedFiles::new(&walker, std::slice::from_ref(&root));
+        let id = |walker: &FileWalker, name: &str| {
+            walker
+                .files
+                .values()
+                .find(|file| file.path.as_ref().unwrap().ends_with(name))
+                .map(|file| file.id)
+        };
+        let a = id(&walker, "a.js").unwrap();
+
+        // an unchanged file and a file which is not linted are not re-linted
+        fs::write(root.join("c.txt"), "").unwrap();
+        let events = vec![
+            DebouncedEvent::Write(root.join("a.js")),
+            DebouncedEvent::Create(root.join("c.txt")),
+        ];
+        let unchanged = tracked.apply(&mut walker, events, &mut load);
+
+        fs::write(root.join("a.js"), "let a = 1;").unwrap();
+        fs::write(root.join("d.js"), "let d;").unwrap();
+        fs::remove_dir_all(root.join("lib")).unwrap();
+        let events = vec![
+            DebouncedEvent::Write(root.join("a.js")),
+            DebouncedEvent::Create(root.join("d.js")),
+            DebouncedEvent::Remove(root.join("lib")),
+        ];
+        let changed = tracked.apply(&mut walker, events, &mut load);
+        let d = id(&walker, "d.js");
+        let b = id(&walker, "b.js");
+
+        assert!(unchanged.is_empty());
+        let mut expected = vec![a, d.unwrap()];
+        expected.sort_unstable();
+        assert_eq!(changed, expected);
+        assert_eq!(walker.files[&a].source, "let a = 1;");
+        assert_eq!(b, None);
+        assert_eq!(walker.files.len(), 2);
+    }
+}
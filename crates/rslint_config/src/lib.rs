@@ -9,9 +9,11 @@ use rslint_core::{get_group_rules_by_name, CstRule, CstRuleStore, Diagnostic, Ru
 use rslint_errors::file::{Files, SimpleFile};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     env,
     fs::read_to_string,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 /// The name of the config files to search for.
@@ -21,23 +23,26 @@ pub const CONFIG_NAMES: [&str; 2] = ["rslintrc.json", "rslintrc.toml"];
 pub type RuleList = Vec<Box<dyn CstRule>>;
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConfigRepr {
-    rules: Option<RulesConfigRepr>,
+    /// Stop looking for config files in the parent directories of this config.
     #[serde(default)]
-    errors: ErrorsConfigRepr,
+    root: bool,
+    rules: Option<RulesConfigRepr>,
+    errors: Option<ErrorsConfigRepr>,
 }
 
 impl Default for ConfigRepr {
     fn default() -> Self {
         Self {
+            root: false,
             rules: None,
             errors: Default::default(),
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default)]
 struct RulesConfigRepr {
     #[serde(deserialize_with = "de::from_rule_objects")]
@@ -150,7 +155,7 @@ impl schemars::JsonSchema for RulesConfigRepr {
 }
 
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct ErrorsConfigRepr {
     formatter: String,
 }
@@ -163,14 +168,54 @@ impl Default for ErrorsConfigRepr {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Config {
     repr: ConfigRepr,
+    origins: Origins,
+}
+
+/// The config files which a config was merged from and which configured each of its settings.
+#[derive(Debug, Clone, Default)]
+struct Origins {
+    /// The config files, from the farthest to the closest one.
+    files: Vec<PathBuf>,
+    rules: HashMap<String, PathBuf>,
+    groups: HashMap<String, PathBuf>,
+    allowed: HashMap<String, PathBuf>,
+}
+
+impl Origins {
+    fn new(path: &Path, repr: &ConfigRepr) -> Self {
+        let mut origins = Self {
+            files: vec![path.to_path_buf()],
+            ..Self::default()
+        };
+        if let Some(rules) = &repr.rules {
+            let entry = |name: &str| (name.to_string(), path.to_path_buf());
+            let configured = rules.errors.iter().chain(&rules.warnings);
+            origins.rules = configured.map(|rule| entry(rule.name())).collect();
+            origins.groups = rules.groups.iter().map(|group| entry(group)).collect();
+            origins.allowed = rules.allowed.iter().map(|rule| entry(rule)).collect();
+        }
+        origins
+    }
+
+    /// Settings of `child` override the same settings of this config.
+    fn merge(&self, child: Origins) -> Origins {
+        let mut merged = self.clone();
+        merged.files.extend(child.files);
+        merged.rules.extend(child.rules);
+        merged.groups.extend(child.groups);
+        merged.allowed.extend(child.allowed);
+        merged
+    }
 }
 
 enum ConfigStyle {
     Toml,
     Json,
+    /// The `rslint` key of a `package.json`.
+    PackageJson,
 }
 
 impl Config {
@@ -178,18 +223,22 @@ impl Config {
     /// dir and all of it ancestors, and if `no_global_config` is `false`,
     /// look in the systems config directory.
     pub fn new(no_global_config: bool) -> Result<Self, (SimpleFile, Diagnostic)> {
-        let path = Self::find_config(no_global_config);
-        let (source, (path, style)) = match path
-            .as_ref()
-            .and_then(|(path, _)| read_to_string(path).ok())
-        {
-            Some(source) => (source, path.unwrap()),
-            None => return Ok(Self::default()),
+        match Self::find_config(no_global_config) {
+            Some((path, style)) => Self::from_file(&path, style),
+            None => Ok(Self::default()),
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn from_file(path: &Path, style: ConfigStyle) -> Result<Self, (SimpleFile, Diagnostic)> {
+        let source = match read_to_string(path) {
+            Ok(source) => source,
+            Err(_) => return Ok(Self::default()),
         };
 
         match style {
             ConfigStyle::Json => match serde_json::from_str::<ConfigRepr>(&source) {
-                Ok(repr) => Ok(Self { repr }),
+                Ok(repr) => Ok(Self::with_origin(repr, path)),
                 Err(err) => {
                     let config_file = SimpleFile::new(path.to_string_lossy().into(), source);
                     let (line, col) = (err.line() - 1, err.column() - 1);
@@ -205,7 +254,7 @@ impl Config {
                 }
             },
             ConfigStyle::Toml => match toml::from_str::<ConfigRepr>(&source) {
-                Ok(repr) => Ok(Self { repr }),
+                Ok(repr) => Ok(Self::with_origin(repr, path)),
 
                 Err(err) => {
                     let config_file = SimpleFile::new(path.to_string_lossy().into(), source);
@@ -223,27 +272,36 @@ impl Config {
                     Err((config_file, d))
                 }
             },
+            ConfigStyle::PackageJson => {
+                let repr =
+                    serde_json::from_str::<serde_json::Value>(&source).and_then(|mut manifest| {
+                        match manifest.get_mut("rslint") {
+                            Some(config) => serde_json::from_value::<ConfigRepr>(config.take()),
+                            None => Ok(ConfigRepr::default()),
+                        }
+                    });
+                match repr {
+                    Ok(repr) => Ok(Self::with_origin(repr, path)),
+                    // the position of the error is lost once the key is taken out of the manifest
+                    Err(err) => {
+                        let config_file = SimpleFile::new(path.to_string_lossy().into(), source);
+                        let msg = format!("invalid `rslint` key: {}", err);
+                        Err((config_file, Diagnostic::error(1, "config", msg)))
+                    }
+                }
+            }
         }
     }
 
-    fn find_config(global_config: bool) -> Option<(PathBuf, ConfigStyle)> {
-        let path = env::current_dir().ok()?;
-        fn search_path(path: &Path) -> Option<(PathBuf, ConfigStyle)> {
-            for config_name in CONFIG_NAMES.iter() {
-                let new_path = path.join(config_name);
-                let style = if config_name.ends_with("json") {
-                    ConfigStyle::Json
-                } else {
-                    ConfigStyle::Toml
-                };
-
-                if new_path.exists() {
-                    return Some((new_path, style));
-                }
-            }
-            None
+    fn with_origin(repr: ConfigRepr, path: &Path) -> Self {
+        Self {
+            origins: Origins::new(path, &repr),
+            repr,
         }
+    }
 
+    fn find_config(global_config: bool) -> Option<(PathBuf, ConfigStyle)> {
+        let path = env::current_dir().ok()?;
         for path in path.ancestors() {
             if let Some(res) = search_path(path) {
                 return Some(res);
@@ -258,9 +316,91 @@ impl Config {
         None
     }
 
+    /// Merge the config of a nested directory into this config, rules configured in `child`
+    /// override the same rules in this config, while groups and allowed rules are combined.
+    pub fn merge(&self, child: Config) -> Config {
+        let rules = match (self.repr.rules.clone(), child.repr.rules) {
+            (Some(parent), Some(child)) => {
+                let overridden = |rule: &dyn CstRule| {
+                    child
+                        .errors
+                        .iter()
+                        .chain(&child.warnings)
+                        .any(|other| other.name() == rule.name())
+                };
+                let inherit = |rules: RuleList| {
+                    rules
+                        .into_iter()
+                        .filter(|rule| !overridden(&**rule))
+                        .collect::<Vec<_>>()
+                };
+                let combine = |mut parent: Vec<String>, child: Vec<String>| {
+                    for item in child {
+                        if !parent.contains(&item) {
+                            parent.push(item);
+                        }
+                    }
+                    parent
+                };
+
+                let mut errors = child.errors.clone();
+                errors.extend(inherit(parent.errors));
+                let mut warnings = child.warnings.clone();
+                warnings.extend(inherit(parent.warnings));
+                Some(RulesConfigRepr {
+                    errors,
+                    warnings,
+                    groups: combine(parent.groups, child.groups),
+                    allowed: combine(parent.allowed, child.allowed),
+                })
+            }
+            (parent, child) => child.or(parent),
+        };
+
+        Config {
+            repr: ConfigRepr {
+                root: child.repr.root,
+                rules,
+                errors: child.repr.errors.or_else(|| self.repr.errors.clone()),
+            },
+            origins: self.origins.merge(child.origins),
+        }
+    }
+
+    /// The config files this config was read from, from the farthest to the closest one.
+    /// This is empty for the default config.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.origins.files
+    }
+
+    /// The config file which configured a rule, or which allowed it if it is allowed, which takes
+    /// priority over configuring it. This is `None` for rules which are only enabled through their group.
+    pub fn rule_origin(&self, rule_name: &str) -> Option<&Path> {
+        self.origins
+            .allowed
+            .get(rule_name)
+            .or_else(|| self.origins.rules.get(rule_name))
+            .map(PathBuf::as_path)
+    }
+
+    /// The config file which enabled a rule group.
+    pub fn group_origin(&self, group: &str) -> Option<&Path> {
+        self.origins.groups.get(group).map(PathBuf::as_path)
+    }
+
+    /// Whether a rule is disabled by the `allowed` field.
+    pub fn is_allowed(&self, rule_name: &str) -> bool {
+        self.origins.allowed.contains_key(rule_name)
+    }
+
+    /// Whether config files in the parent directories of this config are ignored.
+    pub fn is_root(&self) -> bool {
+        self.repr.root
+    }
+
     /// Returns the formatter that should be used.
     pub fn formatter(&self) -> String {
-        self.repr.errors.formatter.clone()
+        self.repr.errors.clone().unwrap_or_default().formatter
     }
 
     pub fn warning_rule_names(&self) -> impl Iterator<Item = &str> {
@@ -347,7 +487,255 @@ impl Config {
     }
 }
 
+/// Get the path of the config file of a directory, which is either a dedicated config file or the
+/// `package.json` of the directory if it has an `rslint` key.
+pub fn config_file(dir: &Path) -> Option<PathBuf> {
+    search_path(dir).map(|(path, _)| path)
+}
+
+/// Find the config file in a directory, a dedicated config file takes priority over the `rslint`
+/// key of the `package.json` of the directory.
+fn search_path(path: &Path) -> Option<(PathBuf, ConfigStyle)> {
+    for config_name in CONFIG_NAMES.iter() {
+        let new_path = path.join(config_name);
+        let style = if config_name.ends_with("json") {
+            ConfigStyle::Json
+        } else {
+            ConfigStyle::Toml
+        };
+
+        if new_path.exists() {
+            return Some((new_path, style));
+        }
+    }
+
+    // a manifest without the key (or which is not valid) is not a config at all
+    let manifest = path.join("package.json");
+    let source = read_to_string(&manifest).ok()?;
+    serde_json::from_str::<serde_json::Value>(&source)
+        .ok()?
+        .get("rslint")?;
+    Some((manifest, ConfigStyle::PackageJson))
+}
+
+/// Resolves the config of each directory by merging the config files of the directory and of its
+/// ancestors from the farthest to the closest one, like ESLint's cascading configs.
+///
+/// A config with `root = true` stops the search in its parent directories, and the global config is
+/// only used if no config file was found at all. The result of every visited directory is cached,
+/// so the files of sibling directories are only read once.
+#[derive(Debug, Default)]
+pub struct ConfigResolver {
+    no_global_config: bool,
+    dirs: HashMap<PathBuf, Option<Arc<Config>>>,
+    fallback: Option<Arc<Config>>,
+    /// The errors of config files which could not be parsed, such files are ignored.
+    pub errors: Vec<(SimpleFile, Diagnostic)>,
+}
+
+impl ConfigResolver {
+    pub fn new(no_global_config: bool) -> Self {
+        Self {
+            no_global_config,
+            ..Self::default()
+        }
+    }
+
+    /// Get the config which applies to the files in `dir`.
+    pub fn resolve(&mut self, dir: &Path) -> Arc<Config> {
+        let dir = env::current_dir()
+            .map(|cwd| cwd.join(dir))
+            .unwrap_or_else(|_| dir.to_path_buf());
+        let dir = dir.canonicalize().unwrap_or(dir);
+        if let Some(config) = self.resolve_inner(&dir) {
+            return config;
+        }
+        self.fallback().clone()
+    }
+
+    fn resolve_inner(&mut self, dir: &Path) -> Option<Arc<Config>> {
+        if let Some(config) = self.dirs.get(dir) {
+            return config.clone();
+        }
+
+        let own =
+            search_path(dir).and_then(|(path, style)| match Config::from_file(&path, style) {
+                Ok(config) => Some(config),
+                Err(err) => {
+                    self.errors.push(err);
+                    None
+                }
+            });
+        let parent = match (&own, dir.parent()) {
+            (Some(config), _) if config.is_root() => None,
+            (_, Some(parent)) => self.resolve_inner(parent),
+            (_, None) => None,
+        };
+
+        let config = match (parent, own) {
+            (Some(parent), Some(own)) => Some(Arc::new(parent.merge(own))),
+            (parent, own) => parent.or_else(|| own.map(Arc::new)),
+        };
+        self.dirs.insert(dir.to_path_buf(), config.clone());
+        config
+    }
+
+    /// The global config, or the default config if there is none.
+    fn fallback(&mut self) -> &Arc<Config> {
+        let no_global_config = self.no_global_config;
+        let errors = &mut self.errors;
+        self.fallback.get_or_insert_with(|| {
+            let global = config_dir()
+                .filter(|_| !no_global_config)
+                .and_then(|dir| search_path(&dir));
+            let config = match global {
+                Some((path, style)) => Config::from_file(&path, style).unwrap_or_else(|err| {
+                    errors.push(err);
+                    Config::default()
+                }),
+                None => Config::default(),
+            };
+            Arc::new(config)
+        })
+    }
+}
+
 fn unique_rules(first: RuleList, mut second: RuleList) -> impl Iterator<Item = Box<dyn CstRule>> {
     second.retain(|rule| !first.iter().any(|prev| prev.name() == rule.name()));
     first.into_iter().chain(second)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn nested_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("pkg/src")).unwrap();
+        fs::write(
+            root.join("rslintrc.toml"),
+            "root = true\n[rules.errors]\nno-empty = {}\nfor-direction = {}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("pkg/rslintrc.toml"),
+            "[rules.warnings]\nno-empty = {}\n",
+        )
+        .unwrap();
+
+        let mut resolver = ConfigResolver::new(true);
+        let (top, pkg, src) = (
+            resolver.resolve(root),
+            resolver.resolve(&root.join("pkg")),
+            resolver.resolve(&root.join("pkg/src")),
+        );
+
+        assert!(resolver.errors.is_empty());
+        assert!(Arc::ptr_eq(&pkg, &src));
+        assert_eq!(top.rule_level_by_name("no-empty"), RuleLevel::Error);
+        assert_eq!(src.rule_level_by_name("no-empty"), RuleLevel::Warning);
+        let mut names = src
+            .rules_store()
+            .0
+            .rules
+            .iter()
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, ["for-direction", "no-empty"]);
+
+        let (top_file, pkg_file) = (root.join("rslintrc.toml"), root.join("pkg/rslintrc.toml"));
+        assert_eq!(src.files(), [top_file.clone(), pkg_file.clone()]);
+        assert_eq!(src.rule_origin("no-empty"), Some(&*pkg_file));
+        assert_eq!(src.rule_origin("for-direction"), Some(&*top_file));
+        assert_eq!(src.rule_origin("no-debugger"), None);
+    }
+
+    #[test]
+    fn inherit_the_formatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("inherit")).unwrap();
+        fs::create_dir_all(root.join("override")).unwrap();
+        fs::write(
+            root.join("rslintrc.toml"),
+            "root = true\n[errors]\nformatter = \"short\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("inherit/rslintrc.toml"),
+            "[rules.errors]\nno-empty = {}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("override/rslintrc.toml"),
+            "[errors]\nformatter = \"json\"\n",
+        )
+        .unwrap();
+
+        let mut resolver = ConfigResolver::new(true);
+        let (inherit, over, top) = (
+            resolver.resolve(&root.join("inherit")),
+            resolver.resolve(&root.join("override")),
+            resolver.resolve(root),
+        );
+
+        assert!(resolver.errors.is_empty());
+        assert_eq!(top.formatter(), "short");
+        assert_eq!(inherit.formatter(), "short");
+        assert_eq!(over.formatter(), "json");
+        assert_eq!(Config::default().formatter(), "long");
+    }
+
+    #[test]
+    fn package_json_configs() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        for dir in ["key", "both", "none", "invalid"].iter() {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("rslintrc.toml"), "root = true\n").unwrap();
+        let manifest = r#"{"name": "a", "rslint": {"rules": {"warnings": {"no-empty": {}}}}}"#;
+        fs::write(root.join("key/package.json"), manifest).unwrap();
+        fs::write(root.join("both/package.json"), manifest).unwrap();
+        fs::write(
+            root.join("both/rslintrc.toml"),
+            "[rules.errors]\nno-empty = {}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("none/package.json"),
+            r#"{"devDependencies": {"rslint": "1.0.0"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("invalid/package.json"),
+            r#"{"rslint": {"rules": 5}}"#,
+        )
+        .unwrap();
+
+        let mut resolver = ConfigResolver::new(true);
+        let configs = ["key", "both", "none", "invalid"]
+            .iter()
+            .map(|dir| resolver.resolve(&root.join(dir)))
+            .collect::<Vec<_>>();
+        let root = root.canonicalize().unwrap();
+
+        assert_eq!(
+            configs[0].rule_level_by_name("no-empty"),
+            RuleLevel::Warning
+        );
+        let key_files = [root.join("rslintrc.toml"), root.join("key/package.json")];
+        assert_eq!(configs[0].files(), key_files);
+        assert_eq!(configs[1].rule_level_by_name("no-empty"), RuleLevel::Error);
+        assert_eq!(configs[2].files(), [root.join("rslintrc.toml")]);
+        assert_eq!(resolver.errors.len(), 1);
+        assert!(resolver.errors[0]
+            .1
+            .title
+            .starts_with("invalid `rslint` key"));
+    }
+}
@@ -0,0 +1,42 @@
+;; An example plugin which reports every tab character of a file, see `src/plugin.rs` for the ABI.
+;;
+;; Run it with `rslint --plugin crates/rslint_core/plugins/no-tabs.wat`, the text format is compiled
+;; when the plugin is loaded. Plugins written in other languages are passed as `.wasm` files.
+(module
+  (import "rslint" "report" (func $report (param i32 i32 i32 i32)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "unexpected tab character")
+
+  ;; the start of the free memory, the instance is thrown away after every file so nothing is freed
+  (global $heap (mut i32) (i32.const 1024))
+
+  (func (export "rslint_alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (local.get $ptr) (local.get $len)))
+    (block $done
+      (loop $grow
+        (br_if $done
+          (i32.le_u (global.get $heap) (i32.mul (memory.size) (i32.const 65536))))
+        (if (i32.eq (memory.grow (i32.const 1)) (i32.const -1))
+          (then unreachable))
+        (br $grow)))
+    (local.get $ptr))
+
+  ;; the syntax tree is not needed, only the bytes of the source are scanned
+  (func (export "rslint_check") (param $src i32) (param $len i32) (param $tree i32) (param $tree_len i32)
+    (local $i i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (if (i32.eq (i32.load8_u (i32.add (local.get $src) (local.get $i))) (i32.const 9))
+          (then
+            (call $report
+              (local.get $i)
+              (i32.add (local.get $i) (i32.const 1))
+              (i32.const 0)
+              (i32.const 24))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next))))
+)
@@ -1,3 +1,4 @@
+use super::FixType;
 use crate::{lint_file_inner, File, LintResult, RuleResult};
 use rslint_parser::*;
 use rslint_text_edit::{apply_indels, Indel};
@@ -31,45 +32,95 @@ fn get_runnable_indels(mut tagged: Vec<TaggedIndel>) -> Vec<TaggedIndel> {
 }
 
 pub fn recursively_apply_fixes(result: &mut LintResult, file: &File) -> String {
-    let script = result.parsed.kind() == SyntaxKind::SCRIPT;
-    let mut parsed = result.parsed.clone();
+    recursively_apply_fixes_of_types(result, file, &FixType::ALL)
+}
+
+/// Like [`recursively_apply_fixes`], but only apply the fixes whose type is in `fix_types`.
+pub fn recursively_apply_fixes_of_types(
+    result: &mut LintResult,
+    file: &File,
+    fix_types: &[FixType],
+) -> String {
+    // the tree of the result may have been released, the file has the same source
+    let mut parsed = file.parse();
+    let mut had_errors = !file.syntax_errors().is_empty();
     let file_id = result.file_id;
     let mut cur_results = result.rule_results.clone();
+    result.breaking_fixes.clear();
 
     for _ in 0..=MAX_FIX_ITERATIONS {
-        let indels = get_runnable_indels(rule_results_to_tagged_indels(&cur_results));
+        let indels = get_runnable_indels(rule_results_to_tagged_indels(&cur_results, fix_types));
 
         if indels.is_empty() {
             break;
         }
-        let mut string = parsed.text().to_string();
+        let old = parsed.text().to_string();
+        let mut string = old.clone();
         apply_indels(
             &indels.iter().map(|x| x.indel.clone()).collect::<Vec<_>>(),
             &mut string,
         );
-        parsed = if script {
-            let res = parse_text(&string, file_id);
-            // this needs to be updated for when fixes are applied "dirty" (when there are parser errors)
-            result.parser_diagnostics = res.errors().to_owned();
-            res.syntax()
-        } else {
-            let res = parse_module(&string, file_id);
-            result.parser_diagnostics = res.errors().to_owned();
-            res.syntax()
-        };
+        let res = parse_with_syntax(&string, file_id, file.kind.into());
+        // this needs to be updated for when fixes are applied "dirty" (when there are parser errors)
+        result.parser_diagnostics = res.errors().to_owned();
+        parsed = res.syntax();
+        if !had_errors && !result.parser_diagnostics.is_empty() {
+            result.breaking_fixes = breaking_fixes(&old, &indels, file);
+        }
+        had_errors = !result.parser_diagnostics.is_empty();
 
         // TODO: should we panic on Err? autofix causing the linter to fail should always be incorrect
         let res = lint_file_inner(parsed.clone(), vec![], file, result.store, result.verbose);
         cur_results = res.rule_results;
     }
     result.rule_results = cur_results;
+    // the syntax errors of an earlier iteration were fixed again
+    if result.parser_diagnostics.is_empty() {
+        result.breaking_fixes.clear();
+    }
     parsed.text().to_string()
 }
 
-fn rule_results_to_tagged_indels(results: &HashMap<&'static str, RuleResult>) -> Vec<TaggedIndel> {
+/// The rules whose fixes make `source`, which has no syntax errors, have syntax errors when they
+/// are applied on their own. If none of them do, the fixes only break it together, so every rule
+/// is returned.
+fn breaking_fixes(source: &str, indels: &[TaggedIndel], file: &File) -> Vec<&'static str> {
+    let mut tags = indels.iter().map(|x| x.tag).collect::<Vec<_>>();
+    tags.sort_unstable();
+    tags.dedup();
+    let breaking = tags
+        .iter()
+        .copied()
+        .filter(|tag| {
+            let mut string = source.to_string();
+            apply_indels(
+                &indels
+                    .iter()
+                    .filter(|x| x.tag == *tag)
+                    .map(|x| x.indel.clone())
+                    .collect::<Vec<_>>(),
+                &mut string,
+            );
+            !parse_with_syntax(&string, file.id, file.kind.into())
+                .errors()
+                .is_empty()
+        })
+        .collect::<Vec<_>>();
+    if breaking.is_empty() {
+        tags
+    } else {
+        breaking
+    }
+}
+
+fn rule_results_to_tagged_indels(
+    results: &HashMap<&'static str, RuleResult>,
+    fix_types: &[FixType],
+) -> Vec<TaggedIndel> {
     results
         .iter()
         .filter_map(|(tag, res)| Some((tag, res.fixer.clone()?)))
+        .filter(|(_, fixer)| fix_types.contains(&fixer.fix_type))
         .flat_map(|(tag, fixer)| {
             fixer
                 .indels
@@ -80,3 +131,80 @@ fn rule_results_to_tagged_indels(results: &HashMap<&'static str, RuleResult>) ->
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::{errors::NoExtraSemi, style::BlockSpacing};
+    use crate::{lint_file, CstRule, CstRuleStore, Rule, RuleCtx};
+    use serde::{Deserialize, Serialize};
+
+    /// A rule with a fix which wraps the whole file in an unclosed paren.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct BreakingFix;
+
+    impl Rule for BreakingFix {
+        fn name(&self) -> &'static str {
+            "breaking-fix"
+        }
+
+        fn group(&self) -> &'static str {
+            "tests"
+        }
+    }
+
+    #[typetag::serde]
+    impl CstRule for BreakingFix {
+        fn check_root(&self, _root: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+            if !ctx.src.starts_with('(') {
+                let err = ctx.err(self.name(), "not wrapped in a paren");
+                ctx.add_err(err);
+                ctx.fix().insert(0, "(");
+            }
+            None
+        }
+    }
+
+    #[test]
+    fn apply_fixes_of_types() {
+        let store = CstRuleStore {
+            rules: vec![
+                Box::new(BlockSpacing::default()),
+                Box::new(NoExtraSemi::default()),
+            ],
+        };
+        let file = File::from_string("if (a) {b;};", FileKind::Script, "test.js");
+        let fix = |fix_types: &[FixType]| {
+            let mut result = lint_file(&file, &store, false);
+            recursively_apply_fixes_of_types(&mut result, &file, fix_types)
+        };
+
+        assert_eq!(fix(&[FixType::Layout]), "if (a) { b; };");
+        assert_eq!(fix(&[FixType::Suggestion]), "if (a) {b;}");
+        assert_eq!(fix(&FixType::ALL), "if (a) { b; }");
+        assert_eq!(fix(&[FixType::Problem]), "if (a) {b;};");
+        assert_eq!("Layout".parse(), Ok(FixType::Layout));
+        assert!("style".parse::<FixType>().is_err());
+    }
+
+    #[test]
+    fn find_breaking_fixes() {
+        let fix = |store: CstRuleStore| {
+            let file = File::from_string("let a = 1;;", FileKind::Script, "test.js");
+            let mut result = lint_file(&file, &store, false);
+            let fixed = recursively_apply_fixes(&mut result, &file);
+            (fixed, result.breaking_fixes)
+        };
+
+        let (fixed, breaking) = fix(CstRuleStore {
+            rules: vec![Box::new(NoExtraSemi::default()), Box::new(BreakingFix)],
+        });
+        assert_eq!(fixed, "(let a = 1;");
+        assert_eq!(breaking, ["breaking-fix"]);
+        let (fixed, breaking) = fix(CstRuleStore {
+            rules: vec![Box::new(NoExtraSemi::default())],
+        });
+        assert_eq!(fixed, "let a = 1;");
+        assert!(breaking.is_empty());
+    }
+}
@@ -8,15 +8,61 @@ use rslint_parser::{ast, AstNode, SyntaxNode, SyntaxNodeExt};
 use rslint_text_edit::apply_indels;
 use rslint_text_edit::Indel;
 use std::borrow::Borrow;
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 
-pub use apply::{recursively_apply_fixes, MAX_FIX_ITERATIONS};
+pub use apply::{recursively_apply_fixes, recursively_apply_fixes_of_types, MAX_FIX_ITERATIONS};
+
+/// The category of a fix, which allows only applying some kinds of fixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FixType {
+    /// A fix for code which is likely a bug, this is the default.
+    Problem,
+    /// A fix which changes code to a better way of doing the same thing.
+    Suggestion,
+    /// A fix which only changes whitespace, semicolons, or other formatting.
+    Layout,
+}
+
+impl FixType {
+    /// Every type of fix.
+    pub const ALL: [FixType; 3] = [FixType::Problem, FixType::Suggestion, FixType::Layout];
+}
+
+impl FromStr for FixType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            _ if s.eq_ignore_ascii_case("problem") => Ok(FixType::Problem),
+            _ if s.eq_ignore_ascii_case("suggestion") => Ok(FixType::Suggestion),
+            _ if s.eq_ignore_ascii_case("layout") => Ok(FixType::Layout),
+            _ => Err(format!(
+                "unknown fix type `{}`, expected `problem`, `suggestion`, or `layout`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FixType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FixType::Problem => "problem",
+            FixType::Suggestion => "suggestion",
+            FixType::Layout => "layout",
+        })
+    }
+}
 
 /// A simple interface for applying changes to source code
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Fixer {
     pub indels: Vec<Indel>,
     pub src: Arc<str>,
+    /// The category of the changes, see [`Fixer::fix_type`].
+    pub fix_type: FixType,
 }
 
 impl Fixer {
@@ -24,9 +70,16 @@ impl Fixer {
         Self {
             indels: vec![],
             src,
+            fix_type: FixType::Problem,
         }
     }
 
+    /// Set the category of the changes, which decides if they are applied with `--fix-type`.
+    pub fn fix_type(&mut self, fix_type: FixType) -> &mut Self {
+        self.fix_type = fix_type;
+        self
+    }
+
     /// Apply this fixer to its source code
     pub fn apply(&self) -> String {
         let mut new = (&*self.src).to_string();
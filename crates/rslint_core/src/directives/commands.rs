@@ -4,6 +4,7 @@ use super::{Component, ComponentKind, Instruction};
 use crate::{CstRule, File};
 use rslint_lexer::SyntaxKind;
 use rslint_parser::SyntaxNode;
+use std::ops::Range;
 
 /// A structure describing a command.
 #[derive(Debug, Clone)]
@@ -17,6 +18,7 @@ pub struct CommandDescriptor {
 pub fn get_command_descriptors() -> Box<[CommandDescriptor]> {
     vec![
         ignore_command_descriptor(),
+        ignore_next_line_command_descriptor(),
         disable_command_descriptor(),
         enable_command_descriptor(),
     ]
@@ -41,6 +43,20 @@ pub fn ignore_command_descriptor() -> CommandDescriptor {
     }
 }
 
+pub fn ignore_next_line_command_descriptor() -> CommandDescriptor {
+    use Instruction::*;
+
+    CommandDescriptor {
+        instructions: vec![
+            CommandName("ignore-next-line"),
+            Repetition(Box::new(RuleName), SyntaxKind::COMMA),
+        ]
+        .into_boxed_slice(),
+        docs: "ignore all or some rules on the line after the directive",
+        name: "ignore-next-line",
+    }
+}
+
 pub fn disable_command_descriptor() -> CommandDescriptor {
     use Instruction::*;
 
@@ -76,6 +92,8 @@ pub enum Command {
     IgnoreNode(SyntaxNode),
     /// Ignore only a subset of rules for a specific `SyntaxNode`.
     IgnoreNodeRules(SyntaxNode, Vec<Box<dyn CstRule>>),
+    /// Ignore all rules or some rules in the byte range of the line after the directive.
+    IgnoreNextLine(Range<usize>, Vec<Box<dyn CstRule>>),
 
     /// Disable the linter for some rules or all rules from some line until the next enable command
     Disable(usize, Vec<Box<dyn CstRule>>),
@@ -100,6 +118,7 @@ impl Command {
 
         match name {
             "ignore" => parse_ignore_command(components, node, top_level),
+            "ignore-next-line" => Some(parse_ignore_next_line_command(components, line, file)),
             "disable" => Some(parse_disable_command(components, line, file)),
             "enable" => Some(parse_enable_command(line, file)),
             _ => None,
@@ -129,6 +148,18 @@ fn parse_ignore_command(
     }
 }
 
+fn parse_ignore_next_line_command(components: &[Component], line: usize, file: &File) -> Command {
+    let start = file.line_start(line + 1).unwrap_or(usize::MAX);
+    let end = file.line_start(line + 2).unwrap_or(usize::MAX);
+    let rules = components
+        .get(1)
+        .and_then(|c| c.kind.repetition())
+        .map(|rules| rules.iter().flat_map(|c| c.kind.rule()).collect())
+        .unwrap_or_default();
+
+    Command::IgnoreNextLine(start..end, rules)
+}
+
 fn parse_disable_command(components: &[Component], line: usize, file: &File) -> Command {
     let line_start = file
         .line_start(line)
@@ -20,9 +20,10 @@ mod parser;
 pub use self::commands::*;
 pub use self::parser::*;
 
-use crate::{rule_tests, CstRule, CstRuleStore, Diagnostic, SyntaxNode};
+use crate::{rule_tests, CstRule, CstRuleStore, Diagnostic, RuleResult, SyntaxNode};
 use rslint_lexer::SyntaxKind;
 use rslint_parser::{util::*, SmolStr, TextRange, TextSize};
+use std::{collections::HashMap, ops::Range};
 
 // TODO: More complex warnings, things like ignoring node directives because of file level directives
 
@@ -56,6 +57,9 @@ impl ComponentKind {
                 "ignore" => Some(
                     "`ignore` will ignore all rules, or any given rules in some range or node.",
                 ),
+                "ignore-next-line" => Some(
+                    "`ignore-next-line` will ignore all rules, or any given rules on the next line.",
+                ),
                 _ => None,
             },
             _ => None,
@@ -169,7 +173,8 @@ impl Directive {
 }
 
 /// Apply file level directives to a store and add their respective diagnostics to the pool of diagnostics.
-/// for file level ignores this will clear all the rules from the store.
+/// for file level ignores this will clear all the rules from the store, file level ignores of some rules
+/// are applied to the results of the rules by [`apply_suppression_directives`].
 ///
 /// This method furthermore issues more contextual warnings like disabling a rule after
 /// the entire file has been disabled.
@@ -190,11 +195,8 @@ pub fn apply_top_level_directives(
                 store.rules.clear();
                 cleared = Some(directive.comment.token.text_range());
             }
-            Some(Command::IgnoreFileRules(rules)) => {
+            Some(Command::IgnoreFileRules(_)) => {
                 ignored.push(directive.comment.token.text_range());
-                store
-                    .rules
-                    .retain(|rule| !rules.iter().any(|allowed| allowed.name() == rule.name()));
             }
             _ => {}
         }
@@ -216,6 +218,172 @@ pub fn apply_top_level_directives(
     }
 }
 
+/// A range of a file in which some rules are suppressed by a directive.
+struct Region<'a> {
+    range: Range<usize>,
+    /// The suppressed rules, every rule is suppressed if it is empty.
+    rules: &'a [Box<dyn CstRule>],
+    directive: &'a Directive,
+    /// The name of the command of the directive.
+    name: &'static str,
+    /// Whether the region is the whole file, which also suppresses diagnostics without a primary label.
+    whole_file: bool,
+    /// Whether the region suppressed any diagnostic.
+    used: bool,
+}
+
+impl Region<'_> {
+    fn applies_to(&self, rule: &str) -> bool {
+        self.rules.is_empty() || self.rules.iter().any(|allowed| allowed.name() == rule)
+    }
+}
+
+/// Remove the diagnostics and fixes of rules suppressed by `ignore`, `ignore-next-line`, and `disable`
+/// directives, and add a note for the directives which did not suppress anything so they can be cleaned up.
+///
+/// A `disable` region starts at the line of the directive and ends at the line of the next `enable`
+/// directive, or at the end of the file if there is none.
+///
+/// `results` must only contain the rules which ran on the file, a directive is only reported as unused
+/// if one of the rules it names ran, since the other rules could not have reported anything.
+pub fn apply_suppression_directives(
+    directives: &[Directive],
+    results: &mut HashMap<&'static str, RuleResult>,
+    diagnostics: &mut Vec<DirectiveError>,
+    file_id: usize,
+) {
+    // no rule ran at all, so every other directive is trivially unused
+    if directives
+        .iter()
+        .any(|directive| matches!(directive.command, Some(Command::IgnoreFile)))
+    {
+        return;
+    }
+
+    let mut commands = directives
+        .iter()
+        .filter_map(|directive| match &directive.command {
+            Some(Command::Disable(start, _)) | Some(Command::Enable(start)) => {
+                Some((*start, directive))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    commands.sort_by_key(|(start, _)| *start);
+
+    let mut regions = vec![];
+    for (idx, (start, directive)) in commands.iter().enumerate() {
+        if let Some(Command::Disable(_, rules)) = &directive.command {
+            let end = commands[idx + 1..]
+                .iter()
+                .find(|(_, dir)| matches!(dir.command, Some(Command::Enable(_))))
+                .map_or(usize::MAX, |(end, _)| *end);
+            regions.push(Region {
+                range: *start..end,
+                rules,
+                directive,
+                name: "disable",
+                whole_file: false,
+                used: false,
+            });
+        }
+    }
+    for directive in directives {
+        let (range, rules, name) = match &directive.command {
+            Some(Command::IgnoreFileRules(rules)) => (0..usize::MAX, rules.as_slice(), "ignore"),
+            Some(Command::IgnoreNode(node)) => (node_range(node), &[][..], "ignore"),
+            Some(Command::IgnoreNodeRules(node, rules)) => {
+                (node_range(node), rules.as_slice(), "ignore")
+            }
+            Some(Command::IgnoreNextLine(range, rules)) => {
+                (range.clone(), rules.as_slice(), "ignore-next-line")
+            }
+            _ => continue,
+        };
+        regions.push(Region {
+            whole_file: matches!(directive.command, Some(Command::IgnoreFileRules(_))),
+            range,
+            rules,
+            directive,
+            name,
+            used: false,
+        });
+    }
+
+    for (name, result) in results.iter_mut() {
+        let mut applies = regions
+            .iter_mut()
+            .filter(|region| region.applies_to(name))
+            .collect::<Vec<_>>();
+        if applies.is_empty() {
+            continue;
+        }
+
+        result.diagnostics.retain(|diagnostic| {
+            let suppressed_by = |region: &&mut &mut Region| match &diagnostic.primary {
+                Some(primary) => region.range.contains(&primary.span.range.start),
+                None => region.whole_file,
+            };
+            match applies.iter_mut().find(suppressed_by) {
+                Some(region) => {
+                    region.used = true;
+                    false
+                }
+                None => true,
+            }
+        });
+        if let Some(fixer) = result.fixer.as_mut() {
+            fixer.indels.retain(|indel| {
+                let start = usize::from(indel.delete.start());
+                !applies.iter().any(|region| region.range.contains(&start))
+            });
+        }
+    }
+
+    for region in regions {
+        let ran = results.keys().any(|rule| region.applies_to(rule));
+        if !region.used && ran {
+            let note = Diagnostic::note(
+                file_id,
+                "linter",
+                format!(
+                    "this {} directive did not suppress any diagnostics",
+                    region.name
+                ),
+            )
+            .primary(region.directive.comment.token.text_range(), "")
+            .unnecessary();
+
+            diagnostics.push(DirectiveError::new(note, DirectiveErrorKind::Other));
+        }
+    }
+}
+
+/// Whether a rule should skip a node because of an `ignore` directive before it.
+pub fn skip_node(directives: &[Directive], node: &SyntaxNode, rule: &dyn CstRule) -> bool {
+    if let Some(comment) = node.first_token().and_then(|t| t.comment()) {
+        if let Some(directive) = directives.iter().find(|dir| dir.comment == comment) {
+            match &directive.command {
+                Some(Command::IgnoreNode(_)) => {
+                    return true;
+                }
+                Some(Command::IgnoreNodeRules(_, rules))
+                    if rules.iter().any(|allowed| allowed.name() == rule.name()) =>
+                {
+                    return true;
+                }
+                _ => {}
+            }
+        }
+    }
+    false
+}
+
+fn node_range(node: &SyntaxNode) -> Range<usize> {
+    let range = node.text_range();
+    usize::from(range.start())..usize::from(range.end())
+}
+
 pub fn apply_node_directives(
     directives: &[Directive],
     node: &SyntaxNode,
@@ -239,25 +407,6 @@ pub fn apply_node_directives(
     Some(store)
 }
 
-pub fn skip_node(directives: &[Directive], node: &SyntaxNode, rule: &dyn CstRule) -> bool {
-    if let Some(comment) = node.first_token().and_then(|t| t.comment()) {
-        if let Some(directive) = directives.iter().find(|dir| dir.comment == comment) {
-            match &directive.command {
-                Some(Command::IgnoreNode(_)) => {
-                    return true;
-                }
-                Some(Command::IgnoreNodeRules(_, rules)) => {
-                    if rules.iter().any(|allowed| allowed.name() == rule.name()) {
-                        return true;
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-    false
-}
-
 rule_tests! {
     crate::groups::errors::NoEmpty::default(),
     err: {
@@ -285,3 +434,134 @@ rule_tests! {
         "
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::rule_prelude::*;
+    use crate::{lint_file, CstRuleStore, File};
+    use rslint_parser::FileKind;
+
+    declare_lint! {
+        /// Report every file without a label.
+        #[derive(Default)]
+        Unlabeled,
+        errors,
+        "unlabeled"
+    }
+
+    #[typetag::serde]
+    impl CstRule for Unlabeled {
+        fn check_root(&self, _: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+            let err = ctx.err(self.name(), "this file is not allowed");
+            ctx.add_err(err);
+            None
+        }
+    }
+
+    #[test]
+    fn disable_regions() {
+        let src = "
+if (a) {}
+// rslint-disable no-empty
+if (a) {}
+if (a) {}
+// rslint-enable
+if (a) {}
+// rslint-disable for-direction
+if (a) {}
+";
+        let file = File::from_string(src, FileKind::Script, "test.js");
+        let mut store = CstRuleStore::new().builtins();
+        store.rules.retain(|rule| rule.name() == "no-empty");
+        let result = lint_file(&file, &store, false);
+
+        let lines = result.rule_results["no-empty"]
+            .diagnostics
+            .iter()
+            .map(|d| file.line_index(d.primary.as_ref().unwrap().span.range.start))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [1, 6, 8]);
+        // `for-direction` did not run, so its region is not reported as unused
+        assert!(result.directive_diagnostics.is_empty());
+    }
+
+    fn no_empty_lines(src: &str) -> (Vec<usize>, Vec<usize>) {
+        let file = File::from_string(src, FileKind::Script, "test.js");
+        let mut store = CstRuleStore::new().builtins();
+        store.rules.retain(|rule| rule.name() == "no-empty");
+        let result = lint_file(&file, &store, false);
+        let line_of =
+            |d: &Diagnostic| file.line_index(d.primary.as_ref().unwrap().span.range.start);
+
+        let rule_lines = result.rule_results["no-empty"]
+            .diagnostics
+            .iter()
+            .map(line_of)
+            .collect();
+        let directive_lines = result
+            .directive_diagnostics
+            .iter()
+            .map(|d| line_of(&d.diagnostic))
+            .collect();
+        (rule_lines, directive_lines)
+    }
+
+    #[test]
+    fn ignore_next_line() {
+        let src = "
+// rslint-ignore-next-line no-empty
+if (a) {}
+if (a) {}
+// rslint-ignore-next-line
+if (a) {}
+// rslint-ignore-next-line for-direction
+if (a) {}
+";
+        let (rules, directives) = no_empty_lines(src);
+        assert_eq!(rules, [3, 7]);
+        assert!(directives.is_empty());
+    }
+
+    #[test]
+    fn unused_ignores() {
+        let src = "
+a();
+// rslint-ignore no-empty
+if (a) {}
+// rslint-ignore no-empty
+if (a) { b }
+// rslint-ignore
+if (a) {}
+";
+        let (rules, directives) = no_empty_lines(src);
+        assert!(rules.is_empty());
+        assert_eq!(directives, [4]);
+
+        let file = File::from_string(src, FileKind::Script, "test.js");
+        let mut store = CstRuleStore::new().builtins();
+        store.rules.retain(|rule| rule.name() == "no-empty");
+        let result = lint_file(&file, &store, false);
+        // unused directives are notes, so they do not make a file fail or warn
+        assert_eq!(
+            result.directive_diagnostics[0].diagnostic.severity,
+            Severity::Note
+        );
+        assert_eq!(result.outcome(), Outcome::Success);
+    }
+
+    #[test]
+    fn ignore_file_rules_without_labels() {
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::new(Unlabeled::new()) as Box<dyn CstRule>]);
+
+        let file = File::from_string("let a;", FileKind::Script, "test.js");
+        let result = lint_file(&file, &store, false);
+        assert_eq!(result.rule_results["unlabeled"].diagnostics.len(), 1);
+
+        let src = "// rslint-ignore unlabeled\n\nlet a;";
+        let file = File::from_string(src, FileKind::Script, "test.js");
+        let result = lint_file(&file, &store, false);
+        assert!(result.rule_results["unlabeled"].diagnostics.is_empty());
+        assert!(result.directive_diagnostics.is_empty());
+    }
+}
@@ -123,6 +123,7 @@ impl<'store, 'file> DirectiveParser<'store, 'file> {
     pub fn get_file_directives(&mut self) -> DirectiveResult {
         let top_level = self.top_level_directives();
         let mut result = DirectiveResult::default();
+        let mut seen = vec![];
 
         for descendant in self.root.descendants().skip(1) {
             let comment = descendant
@@ -134,6 +135,11 @@ impl<'store, 'file> DirectiveParser<'store, 'file> {
                 Some(comment) if comment.token.parent().is::<ModuleItem>() => comment,
                 _ => continue,
             };
+            // nested nodes share their first token, the directive applies to the outermost one
+            if seen.contains(&comment.token) {
+                continue;
+            }
+            seen.push(comment.token.clone());
 
             let directive = self.parse_directive(comment, Some(descendant), false);
             result.extend(directive);
@@ -187,8 +193,16 @@ impl<'store, 'file> DirectiveParser<'store, 'file> {
             return Err(DirectiveError::new(d, DirectiveErrorKind::ExpectedCommand));
         }
 
-        let cmd_tok = lexer.next().unwrap();
-        let cmd_name = lexer.source_of(&cmd_tok);
+        // command names such as `ignore-next-line` are lexed as multiple tokens
+        let cmd_start = lexer.next().unwrap().range.start();
+        while lexer
+            .peek_with_spaces()
+            .map_or(false, |tok| tok.kind == T![-] || tok.kind == T![ident])
+        {
+            lexer.next();
+        }
+        let cmd_range = TextRange::new(cmd_start, (lexer.abs_cur() as u32).into());
+        let cmd_name = lexer.source_range(cmd_range);
 
         let cmd = self
             .commands
@@ -202,7 +216,7 @@ impl<'store, 'file> DirectiveParser<'store, 'file> {
                 // TODO: Suggest name using `find_best_match_for_name`
                 let d = self
                     .err(&format!("unknown directive command: `{}`", cmd_name))
-                    .primary(cmd_tok.range, "");
+                    .primary(cmd_range, "");
 
                 return Err(DirectiveError::new(
                     d,
@@ -215,7 +229,7 @@ impl<'store, 'file> DirectiveParser<'store, 'file> {
             &mut lexer,
             Component {
                 kind: ComponentKind::CommandName(cmd_name.into()),
-                range: cmd_tok.range,
+                range: cmd_range,
             },
             &cmd,
         )?;
@@ -321,10 +335,12 @@ impl<'store, 'file> DirectiveParser<'store, 'file> {
                 let name_range = TextRange::new(start, end.into());
                 let name = lexer.source_range(name_range);
 
+                // the store may be filtered, for example by `--rule`, so directives can still
+                // name the builtin rules which do not run
                 let rule = self
                     .store
-                    .map(|store| store.get(name))
-                    .unwrap_or_else(|| crate::get_rule_by_name(name));
+                    .and_then(|store| store.get(name))
+                    .or_else(|| crate::get_rule_by_name(name));
                 if let Some(rule) = rule {
                     Ok(vec![Component {
                         kind: ComponentKind::Rule(rule),
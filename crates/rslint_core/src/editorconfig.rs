@@ -0,0 +1,100 @@
+//! Formatting conventions of a file declared in `.editorconfig` files.
+//!
+//! Finding and matching the `.editorconfig` files of a file is left to the user of the crate
+//! since the core runner never reads files, see [`EditorConfig::set`].
+
+/// The style of indentation used in a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+/// The line terminator used in a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndOfLine {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+/// The `.editorconfig` properties of a file which are relevant to rules, every property is
+/// `None` if it is not declared (or set to `unset`). Rules should only fall back to these if
+/// their own options are not explicitly set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EditorConfig {
+    pub indent_style: Option<IndentStyle>,
+    /// The number of columns of a single indentation level, see [`EditorConfig::indent_width`].
+    pub indent_size: Option<usize>,
+    /// The number of columns of a tab character.
+    pub tab_width: Option<usize>,
+    pub max_line_length: Option<usize>,
+    pub end_of_line: Option<EndOfLine>,
+}
+
+impl EditorConfig {
+    /// Set a property from a `key = value` pair of a matching `.editorconfig` section,
+    /// the key and value are case-insensitive and unknown properties or values are ignored.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let value = value.trim().to_ascii_lowercase();
+        let number = || value.parse::<usize>().ok();
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "indent_style" => {
+                self.indent_style = match value.as_str() {
+                    "tab" => Some(IndentStyle::Tab),
+                    "space" => Some(IndentStyle::Space),
+                    _ => None,
+                }
+            }
+            // `tab` means the indent size is the tab width, which `indent_width` falls back to
+            "indent_size" => self.indent_size = number(),
+            "tab_width" => self.tab_width = number(),
+            "max_line_length" => self.max_line_length = number(),
+            "end_of_line" => {
+                self.end_of_line = match value.as_str() {
+                    "lf" => Some(EndOfLine::Lf),
+                    "crlf" => Some(EndOfLine::Crlf),
+                    "cr" => Some(EndOfLine::Cr),
+                    _ => None,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The number of columns of a single indentation level, which defaults to the tab width.
+    pub fn indent_width(&self) -> Option<usize> {
+        self.indent_size.or(self.tab_width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_properties() {
+        let mut config = EditorConfig::default();
+        config.set("indent_style", "Space");
+        config.set("tab_width", "8");
+        config.set("indent_size", "tab");
+        config.set("MAX_LINE_LENGTH", "off");
+        config.set("end_of_line", "crlf");
+        config.set("charset", "utf-8");
+
+        assert_eq!(
+            config,
+            EditorConfig {
+                indent_style: Some(IndentStyle::Space),
+                indent_size: None,
+                tab_width: Some(8),
+                max_line_length: None,
+                end_of_line: Some(EndOfLine::Crlf),
+            }
+        );
+        assert_eq!(config.indent_width(), Some(8));
+        config.set("indent_style", "unset");
+        assert_eq!(config.indent_style, None);
+    }
+}
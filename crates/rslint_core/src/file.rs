@@ -1,10 +1,36 @@
 //! Representation of a file for the linter
 
-use rslint_parser::{parse_with_syntax, FileKind, ParserError, SyntaxNode};
+use crate::EditorConfig;
+use once_cell::sync::OnceCell;
+use rslint_errors::file::{FileId, Files};
+use rslint_parser::{parse_with_syntax, FileKind, Parse, ParserError, SyntaxNode};
+use std::hash::{Hash, Hasher};
 use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+/// A 0-based line and column in a file, the column is counted in bytes from the start of the line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// The start and end [`Position`] of a range in a file, see [`File::span_of`].
+///
+/// This is not named `Span` to not be confused with the [`Span`](crate::Span) trait of ranges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PositionRange {
+    pub start: Position,
+    pub end: Position,
+}
+
 // 0 is reserved for "no file id" (virtual files)
 static FILE_ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
 
@@ -22,20 +48,51 @@ pub struct File {
     pub kind: FileKind,
     /// The cached line start locations in this file.
     pub line_starts: Vec<usize>,
+    /// The name of the encoding the source was transcoded from if the file on disk was not UTF-8.
+    pub transcoded_from: Option<&'static str>,
+    /// The `.editorconfig` properties of this file, which are exposed to rules.
+    pub editorconfig: EditorConfig,
+    /// The kind this file had before a kind hint replaced it, which is restored once the source
+    /// no longer has a hint, see [`File::kind_hint`].
+    unhinted_kind: Option<FileKind>,
+    /// The cached result of parsing this file, this is cleared by [`File::update_src`].
+    parsed: ParseCache,
+}
+
+/// A lazily initialized parse result, this is ignored when comparing or hashing files
+/// since it is purely derived from the source.
+#[derive(Debug, Clone, Default)]
+struct ParseCache(OnceCell<Parse<()>>);
+
+impl PartialEq for ParseCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ParseCache {}
+
+impl Hash for ParseCache {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
 }
 
 impl File {
     pub fn new_concrete(source: String, path: PathBuf) -> Self {
         let id = FILE_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
-        let ext = path
-            .extension()
-            .map_or("".into(), |ext| ext.to_string_lossy());
+        let ext = path.extension().map_or(String::new(), |ext| {
+            ext.to_string_lossy().to_ascii_lowercase()
+        });
 
-        let kind = match ext.as_ref() {
+        let kind = match ext.as_str() {
             "mjs" => FileKind::Module,
-            "js" => FileKind::Script,
+            "js" | "cjs" => FileKind::Script,
             "ts" => FileKind::TypeScript,
-            _ => panic!("tried to make a file with extensions outside of `mjs`, `js`, or `ts`"),
+            // any other extension was explicitly requested to be linted, treat it as a script
+            _ => FileKind::Script,
+        };
+        let (kind, unhinted_kind) = match Self::kind_hint(&source) {
+            Some(hint) if kind != FileKind::TypeScript => (hint, Some(kind)),
+            _ => (kind, None),
         };
         let line_starts = Self::line_starts(&source).collect();
 
@@ -48,32 +105,190 @@ impl File {
             id,
             kind,
             line_starts,
+            transcoded_from: None,
+            editorconfig: EditorConfig::default(),
+            unhinted_kind,
+            parsed: ParseCache::default(),
         }
     }
 
-    pub fn from_string(source: impl ToString, kind: FileKind, name: impl ToString) -> Self {
+    /// Make a new virtual (in-memory) file which has no path on disk.
+    pub fn new_virtual(source: String, name: String, kind: FileKind) -> Self {
         let id = FILE_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
-        let source = source.to_string();
         let line_starts = Self::line_starts(&source).collect();
 
         Self {
             source,
-            name: name.to_string(),
+            name,
             path: None,
             id,
             kind,
             line_starts,
+            transcoded_from: None,
+            editorconfig: EditorConfig::default(),
+            unhinted_kind: None,
+            parsed: ParseCache::default(),
         }
     }
 
+    pub fn from_string(source: impl ToString, kind: FileKind, name: impl ToString) -> Self {
+        Self::new_virtual(source.to_string(), name.to_string(), kind)
+    }
+
+    /// The kind forced by a `// @rslint-module` or `// @rslint-script` comment before any code
+    /// (after the shebang if there is one), which takes priority over the extension of a file.
+    ///
+    /// Block comments such as `/* @rslint-module */` work too, TypeScript files ignore the hint.
+    pub fn kind_hint(source: &str) -> Option<FileKind> {
+        let mut rest = source.trim_start_matches('\u{FEFF}');
+        if rest.starts_with("#!") {
+            rest = rest.find('\n').map_or("", |idx| &rest[idx..]);
+        }
+        loop {
+            rest = rest.trim_start();
+            let (comment, after) = if let Some(line) = rest.strip_prefix("//") {
+                line.split_at(line.find('\n').unwrap_or(line.len()))
+            } else if let Some(block) = rest.strip_prefix("/*") {
+                let end = block.find("*/")?;
+                (&block[..end], &block[end + 2..])
+            } else {
+                return None;
+            };
+            match comment.trim() {
+                "@rslint-module" => return Some(FileKind::Module),
+                "@rslint-script" => return Some(FileKind::Script),
+                _ => rest = after,
+            }
+        }
+    }
+
+    /// Replace the source code of this file, recomputing the line starts and clearing the cached parse.
+    ///
+    /// The kind of a concrete file is detected again, so adding a kind hint applies it and removing
+    /// the hint restores the kind the file had without it.
     pub fn update_src(&mut self, new: String) {
         self.line_starts = Self::line_starts(&new).collect();
         self.source = new;
+        self.parsed = ParseCache::default();
+        self.apply_kind_hint();
     }
 
-    // TODO: Needs to work correctly for \u2028, \u2029, and \r line endings
+    /// Replace the source code of this file like [`File::update_src`], but only splice in the range
+    /// which differs from the current source with [`File::apply_edit`], so a small change to a large
+    /// file does not recompute every line start. Returns whether the source changed.
+    pub fn update_src_incrementally(&mut self, new: &str) -> bool {
+        if self.source == new {
+            return false;
+        }
+        let (old, len) = (self.source.as_bytes(), self.source.len());
+        let mut prefix = old
+            .iter()
+            .zip(new.as_bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !self.source.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let mut suffix = old
+            .iter()
+            .rev()
+            .zip(new.as_bytes().iter().rev())
+            .take(len.min(new.len()) - prefix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !self.source.is_char_boundary(len - suffix)
+            || !new.is_char_boundary(new.len() - suffix)
+        {
+            suffix -= 1;
+        }
+        self.apply_edit(prefix..len - suffix, &new[prefix..new.len() - suffix]);
+        true
+    }
+
+    // a hint takes priority over the extension of a concrete file, but not over `.ts`
+    fn apply_kind_hint(&mut self) {
+        match Self::kind_hint(&self.source) {
+            Some(hint) if self.path.is_some() && self.kind != FileKind::TypeScript => {
+                self.unhinted_kind.get_or_insert(self.kind);
+                self.kind = hint;
+            }
+            Some(_) => {}
+            None => {
+                if let Some(kind) = self.unhinted_kind.take() {
+                    self.kind = kind;
+                }
+            }
+        }
+    }
+
+    /// Compute the byte index of the start of every line in `source`.
+    ///
+    /// `\n`, `\r\n`, `\r`, `\u{2028}`, and `\u{2029}` are all treated as line terminators.
     pub fn line_starts(source: &str) -> impl Iterator<Item = usize> + '_ {
-        std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        std::iter::once(0).chain(
+            source
+                .char_indices()
+                .filter_map(move |(idx, c)| line_terminator_end(source, idx, c)),
+        )
+    }
+
+    /// Replace the text in `range` with `replacement`.
+    ///
+    /// Unlike [`File::update_src`] this only recomputes the line starts inside of the edited range,
+    /// the line starts after it are simply shifted. This also clears the cached parse and applies
+    /// the kind hint of the new source like [`File::update_src`].
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or not on char boundaries.
+    pub fn apply_edit(&mut self, range: Range<usize>, replacement: &str) {
+        self.source.replace_range(range.clone(), replacement);
+        self.parsed = ParseCache::default();
+
+        // a `\r` before the edit may now be (or no longer be) followed by a `\n`
+        let scan_start = if range.start > 0 && self.source.as_bytes()[range.start - 1] == b'\r' {
+            range.start - 1
+        } else {
+            range.start
+        };
+        let scan_end = range.start + replacement.len();
+        let source = &self.source;
+        let new_starts = source[scan_start..scan_end]
+            .char_indices()
+            .filter_map(|(idx, c)| line_terminator_end(source, scan_start + idx, c))
+            .collect::<Vec<_>>();
+
+        let head = self
+            .line_starts
+            .partition_point(|start| *start <= scan_start);
+        let tail = self
+            .line_starts
+            .partition_point(|start| *start <= range.end);
+        for start in &mut self.line_starts[tail..] {
+            *start = *start - range.end + scan_end;
+        }
+        self.line_starts.splice(head..tail, new_starts);
+        self.apply_kind_hint();
+    }
+
+    /// The byte index of the start of every line, which is useful for converting many indices
+    /// at once. [`File::line_starts`] computes them for any source.
+    pub fn line_starts_slice(&self) -> &[usize] {
+        &self.line_starts
+    }
+
+    /// The number of lines in this file, an empty file has a single (empty) line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The length of the source code of this file in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.source.len()
+    }
+
+    /// Whether the source code of this file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.source.is_empty()
     }
 
     pub fn line_start(&self, line_index: usize) -> Option<usize> {
@@ -93,9 +308,96 @@ impl File {
         }
     }
 
+    /// Convert a line and a column counted in bytes to a byte index.
+    ///
+    /// A column past the end of the line is clamped to the end of the line (before its terminator),
+    /// and a column inside of a character resolves to the start of that character.
     pub fn line_col_to_index(&self, line: usize, column: usize) -> Option<usize> {
-        let start = self.line_start(line)?;
-        Some(start + column)
+        let range = self.line_range(line)?;
+        let text = &self.source[range.clone()];
+        let len = text
+            .find(['\n', '\r', '\u{2028}', '\u{2029}'])
+            .unwrap_or(text.len());
+        let mut idx = column.min(len);
+        while !text.is_char_boundary(idx) {
+            idx -= 1;
+        }
+        Some(range.start + idx)
+    }
+
+    /// Convert a line and a column counted in UTF-16 code units (as used by LSP) to a byte index.
+    ///
+    /// A column inside of a surrogate pair resolves to the start of that character, and a column
+    /// past the end of the line is clamped to the end of the line (before its terminator).
+    pub fn line_col_to_index_utf16(&self, line: usize, utf16_col: usize) -> Option<usize> {
+        let range = self.line_range(line)?;
+        let mut units = 0;
+        for (idx, c) in self.source[range.clone()].char_indices() {
+            if units + c.len_utf16() > utf16_col
+                || matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+            {
+                return Some(range.start + idx);
+            }
+            units += c.len_utf16();
+        }
+        Some(range.end)
+    }
+
+    /// Get the column of a byte index counted in UTF-16 code units (as used by LSP).
+    pub fn index_to_utf16_col(&self, byte_index: usize) -> usize {
+        let byte_index = byte_index.min(self.source.len());
+        let start = self.line_starts[self.line_index(byte_index)];
+        self.source[start..]
+            .char_indices()
+            .take_while(|(idx, _)| start + idx < byte_index)
+            .map(|(_, c)| c.len_utf16())
+            .sum()
+    }
+
+    /// Convert a byte range into 0-based `(line, column)` pairs for its start and end, the
+    /// columns are counted in bytes from the start of the line.
+    ///
+    /// Indices past the end of the file are clamped to the end of the file.
+    pub fn byte_range_to_span(&self, range: Range<usize>) -> ((usize, usize), (usize, usize)) {
+        let span = self.span_of(range);
+        (
+            (span.start.line, span.start.column),
+            (span.end.line, span.end.column),
+        )
+    }
+
+    /// Get the position of a byte index, an index past the end of the file is clamped to the end
+    /// of the file.
+    pub fn position_at(&self, byte_index: usize) -> Position {
+        let byte_index = byte_index.min(self.source.len());
+        let line = self.line_index(byte_index);
+        Position::new(line, byte_index - self.line_starts[line])
+    }
+
+    /// Get the positions of the start and end of a byte range, see [`File::position_at`].
+    pub fn span_of(&self, range: Range<usize>) -> PositionRange {
+        PositionRange {
+            start: self.position_at(range.start),
+            end: self.position_at(range.end),
+        }
+    }
+
+    /// Get the byte index of a position, or `None` if the line does not exist, the column is
+    /// past the end of the line, or the column is not on a char boundary.
+    pub fn byte_at(&self, position: Position) -> Option<usize> {
+        let range = self.line_range(position.line)?;
+        let index = range.start.checked_add(position.column)?;
+        if index <= range.end && self.source.is_char_boundary(index) {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Get the source text covered by `range`, or `None` if the range is out of bounds
+    /// or does not lie on char boundaries, instead of panicking like indexing the source does.
+    pub fn text_at(&self, range: Range<usize>) -> Option<&str> {
+        self.source.get(range)
     }
 
     pub fn line_range(&self, line_index: usize) -> Option<Range<usize>> {
@@ -105,13 +407,449 @@ impl File {
         Some(line_start..next_line_start)
     }
 
+    /// Parse this file, or get the cached result if the file was already parsed.
+    ///
+    /// This gives access to both the syntax tree and the errors without cloning either.
+    pub fn parse_result(&self) -> &Parse<()> {
+        self.parsed
+            .0
+            .get_or_init(|| parse_with_syntax(&self.source, self.id, self.kind.into()))
+    }
+
+    /// Drop the cached result of parsing this file to free the memory of its syntax tree, the
+    /// file is parsed again the next time it is needed.
+    pub fn clear_parse(&mut self) {
+        self.parsed = ParseCache::default();
+    }
+
     /// Parse this file into a syntax node, ignoring any errors produced. This
+    /// only parses the file the first time it is called, use [`File::parse_with_errors`]
+    /// or [`File::parse_result`] to also get the errors from the same parse.
+    ///
+    /// The tree is lossless even if the file has syntax errors, every whitespace and comment is a
+    /// `WHITESPACE` or `COMMENT` token of the tree, so the text of the tree is always the source.
+    /// See [`SyntaxTokenExt::leading_trivia`](rslint_parser::util::SyntaxTokenExt::leading_trivia)
+    /// for getting the trivia around a token.
     pub fn parse(&self) -> SyntaxNode {
-        parse_with_syntax(&self.source, self.id, self.kind.into()).syntax()
+        self.parse_result().syntax()
     }
 
+    /// Parse this file into a syntax node and the errors produced while parsing.
     pub fn parse_with_errors(&self) -> (Vec<ParserError>, SyntaxNode) {
-        let parse = parse_with_syntax(&self.source, self.id, self.kind.into());
+        let parse = self.parse_result();
         (parse.errors().to_vec(), parse.syntax())
     }
+
+    /// Every syntax error the parser recovered from while parsing this file (such as
+    /// ``expected `')'` but instead found `{` ``), in the order they were found.
+    ///
+    /// The primary label of each error points at the offending token, so they can be rendered
+    /// with an [`Emitter`](rslint_errors::Emitter) over this file since it implements [`Files`].
+    pub fn syntax_errors(&self) -> &[ParserError] {
+        self.parse_result().errors()
+    }
+}
+
+/// A single file is its own file database, it only knows about its own id.
+impl Files for File {
+    fn name(&self, id: FileId) -> Option<&str> {
+        Some(self.name.as_str()).filter(|_| id == self.id)
+    }
+
+    fn source(&self, id: FileId) -> Option<&str> {
+        Some(self.source.as_str()).filter(|_| id == self.id)
+    }
+
+    fn line_index(&self, id: FileId, byte_index: usize) -> Option<usize> {
+        Some(self.line_index(byte_index)).filter(|_| id == self.id)
+    }
+
+    fn line_range(&self, id: FileId, line_index: usize) -> Option<Range<usize>> {
+        if id == self.id {
+            self.line_range(line_index)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get the byte index after `c` (located at `idx`) if it terminates a line.
+fn line_terminator_end(source: &str, idx: usize, c: char) -> Option<usize> {
+    match c {
+        // a `\r` directly followed by `\n` is terminated by the `\n`
+        '\r' if source.as_bytes().get(idx + 1) == Some(&b'\n') => None,
+        '\n' | '\r' | '\u{2028}' | '\u{2029}' => Some(idx + c.len_utf8()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::util::SyntaxTokenExt;
+
+    #[test]
+    fn line_starts_mixed_terminators() {
+        let file = File::from_string("a\nb\r\nc\rd\u{2028}e\u{2029}f", FileKind::Script, "test");
+        assert_eq!(file.line_starts_slice(), [0, 2, 5, 7, 11, 15]);
+    }
+
+    #[test]
+    fn line_index_mixed_terminators() {
+        let file = File::from_string("foo\r\nbar\rbaz\u{2028}qux", FileKind::Script, "test");
+        assert_eq!(file.line_index(0), 0);
+        assert_eq!(file.line_index(4), 0);
+        assert_eq!(file.line_index(5), 1);
+        assert_eq!(file.line_index(9), 2);
+        assert_eq!(file.line_index(15), 3);
+        assert_eq!(
+            &file.source[file.line_col_to_index(1, 1).unwrap()..][..1],
+            "a"
+        );
+        assert_eq!(
+            &file.source[file.line_col_to_index(2, 2).unwrap()..][..1],
+            "z"
+        );
+        assert_eq!(&file.source[file.line_col_to_index(3, 0).unwrap()..], "qux");
+    }
+
+    #[test]
+    fn clamp_columns_to_the_line() {
+        let file = File::from_string("foo\r\nbé\nbaz", FileKind::Script, "test");
+        assert_eq!(file.line_col_to_index(0, 3), Some(3));
+        assert_eq!(file.line_col_to_index(0, 10), Some(3));
+        assert_eq!(file.line_col_to_index(1, 2), Some(6));
+        assert_eq!(file.line_col_to_index(1, 100), Some(8));
+        assert_eq!(file.line_col_to_index(2, 100), Some(12));
+        assert_eq!(file.line_col_to_index(3, 0), None);
+    }
+
+    #[test]
+    fn utf16_columns() {
+        let file = File::from_string("let a = 'é😀';\nb", FileKind::Script, "test");
+        let emoji = file.source.find('😀').unwrap();
+        assert_eq!(file.line_col_to_index_utf16(0, 10), Some(emoji));
+        assert_eq!(file.line_col_to_index_utf16(0, 11), Some(emoji));
+        assert_eq!(file.line_col_to_index_utf16(0, 12), Some(emoji + 4));
+        assert_eq!(
+            file.line_col_to_index_utf16(0, 100),
+            Some(file.source.find('\n').unwrap())
+        );
+        assert_eq!(
+            file.line_col_to_index_utf16(1, 0),
+            Some(file.source.len() - 1)
+        );
+        assert_eq!(file.index_to_utf16_col(emoji), 10);
+        assert_eq!(file.index_to_utf16_col(emoji + 4), 12);
+        assert_eq!(file.index_to_utf16_col(file.source.len()), 1);
+    }
+
+    #[test]
+    fn update_src_invalidates_parse() {
+        let mut file = File::from_string("let a = 5;", FileKind::Script, "test");
+        assert_eq!(file.parse().text(), "let a = 5;");
+        file.update_src("let b = 6;".to_string());
+        assert_eq!(file.parse().text(), "let b = 6;");
+    }
+
+    #[test]
+    fn lossless_trees() {
+        let sources = [
+            (
+                "/** docs */\nfunction a(b /* c */) {\r\n\t return b; // d\n}\n\n",
+                FileKind::Script,
+            ),
+            (
+                "import a from 'a';   \n\n/* b */ export { a };",
+                FileKind::Module,
+            ),
+            (
+                "let a: number = 1; // b\n\u{2028}  type C = { d: string };\n",
+                FileKind::TypeScript,
+            ),
+            ("if (a { /* unclosed\n  let = ;", FileKind::Script),
+            ("   \n// only comments\n", FileKind::Script),
+        ];
+        for (source, kind) in sources.iter() {
+            let file = File::from_string(*source, *kind, "test");
+            let tree = file.parse();
+            assert_eq!(tree.to_string(), *source);
+            let trivia = tree
+                .descendants_with_tokens()
+                .filter_map(|elem| elem.into_token())
+                .filter(|token| token.kind().is_trivia())
+                .map(|token| token.text().to_string())
+                .collect::<String>();
+            assert!(trivia.contains("//") || trivia.contains("/*"));
+        }
+
+        let file = File::from_string(sources[0].0, FileKind::Script, "test");
+        let tree = file.parse();
+        let ret = tree
+            .descendants_with_tokens()
+            .filter_map(|elem| elem.into_token())
+            .find(|token| token.text() == "return")
+            .unwrap();
+        let leading = ret.leading_trivia();
+        assert_eq!(leading.len(), 1);
+        assert_eq!(leading[0].text(), "\r\n\t ");
+        let semicolon = ret
+            .next_token()
+            .unwrap()
+            .next_token()
+            .unwrap()
+            .next_token()
+            .unwrap();
+        let trailing = semicolon
+            .trailing_trivia()
+            .iter()
+            .map(|token| token.text().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(trailing, [" ", "// d", "\n"]);
+    }
+
+    #[test]
+    fn clear_parse() {
+        let mut file = File::from_string("let a = 5;", FileKind::Script, "test");
+        let tree = file.parse();
+        file.clear_parse();
+        assert!(file.parsed.0.get().is_none());
+        assert_eq!(file.parse().to_string(), tree.to_string());
+    }
+
+    #[test]
+    fn line_count_and_len() {
+        let cases = [
+            ("", 1),
+            ("a", 1),
+            ("a\n", 2),
+            ("a\nb", 2),
+            ("a\r\nb\r\nc", 3),
+            ("\n\n\n", 4),
+        ];
+        for (source, lines) in cases.iter() {
+            let file = File::from_string(source, FileKind::Script, "test");
+            assert_eq!(file.line_count(), *lines, "line count of {:?}", source);
+            assert_eq!(file.byte_len(), source.len());
+            assert_eq!(file.is_empty(), source.is_empty());
+        }
+    }
+
+    #[test]
+    fn parse_result_has_errors() {
+        let file = File::from_string("let a = ;", FileKind::Script, "test");
+        let (errors, node) = file.parse_with_errors();
+        assert!(!errors.is_empty());
+        assert_eq!(file.parse_result().errors(), errors.as_slice());
+        assert_eq!(file.parse_result().syntax(), node);
+    }
+
+    #[test]
+    fn report_every_syntax_error() {
+        use rslint_errors::{termcolor::NoColor, Emitter};
+
+        let file = File::from_string("foo(;\nif (a {}\n", FileKind::Script, "test.js");
+        let errors = file.syntax_errors();
+        assert!(errors.len() >= 2, "{:?}", errors);
+        let lines = errors
+            .iter()
+            .map(|err| file.line_index(err.primary.as_ref().unwrap().span.range.start))
+            .collect::<Vec<_>>();
+        assert_eq!(lines.first(), Some(&0));
+        assert_eq!(lines.last(), Some(&1));
+
+        let mut out = NoColor::new(vec![]);
+        for err in errors {
+            Emitter::new(&file).emit_with_writer(err, &mut out).unwrap();
+        }
+        let out = String::from_utf8(out.into_inner()).unwrap();
+        assert!(
+            out.contains("expected `')'` but instead found `{`"),
+            "{}",
+            out
+        );
+        assert!(out.contains("test.js:2:7"), "{}", out);
+    }
+
+    #[test]
+    fn kind_hints() {
+        let cases = [
+            (
+                "// @rslint-module\nimport a from 'a';",
+                Some(FileKind::Module),
+            ),
+            (
+                "#!/usr/bin/env node\n  /* @rslint-script */",
+                Some(FileKind::Script),
+            ),
+            ("// a license\n\n//@rslint-module", Some(FileKind::Module)),
+            ("let a;\n// @rslint-module", None),
+            ("// @rslint-modules", None),
+            ("/* @rslint-module", None),
+        ];
+        for (source, kind) in cases.iter() {
+            assert_eq!(File::kind_hint(source), *kind, "{:?}", source);
+        }
+
+        let file = File::new_concrete("// @rslint-module\nexport {};".into(), "a.js".into());
+        assert_eq!(file.kind, FileKind::Module);
+        let file = File::new_concrete("// @rslint-script\nlet a;".into(), "a.mjs".into());
+        assert_eq!(file.kind, FileKind::Script);
+        let mut file = File::new_concrete("// @rslint-module".into(), "a.ts".into());
+        assert_eq!(file.kind, FileKind::TypeScript);
+        file.update_src("// @rslint-script".into());
+        assert_eq!(file.kind, FileKind::TypeScript);
+    }
+
+    #[test]
+    fn update_src_detects_the_kind_again() {
+        let mut file = File::new_concrete("let a;".into(), "a.js".into());
+        file.update_src("// @rslint-module\nexport {};".into());
+        assert_eq!(file.kind, FileKind::Module);
+        file.update_src("// @rslint-script\nlet a;".into());
+        assert_eq!(file.kind, FileKind::Script);
+        file.update_src("let a;".into());
+        assert_eq!(file.kind, FileKind::Script);
+
+        let mut file = File::new_concrete("// @rslint-module\nexport {};".into(), "a.js".into());
+        file.update_src("let a;".into());
+        assert_eq!(file.kind, FileKind::Script);
+        file.apply_edit(0..0, "/* @rslint-module */ ");
+        assert_eq!(file.kind, FileKind::Module);
+        file.apply_edit(0..21, "");
+        assert_eq!(file.kind, FileKind::Script);
+
+        // the kind set from outside of the file, e.g. by a `package.json`, is kept as well
+        let mut file = File::new_concrete("export {};".into(), "a.js".into());
+        file.kind = FileKind::Module;
+        file.update_src("// @rslint-script\nlet a;".into());
+        assert_eq!(file.kind, FileKind::Script);
+        file.update_src("export {};".into());
+        assert_eq!(file.kind, FileKind::Module);
+
+        let mut file = File::new_virtual("// @rslint-module".into(), "a".into(), FileKind::Script);
+        file.update_src("let a;".into());
+        assert_eq!(file.kind, FileKind::Script);
+    }
+
+    #[test]
+    fn parse_shebang() {
+        let source = "#!/usr/bin/env node\nlet a = 5;";
+        for kind in [FileKind::Script, FileKind::Module].iter() {
+            let file = File::from_string(source, *kind, "test");
+            let (errors, node) = file.parse_with_errors();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let stmt = node.first_child().unwrap();
+            assert_eq!(
+                usize::from(stmt.text_range().start()),
+                source.find("let").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn line_range_of_last_line() {
+        let file = File::from_string("let a;\nlet b = 5;", FileKind::Script, "test");
+        let range = file.line_range(1).unwrap();
+        assert_eq!(range, 7..17);
+        assert_eq!(&file.source[range], "let b = 5;");
+        assert_eq!(file.line_range(2), None);
+
+        let file = File::from_string("let a;\n", FileKind::Script, "test");
+        assert_eq!(file.line_range(0), Some(0..7));
+        assert_eq!(file.line_range(1), Some(7..7));
+    }
+
+    #[test]
+    fn byte_range_to_span() {
+        let file = File::from_string("let a;\r\nlet é = 5;\n", FileKind::Script, "test");
+        assert_eq!(file.byte_range_to_span(0..3), ((0, 0), (0, 3)));
+        assert_eq!(file.byte_range_to_span(4..12), ((0, 4), (1, 4)));
+        assert_eq!(file.byte_range_to_span(14..15), ((1, 6), (1, 7)));
+        assert_eq!(file.byte_range_to_span(15..100), ((1, 7), (2, 0)));
+        assert_eq!(file.byte_range_to_span(100..200), ((2, 0), (2, 0)));
+    }
+
+    #[test]
+    fn positions() {
+        let file = File::from_string("let a;\r\nlet é = 5;\n", FileKind::Script, "test");
+        assert_eq!(file.position_at(0), Position::new(0, 0));
+        assert_eq!(file.position_at(8), Position::new(1, 0));
+        assert_eq!(file.position_at(14), Position::new(1, 6));
+        assert_eq!(file.position_at(100), Position::new(2, 0));
+        assert_eq!(
+            file.span_of(4..12),
+            PositionRange {
+                start: Position::new(0, 4),
+                end: Position::new(1, 4),
+            }
+        );
+
+        for index in (0..=file.source.len()).filter(|idx| file.source.is_char_boundary(*idx)) {
+            assert_eq!(file.byte_at(file.position_at(index)), Some(index));
+        }
+        // inside of `é`
+        assert_eq!(file.byte_at(Position::new(1, 5)), None);
+        assert_eq!(file.byte_at(Position::new(0, 9)), None);
+        assert_eq!(file.byte_at(Position::new(3, 0)), None);
+    }
+
+    #[test]
+    fn text_at() {
+        let file = File::from_string("let é = 5;", FileKind::Script, "test.js");
+        assert_eq!(file.text_at(0..3), Some("let"));
+        assert_eq!(file.text_at(4..6), Some("é"));
+        assert_eq!(file.text_at(4..5), None);
+        assert_eq!(file.text_at(10..20), None);
+    }
+
+    #[test]
+    fn apply_edit_updates_line_starts() {
+        let edits: &[(Range<usize>, &str)] = &[
+            (0..0, "a\nb"),
+            (1..2, "\r"),
+            (2..2, "\n"),
+            (1..3, ""),
+            (0..0, "x\r"),
+            (2..2, "\n\u{2028}  \r\n"),
+            (3..9, "y"),
+            (4..6, "\u{2029}z\r"),
+            (9..9, "\n"),
+        ];
+        let mut file = File::from_string("", FileKind::Script, "test");
+        for (range, replacement) in edits {
+            file.apply_edit(range.clone(), replacement);
+            assert_eq!(
+                file.line_starts,
+                File::line_starts(&file.source).collect::<Vec<_>>(),
+                "line starts of {:?}",
+                file.source
+            );
+        }
+    }
+
+    #[test]
+    fn update_src_incrementally_matches_update_src() {
+        let sources = [
+            "let a;\nlet b;\n",
+            "let a;\r\nlet b;\n",
+            "let a;\r\nlet café;\n",
+            "let a;\r\nlet cafè;\n",
+            "// @rslint-module\nlet cafè;\n",
+            "",
+            "\n\n\u{2028}",
+        ];
+        let mut file = File::new_concrete(String::new(), PathBuf::from("a.js"));
+        for source in sources.iter() {
+            let mut expected = file.clone();
+            expected.update_src(source.to_string());
+            let changed = file.source != *source;
+            assert_eq!(file.update_src_incrementally(source), changed);
+            assert_eq!(file.source, expected.source);
+            assert_eq!(file.line_starts, expected.line_starts, "{:?}", source);
+            assert_eq!(file.kind, expected.kind);
+        }
+        assert!(!file.update_src_incrementally("\n\n\u{2028}"));
+    }
 }
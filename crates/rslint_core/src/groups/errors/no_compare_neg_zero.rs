@@ -33,7 +33,7 @@ declare_lint! {
     #[derive(Default)]
     NoCompareNegZero,
     errors,
-    tags(Recommended),
+    tags(Recommended, Fixable),
     "no-compare-neg-zero"
 }
 
@@ -25,7 +25,7 @@ declare_lint! {
     #[derive(Default)]
     NoExtraSemi,
     errors,
-    tags(Recommended),
+    tags(Recommended, Fixable),
     "no-extra-semi"
 }
 
@@ -48,7 +48,7 @@ impl CstRule for NoExtraSemi {
                 .parent()
                 .map_or(true, |parent| !ALLOWED.contains(&parent.kind()))
         {
-            ctx.fix().delete(node);
+            ctx.fix().fix_type(FixType::Suggestion).delete(node);
             let err = ctx
                 .err(self.name(), "unnecessary semicolon")
                 .primary(node, "")
@@ -25,7 +25,7 @@ declare_lint! {
     #[derive(Default)]
     NoNewSymbol,
     errors,
-    tags(Recommended),
+    tags(Recommended, Fixable),
     "no-new-symbol",
 }
 
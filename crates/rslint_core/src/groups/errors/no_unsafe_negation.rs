@@ -29,7 +29,7 @@ declare_lint! {
     #[derive(Default)]
     NoUnsafeNegation,
     errors,
-    tags(Recommended),
+    tags(Recommended, Fixable),
     "no-unsafe-negation"
 }
 
@@ -55,6 +55,7 @@ declare_lint! {
     #[derive(Default)]
     SimplifyRegex,
     regex,
+    tags(Fixable),
     "simplify-regex"
 }
 
@@ -82,7 +83,9 @@ impl CstRule for SimplifyRegex {
 
         if !err.footers.is_empty() {
             let string = regex.node.to_string();
-            ctx.fix().replace(range, &string);
+            ctx.fix()
+                .fix_type(FixType::Suggestion)
+                .replace(range, &string);
             err.title.push_str(&format!(" to `{}`", string));
             ctx.add_err(err);
         }
@@ -51,6 +51,7 @@ declare_lint! {
     #[derive(rslint_macros::Mergeable)]
     BlockSpacing,
     style,
+    tags(Fixable),
     "block-spacing",
     /// The style of spacing, either "always" (default) to require one or more spaces, or
     /// "never" to disallow spaces
@@ -94,6 +95,7 @@ impl CstRule for BlockSpacing {
             ctx.add_err(err);
             let fix = ctx
                 .fix()
+                .fix_type(FixType::Layout)
                 .delete_multiple(open_token.trailing_whitespace(false));
             if self.style == "always" {
                 fix.insert_after(open_token, " ");
@@ -108,6 +110,7 @@ impl CstRule for BlockSpacing {
             ctx.add_err(err);
             let fix = ctx
                 .fix()
+                .fix_type(FixType::Layout)
                 .delete_multiple(close_token.leading_whitespace(false));
             if self.style == "always" {
                 fix.insert_before(close_token, " ");
@@ -29,7 +29,9 @@
 // FIXME: Workaround for https://github.com/GREsau/schemars/pull/65
 #![allow(clippy::field_reassign_with_default)]
 
+mod editorconfig;
 mod file;
+mod registry;
 mod rule;
 mod store;
 mod testing;
@@ -37,25 +39,33 @@ mod testing;
 pub mod autofix;
 pub mod directives;
 pub mod groups;
+#[cfg(feature = "plugins")]
+pub mod plugin;
 pub mod rule_prelude;
 pub mod util;
 
 pub use self::{
-    file::File,
+    editorconfig::{EditorConfig, EndOfLine, IndentStyle},
+    file::{File, Position, PositionRange},
+    registry::{RuleMeta, RuleRegistry},
     rule::{CstRule, Inferable, Outcome, Rule, RuleCtx, RuleLevel, RuleResult, Tag},
     store::CstRuleStore,
 };
 pub use rslint_errors::{Diagnostic, Severity, Span};
 
 pub use crate::directives::{
-    apply_top_level_directives, skip_node, Directive, DirectiveError, DirectiveErrorKind,
-    DirectiveParser,
+    apply_suppression_directives, apply_top_level_directives, skip_node, Directive, DirectiveError,
+    DirectiveErrorKind, DirectiveParser,
 };
 
 use dyn_clone::clone_box;
 use rslint_parser::{util::SyntaxNodeExt, SyntaxKind, SyntaxNode};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The version of the crate, which changes whenever the rules may behave differently.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// The result of linting a file.
 // TODO: A lot of this stuff can be shoved behind a "linter options" struct
@@ -72,6 +82,14 @@ pub struct LintResult<'s> {
     pub file_id: usize,
     pub verbose: bool,
     pub fixed_code: Option<String>,
+    /// The rules whose fixes likely gave a file without syntax errors syntax errors when they were
+    /// applied by [`recursively_apply_fixes`](autofix::recursively_apply_fixes), sorted by name.
+    pub breaking_fixes: Vec<&'static str>,
+    /// The time spent running each rule on the file.
+    pub timings: HashMap<&'static str, Duration>,
+    /// The rules which were not run because they do not run on the kind of the file, see
+    /// [`Rule::runs_on`].
+    pub skipped: Vec<&'static str>,
 }
 
 impl LintResult<'_> {
@@ -94,6 +112,12 @@ impl LintResult<'_> {
         self.diagnostics().into()
     }
 
+    /// Replace the syntax tree of the result with an empty tree, so the memory of the tree is
+    /// freed once nothing else refers to it. Fixes parse the file again so they still work.
+    pub fn release_tree(&mut self) {
+        self.parsed = rslint_parser::parse_text("", self.file_id).syntax();
+    }
+
     /// Attempt to automatically fix any fixable issues and return the fixed code.
     ///
     /// This will not run if there are syntax errors unless `dirty` is set to true.
@@ -141,23 +165,33 @@ pub(crate) fn lint_file_inner<'s>(
     let src: Arc<str> = Arc::from(node.to_string());
 
     // FIXME: Replace with thread pool
-    let results = new_store
+    let mut timings = HashMap::new();
+    let (rules, skipped): (Vec<_>, Vec<_>) = new_store
         .rules
+        .into_iter()
+        .partition(|rule| rule.runs_on(file.kind));
+    let mut results = rules
         .into_iter()
         .map(|rule| {
-            (
-                rule.name(),
-                run_rule(
-                    &*rule,
-                    file.id,
-                    node.clone(),
-                    verbose,
-                    &directives,
-                    src.clone(),
-                ),
-            )
+            let start = Instant::now();
+            let result = run_rule_with_editorconfig(
+                &*rule,
+                file.id,
+                node.clone(),
+                verbose,
+                src.clone(),
+                file.editorconfig,
+            );
+            timings.insert(rule.name(), start.elapsed());
+            (rule.name(), result)
         })
         .collect();
+    apply_suppression_directives(
+        directives.as_slice(),
+        &mut results,
+        &mut directive_diagnostics,
+        file.id,
+    );
 
     LintResult {
         parser_diagnostics,
@@ -168,10 +202,16 @@ pub(crate) fn lint_file_inner<'s>(
         file_id: file.id,
         verbose,
         fixed_code: None,
+        breaking_fixes: vec![],
+        timings,
+        skipped: skipped.iter().map(|rule| rule.name()).collect(),
     }
 }
 
-/// Run a single run on an entire parsed file.
+/// Run a single run on an entire parsed file, skipping the nodes ignored by `directives`.
+///
+/// Other directives are not applied, [`lint_file`] applies every directive to the results of
+/// the rules with [`apply_suppression_directives`] instead.
 ///
 /// # Panics
 /// Panics if `root`'s kind is not `SCRIPT` or `MODULE`
@@ -182,6 +222,67 @@ pub fn run_rule(
     verbose: bool,
     directives: &[Directive],
     src: Arc<str>,
+) -> RuleResult {
+    let result = check_rule(
+        rule,
+        file_id,
+        root,
+        verbose,
+        directives,
+        src,
+        EditorConfig::default(),
+    );
+    tag_diagnostics(rule, result)
+}
+
+/// Run a single rule on an entire parsed file with the `.editorconfig` properties of the file.
+///
+/// # Panics
+/// Panics if `root`'s kind is not `SCRIPT` or `MODULE`
+pub fn run_rule_with_editorconfig(
+    rule: &dyn CstRule,
+    file_id: usize,
+    root: SyntaxNode,
+    verbose: bool,
+    src: Arc<str>,
+    editorconfig: EditorConfig,
+) -> RuleResult {
+    let result = run_rule_untagged(rule, file_id, root, verbose, src, editorconfig);
+    tag_diagnostics(rule, result)
+}
+
+// the code of a rule diagnostic is the name of the rule, which filtering and grouping rely on
+fn tag_diagnostics(rule: &dyn CstRule, mut result: RuleResult) -> RuleResult {
+    for diagnostic in result.diagnostics.iter_mut() {
+        diagnostic
+            .code
+            .get_or_insert_with(|| rule.name().to_string());
+    }
+    result
+}
+
+/// Like [`run_rule_with_editorconfig`], but without tagging the diagnostics which have no code
+/// with the name of the rule, which lets the rule tests reject rules that do not tag them.
+#[doc(hidden)]
+pub fn run_rule_untagged(
+    rule: &dyn CstRule,
+    file_id: usize,
+    root: SyntaxNode,
+    verbose: bool,
+    src: Arc<str>,
+    editorconfig: EditorConfig,
+) -> RuleResult {
+    check_rule(rule, file_id, root, verbose, &[], src, editorconfig)
+}
+
+fn check_rule(
+    rule: &dyn CstRule,
+    file_id: usize,
+    root: SyntaxNode,
+    verbose: bool,
+    directives: &[Directive],
+    src: Arc<str>,
+    editorconfig: EditorConfig,
 ) -> RuleResult {
     assert!(root.kind() == SyntaxKind::SCRIPT || root.kind() == SyntaxKind::MODULE);
     let mut ctx = RuleCtx {
@@ -190,6 +291,7 @@ pub fn run_rule(
         diagnostics: vec![],
         fixer: None,
         src,
+        editorconfig,
     };
 
     rule.check_root(&root, &mut ctx);
@@ -208,6 +310,7 @@ pub fn run_rule(
         };
         true
     });
+
     RuleResult::new(ctx.diagnostics, ctx.fixer)
 }
 
@@ -0,0 +1,332 @@
+//! Rules compiled to WebAssembly which are loaded at runtime, see [`WasmRule`].
+//!
+//! # ABI
+//!
+//! A plugin is a WebAssembly module (or its text format) which exports:
+//! - `memory`, the memory the host passes the file through.
+//! - `rslint_alloc(len: i32) -> i32`, which returns a pointer to `len` bytes of `memory` which the
+//!   host can write to.
+//! - `rslint_check(source: i32, source_len: i32, tree: i32, tree_len: i32)`, which checks a file.
+//!   `source` is its UTF-8 source code, and `tree` is the JSON array of the nodes of its syntax tree
+//!   in preorder, each being an object like `{"kind": "IF_STMT", "start": 0, "end": 9, "parent": 0}`
+//!   with the byte range of the node without its surrounding whitespace and the index of its parent.
+//!
+//! And may import from the `rslint` module:
+//! - `report(start: i32, end: i32, message: i32, message_len: i32)`, which reports a diagnostic
+//!   at the byte range `start..end` of the source with the UTF-8 message stored at `message`.
+//!
+//! The name of the rule is the name of the plugin file without its extension. A new instance of the
+//! plugin is made for every file, so plugins never keep state between files.
+
+use crate::util::hash_source;
+
+use crate::{CstRule, Rule, RuleCtx};
+use once_cell::sync::Lazy;
+use rslint_parser::{SyntaxNode, SyntaxNodeExt, WalkEvent};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use wasmi::{Caller, Engine, Extern, Linker, Module, Store};
+
+/// A diagnostic reported by a plugin for a byte range of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Report {
+    range: Range<usize>,
+    message: String,
+}
+
+/// A rule which runs a WebAssembly plugin on the root of every file.
+///
+/// The diagnostics of the plugin are errors with the name of the rule as their code, so they are
+/// mapped to levels and ignored by directives like the diagnostics of any other rule.
+///
+/// Rules are serialized as the path of their plugin, which is loaded again when they are deserialized.
+#[derive(Clone)]
+pub struct WasmRule {
+    name: &'static str,
+    path: PathBuf,
+    /// The hash of the plugin file, so the cache is invalidated if a plugin is rebuilt.
+    hash: u64,
+    engine: Engine,
+    module: Arc<Module>,
+}
+
+impl WasmRule {
+    /// Load and compile the plugin at `path`, which is either a binary or a text format module.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .filter(|stem| !stem.is_empty())
+            .ok_or_else(|| "the name of a plugin file must be valid UTF-8".to_string())?;
+        let bytes = fs::read(path).map_err(|err| err.to_string())?;
+        let wasm = wat::parse_bytes(&bytes).map_err(|err| err.to_string())?;
+        let engine = Engine::default();
+        let module = Module::new(&engine, &wasm[..]).map_err(|err| err.to_string())?;
+
+        Ok(Self {
+            name: intern(name),
+            path: path.to_path_buf(),
+            hash: hash_source(&bytes),
+            engine,
+            module: Arc::new(module),
+        })
+    }
+
+    /// The path the plugin was loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Run the plugin on a file, returning the diagnostics it reported.
+    fn run(&self, source: &str, tree: &str) -> Result<Vec<Report>, String> {
+        let mut store = Store::new(&self.engine, Vec::<Report>::new());
+        let mut linker = Linker::new(&self.engine);
+        linker
+            .func_wrap(
+                "rslint",
+                "report",
+                |mut caller: Caller<'_, Vec<Report>>, start: i32, end: i32, ptr: i32, len: i32| {
+                    let mut message = vec![0; usize::try_from(len).unwrap_or_default()];
+                    if let Some(memory) = caller.get_export("memory").and_then(Extern::into_memory)
+                    {
+                        if memory
+                            .read(&caller, ptr as u32 as usize, &mut message)
+                            .is_err()
+                        {
+                            message.clear();
+                        }
+                    }
+                    caller.data_mut().push(Report {
+                        range: start as u32 as usize..end as u32 as usize,
+                        message: String::from_utf8_lossy(&message).into_owned(),
+                    });
+                },
+            )
+            .map_err(|err| err.to_string())?;
+        let instance = linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|err| err.to_string())?;
+
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| "the plugin does not export `memory`".to_string())?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "rslint_alloc")
+            .map_err(|err| err.to_string())?;
+        let check = instance
+            .get_typed_func::<(i32, i32, i32, i32), ()>(&store, "rslint_check")
+            .map_err(|err| err.to_string())?;
+        let pass = |store: &mut Store<_>, bytes: &[u8]| {
+            let len = i32::try_from(bytes.len()).map_err(|err| err.to_string())?;
+            let ptr = alloc
+                .call(&mut *store, len)
+                .map_err(|err| err.to_string())?;
+            memory
+                .write(&mut *store, ptr as u32 as usize, bytes)
+                .map_err(|err| err.to_string())?;
+            Ok::<_, String>((ptr, len))
+        };
+        let (source_ptr, source_len) = pass(&mut store, source.as_bytes())?;
+        let (tree_ptr, tree_len) = pass(&mut store, tree.as_bytes())?;
+        check
+            .call(&mut store, (source_ptr, source_len, tree_ptr, tree_len))
+            .map_err(|err| err.to_string())?;
+        Ok(store.into_data())
+    }
+}
+
+impl fmt::Debug for WasmRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmRule")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
+
+impl Serialize for WasmRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.path.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WasmRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = PathBuf::deserialize(deserializer)?;
+        Self::load(&path).map_err(D::Error::custom)
+    }
+}
+
+impl Rule for WasmRule {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn group(&self) -> &'static str {
+        "plugins"
+    }
+}
+
+#[typetag::serde]
+impl CstRule for WasmRule {
+    fn check_root(&self, root: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        let src = ctx.src.clone();
+        match self.run(&src, &tree_to_json(root).to_string()) {
+            Ok(reports) => {
+                for report in reports {
+                    let range = char_range(&src, report.range);
+                    let err = ctx.err(self.name, report.message).primary(range, "");
+                    ctx.add_err(err);
+                }
+            }
+            Err(err) => {
+                let err = ctx.err(self.name, format!("the plugin failed: {}", err));
+                ctx.add_err(err);
+            }
+        }
+        None
+    }
+}
+
+/// Get a name which outlives the rule because of [`Rule::name`], the names are only leaked the
+/// first time they are used since the same plugins are usually loaded many times.
+fn intern(name: &str) -> &'static str {
+    static NAMES: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+    let mut names = NAMES.lock().unwrap_or_else(|err| err.into_inner());
+    match names.get(name) {
+        Some(interned) => interned,
+        None => {
+            let interned = Box::leak(name.to_string().into_boxed_str());
+            names.insert(interned);
+            interned
+        }
+    }
+}
+
+/// The nodes of a tree in preorder, with their kind, trimmed range, and the index of their parent.
+fn tree_to_json(root: &SyntaxNode) -> Value {
+    let mut nodes = vec![];
+    let mut parents = vec![];
+    for event in root.preorder() {
+        match event {
+            WalkEvent::Enter(node) => {
+                let range = node.trimmed_range();
+                nodes.push(json!({
+                    "kind": format!("{:?}", node.kind()),
+                    "start": usize::from(range.start()),
+                    "end": usize::from(range.end()),
+                    "parent": parents.last(),
+                }));
+                parents.push(nodes.len() - 1);
+            }
+            WalkEvent::Leave(_) => {
+                parents.pop();
+            }
+        }
+    }
+    Value::Array(nodes)
+}
+
+/// Shrink a range reported by a plugin to the source and to character boundaries.
+fn char_range(src: &str, range: Range<usize>) -> Range<usize> {
+    let floor = |mut idx: usize| {
+        idx = idx.min(src.len());
+        while !src.is_char_boundary(idx) {
+            idx -= 1;
+        }
+        idx
+    };
+    let end = floor(range.end);
+    floor(range.start.min(end))..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lint_file, CstRuleStore, File};
+    use rslint_parser::{parse_text, FileKind};
+
+    fn lint(rule: WasmRule, source: &str) -> Vec<(Option<String>, String, Option<Range<usize>>)> {
+        let store = CstRuleStore {
+            rules: vec![Box::new(rule)],
+        };
+        let file = File::from_string(source, FileKind::Script, "test.js");
+        lint_file(&file, &store, false)
+            .diagnostics()
+            .map(|d| {
+                let range = d.primary.as_ref().map(|primary| primary.span.range.clone());
+                (d.code.clone(), d.title.clone(), range)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn example_plugin() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("plugins/no-tabs.wat");
+        let rule = WasmRule::load(&path).unwrap();
+        assert_eq!(rule.name(), "no-tabs");
+
+        let message = "unexpected tab character".to_string();
+        assert_eq!(
+            lint(rule, "let a;\n\tlet b;\t"),
+            [
+                (Some("no-tabs".to_string()), message.clone(), Some(7..8)),
+                (Some("no-tabs".to_string()), message, Some(14..15)),
+            ]
+        );
+    }
+
+    #[test]
+    fn failing_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rslint-failing-plugin.wat");
+        fs::write(
+            &path,
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "rslint_alloc") (param i32) (result i32) i32.const 0)
+                (func (export "rslint_check") (param i32 i32 i32 i32) unreachable))"#,
+        )
+        .unwrap();
+        let rule = WasmRule::load(&path);
+
+        let diagnostics = lint(rule.unwrap(), "let a;");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].1.starts_with("the plugin failed"));
+        assert!(WasmRule::load(Path::new("missing.wasm")).is_err());
+    }
+
+    #[test]
+    fn serialize_trees() {
+        let root = parse_text("if (a) {}", 0).syntax();
+        let tree = tree_to_json(&root);
+        assert_eq!(
+            tree[0],
+            json!({ "kind": "SCRIPT", "start": 0, "end": 9, "parent": null })
+        );
+        assert_eq!(
+            tree[1],
+            json!({ "kind": "IF_STMT", "start": 0, "end": 9, "parent": 0 })
+        );
+        assert_eq!(tree[2]["parent"], 1);
+        assert_eq!(char_range("é", 1..5), 0..2);
+    }
+
+    #[test]
+    fn intern_names() {
+        let name = intern("plugin-name");
+        let owned = String::from("plugin-name");
+        assert!(std::ptr::eq(name, intern(&owned)));
+        assert_eq!(name, "plugin-name");
+    }
+}
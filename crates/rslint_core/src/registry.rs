@@ -0,0 +1,107 @@
+//! The metadata of every built in rule, see [`RuleRegistry`].
+
+use crate::{CstRuleStore, Rule, RuleLevel, Tag};
+
+/// The metadata of a rule, which is declared along with the rule in [`declare_lint`](crate::declare_lint).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleMeta {
+    /// The unique, kebab-case name of the rule.
+    pub name: &'static str,
+    /// The name of the group the rule belongs to.
+    pub group: &'static str,
+    /// The docs of the rule, an empty string if it has none.
+    pub docs: &'static str,
+    /// The tags of the rule, such as whether it is recommended.
+    pub tags: &'static [Tag],
+}
+
+impl RuleMeta {
+    /// Get the metadata of a rule.
+    pub fn of(rule: &dyn Rule) -> Self {
+        Self {
+            name: rule.name(),
+            group: rule.group(),
+            docs: rule.docs(),
+            tags: rule.tags(),
+        }
+    }
+
+    /// Whether the rule runs if no config is provided.
+    pub fn recommended(&self) -> bool {
+        self.tags.contains(&Tag::Recommended)
+    }
+
+    /// Whether the rule can fix some of its diagnostics with `--fix`.
+    pub fn fixable(&self) -> bool {
+        self.tags.contains(&Tag::Fixable)
+    }
+
+    /// The level of the rule if no config is provided, recommended rules are errors and the
+    /// other rules do not run.
+    pub fn default_level(&self) -> Option<RuleLevel> {
+        self.recommended().then_some(RuleLevel::Error)
+    }
+}
+
+/// A registry of the metadata of the built in rules, in the order of [`CstRuleStore::builtins`].
+///
+/// # Examples
+/// ```
+/// use rslint_core::RuleRegistry;
+///
+/// let registry = RuleRegistry::builtins();
+/// assert_eq!(registry.get("no-empty").unwrap().group, "errors");
+/// assert!(registry.get("no-such-rule").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleRegistry {
+    rules: Vec<RuleMeta>,
+}
+
+impl RuleRegistry {
+    /// The registry of every rule of every group.
+    pub fn builtins() -> Self {
+        let rules = CstRuleStore::new()
+            .builtins()
+            .rules
+            .iter()
+            .map(|rule| RuleMeta::of(&**rule))
+            .collect();
+        Self { rules }
+    }
+
+    /// The metadata of every rule.
+    pub fn all_rules(&self) -> Vec<RuleMeta> {
+        self.rules.clone()
+    }
+
+    /// Get the metadata of a rule by its name.
+    pub fn get(&self, name: &str) -> Option<RuleMeta> {
+        self.rules.iter().find(|rule| rule.name == name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn builtin_rules() {
+        let registry = RuleRegistry::builtins();
+        let rules = registry.all_rules();
+        let names = rules.iter().map(|rule| rule.name).collect::<HashSet<_>>();
+        assert_eq!(names.len(), rules.len());
+
+        let no_extra_semi = registry.get("no-extra-semi").unwrap();
+        assert_eq!(no_extra_semi.group, "errors");
+        assert!(no_extra_semi.fixable());
+        assert_eq!(no_extra_semi.default_level(), Some(RuleLevel::Error));
+
+        let block_spacing = registry.get("block-spacing").unwrap();
+        assert!(block_spacing.fixable());
+        assert_eq!(block_spacing.default_level(), None);
+        assert!(!registry.get("no-debugger").unwrap().fixable());
+        assert!(!block_spacing.docs.is_empty());
+    }
+}
@@ -4,10 +4,10 @@
 #![allow(unused_variables, unused_imports)]
 
 use crate::autofix::Fixer;
-use crate::Diagnostic;
+use crate::{Diagnostic, EditorConfig};
 use dyn_clone::DynClone;
 use rslint_errors::Severity;
-use rslint_parser::{SyntaxNode, SyntaxNodeExt, SyntaxToken};
+use rslint_parser::{FileKind, SyntaxNode, SyntaxNodeExt, SyntaxToken};
 use rslint_text_edit::apply_indels;
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
@@ -26,6 +26,8 @@ pub enum Tag {
     OnlyJS,
     /// This rule should only run on TypeScript files.
     OnlyTS,
+    /// This rule can automatically fix some of its diagnostics.
+    Fixable,
 }
 
 /// The main type of rule run by the runner. The rule takes individual
@@ -52,6 +54,30 @@ pub enum Tag {
 /// - Do not unwrap pieces of an AST node (sometimes it is ok because they are guaranteed to be there), since that will cause panics
 /// with error recovery.
 /// - Do not use node or string coloring outside of diagnostic notes, it messes with termcolor and ends up looking horrible.
+///
+/// # Trivia
+/// The tree is lossless, whitespace and comments are `WHITESPACE` and `COMMENT` tokens of the tree (trivia) like any
+/// other token, and [`check_token`](CstRule::check_token) is called for them too. Trivia are usually siblings of the
+/// tokens they surround, but the leading comments of a statement are part of its node, so use
+/// [`leading_trivia`](rslint_parser::util::SyntaxTokenExt::leading_trivia) and
+/// [`trailing_trivia`](rslint_parser::util::SyntaxTokenExt::trailing_trivia) instead of the siblings of a token.
+/// For example, a rule requiring a blank line before every block comment:
+///
+/// ```ignore
+/// fn check_token(&self, token: &SyntaxToken, ctx: &mut RuleCtx) -> Option<()> {
+///     if token.kind() != SyntaxKind::COMMENT || token.text().starts_with("//") {
+///         return None;
+///     }
+///     let whitespace = token.leading_trivia().pop().filter(|t| t.kind() == SyntaxKind::WHITESPACE);
+///     let line_breaks = whitespace.map_or(0, |t| t.text().matches('\n').count());
+///     // comments at the start of the file or after a blank line are fine
+///     if token.prev_token().is_some() && line_breaks == 1 {
+///         let err = ctx.err(self.name(), "expected a blank line before this comment").primary(token, "");
+///         ctx.add_err(err);
+///     }
+///     None
+/// }
+/// ```
 #[typetag::serde]
 pub trait CstRule: Rule {
     /// Check an individual node in the syntax tree.
@@ -105,6 +131,17 @@ pub trait Rule: Debug + DynClone + Send + Sync {
     fn recommended(&self) -> bool {
         self.tags().iter().any(|x| x == &Tag::Recommended)
     }
+    /// Whether this rule can fix some of its diagnostics, this is a simple helper around [`Self::tags`].
+    fn fixable(&self) -> bool {
+        self.tags().contains(&Tag::Fixable)
+    }
+    /// Whether this rule should run on a file of this kind, this is a simple helper around [`Self::tags`].
+    fn runs_on(&self, kind: FileKind) -> bool {
+        match kind {
+            FileKind::TypeScript => !self.tags().contains(&Tag::OnlyJS),
+            FileKind::Script | FileKind::Module => !self.tags().contains(&Tag::OnlyTS),
+        }
+    }
 
     #[cfg(feature = "schema")]
     fn schema(&self) -> Option<schemars::schema::RootSchema> {
@@ -145,6 +182,8 @@ pub struct RuleCtx {
     pub diagnostics: Vec<Diagnostic>,
     pub fixer: Option<Fixer>,
     pub src: Arc<str>,
+    /// The `.editorconfig` properties of the file, which rules can fall back to if their options are not set.
+    pub editorconfig: EditorConfig,
 }
 
 impl RuleCtx {
@@ -173,6 +212,7 @@ impl RuleCtx {
             diagnostics: vec![],
             fixer: None,
             src: Arc::from(String::new()),
+            editorconfig: EditorConfig::default(),
         }
     }
 }
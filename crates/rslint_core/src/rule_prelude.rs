@@ -2,7 +2,7 @@
 
 #[doc(no_inline)]
 pub use crate::{
-    autofix::{Fixer, Unwrappable, Wrapping},
+    autofix::{FixType, Fixer, Unwrappable, Wrapping},
     declare_lint, rule_tests, ts_rule_tests, util, CstRule, Diagnostic, Outcome, RuleCtx,
     RuleResult, Span,
 };
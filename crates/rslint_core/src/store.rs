@@ -53,3 +53,46 @@ impl CstRuleStore {
             .cloned()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::rule_prelude::*;
+    use crate::{lint_file, CstRuleStore, File};
+    use rslint_parser::FileKind;
+
+    declare_lint! {
+        /// Disallow debugger statements in JavaScript files.
+        #[derive(Default)]
+        JsOnlyDebugger,
+        errors,
+        tags(OnlyJS),
+        "js-only-debugger"
+    }
+
+    #[typetag::serde]
+    impl CstRule for JsOnlyDebugger {
+        fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+            if node.kind() == SyntaxKind::DEBUGGER_STMT {
+                let err = ctx.err(self.name(), "debugger statements are not allowed");
+                ctx.add_err(err.primary(node, ""));
+            }
+            None
+        }
+    }
+
+    #[test]
+    fn skip_rules_on_other_file_kinds() {
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::new(JsOnlyDebugger::new()) as Box<dyn CstRule>]);
+
+        let js = File::from_string("debugger;", FileKind::Script, "a.js");
+        let result = lint_file(&js, &store, false);
+        assert_eq!(result.rule_results["js-only-debugger"].diagnostics.len(), 1);
+        assert!(result.skipped.is_empty());
+
+        let ts = File::from_string("debugger;", FileKind::TypeScript, "a.ts");
+        let result = lint_file(&ts, &store, false);
+        assert!(result.rule_results.is_empty());
+        assert_eq!(result.skipped, ["js-only-debugger"]);
+    }
+}
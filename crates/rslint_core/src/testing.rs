@@ -52,10 +52,14 @@ macro_rules! rule_tests {
         fn $err_name() {
             $(
                 let res = rslint_parser::parse_module($code, 0);
-                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[], std::sync::Arc::from($code.to_string()));
+                let errs = $crate::run_rule_untagged(&$rule, 0, res.syntax(), true, std::sync::Arc::from($code.to_string()), Default::default());
                 if errs.diagnostics.is_empty() {
                     panic!("\nExpected:\n```\n{}\n```\nto fail linting, but instead it passed (with {} parsing errors)", $code, res.errors().len());
                 }
+                // every diagnostic must be tagged with its rule so it can be filtered and grouped
+                for diagnostic in &errs.diagnostics {
+                    assert_eq!(diagnostic.code.as_deref(), Some(<_ as $crate::Rule>::name(&$rule)), "the rule of `{}`", diagnostic.title);
+                }
             )*
         }
 
@@ -63,7 +67,7 @@ macro_rules! rule_tests {
         fn $ok_name() {
             $(
                 let res = rslint_parser::parse_module($ok_code, 0);
-                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[], std::sync::Arc::from($ok_code.to_string()));
+                let errs = $crate::run_rule_untagged(&$rule, 0, res.syntax(), true, std::sync::Arc::from($ok_code.to_string()), Default::default());
 
                 if !errs.diagnostics.is_empty() {
                     panic!("\nExpected:\n```\n{}\n```\nto pass linting, but instead it threw errors (along with {} parsing errors):\n\n", $ok_code, res.errors().len());
@@ -119,10 +123,14 @@ macro_rules! ts_rule_tests {
         fn $err_name() {
             $(
                 let res = rslint_parser::parse_typescript($code, 0);
-                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[], std::sync::Arc::from($code.to_string()));
+                let errs = $crate::run_rule_untagged(&$rule, 0, res.syntax(), true, std::sync::Arc::from($code.to_string()), Default::default());
                 if errs.diagnostics.is_empty() {
                     panic!("\nExpected:\n```\n{}\n```\nto fail linting, but instead it passed (with {} parsing errors)", $code, res.errors().len());
                 }
+                // every diagnostic must be tagged with its rule so it can be filtered and grouped
+                for diagnostic in &errs.diagnostics {
+                    assert_eq!(diagnostic.code.as_deref(), Some(<_ as $crate::Rule>::name(&$rule)), "the rule of `{}`", diagnostic.title);
+                }
             )*
         }
 
@@ -130,7 +138,7 @@ macro_rules! ts_rule_tests {
         fn $ok_name() {
             $(
                 let res = rslint_parser::parse_typescript($ok_code, 0);
-                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[], std::sync::Arc::from($ok_code.to_string()));
+                let errs = $crate::run_rule_untagged(&$rule, 0, res.syntax(), true, std::sync::Arc::from($ok_code.to_string()), Default::default());
 
                 if !errs.diagnostics.is_empty() {
                     panic!("\nExpected:\n```\n{}\n```\nto pass linting, but instead it threw errors (along with {} parsing errors):\n\n", $ok_code, res.errors().len());
@@ -16,6 +16,17 @@ use std::collections::{BinaryHeap, HashMap};
 use std::hash::Hash;
 use SyntaxKind::*;
 
+/// Hash some source code using 64 bit FNV-1a, which is stable between runs and platforms
+/// unlike the hasher of the standard library.
+pub fn hash_source(source: impl AsRef<[u8]>) -> u64 {
+    source
+        .as_ref()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
+
 // rustfmt panics on this function for me
 #[rustfmt::skip]
 pub fn most_frequent<T>(items: Vec<T>) -> T
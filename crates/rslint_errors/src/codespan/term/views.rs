@@ -1,6 +1,6 @@
 use std::ops::Range;
 
-use super::super::diagnostic::{Diagnostic, LabelStyle};
+use super::super::diagnostic::{Diagnostic, LabelStyle, Severity};
 use super::super::files::{Error, Files, Location};
 use super::super::term::renderer::{Locus, MultiLabel, Renderer, SingleLabel};
 use super::super::term::Config;
@@ -15,6 +15,120 @@ fn count_digits(mut n: usize) -> usize {
     count
 }
 
+/// Lines longer than this many bytes (such as the lines of minified files) are only rendered in
+/// a window around their labels, see [`window_line`].
+const MAX_LINE_LEN: usize = 512;
+
+/// The number of bytes shown before and after the labels of a windowed line.
+const WINDOW_CONTEXT: usize = 120;
+
+/// The marker for the parts of a windowed line which are not rendered.
+const ELLIPSIS: &str = "...";
+
+/// A line of source code shortened to a window around its labels, with the labels moved to
+/// their position in the window.
+struct WindowedLine<'diagnostic> {
+    source: String,
+    single_labels: Vec<SingleLabel<'diagnostic>>,
+    multi_labels: Vec<(usize, LabelStyle, MultiLabel<'diagnostic>)>,
+}
+
+/// Shorten a line which is longer than [`MAX_LINE_LEN`] to [`WINDOW_CONTEXT`] bytes around
+/// its labels, replacing the rest with ellipses, or return `None` if the line is short enough.
+/// Only the rendered text is windowed, the labels of a diagnostic keep their byte offsets.
+fn window_line<'diagnostic>(
+    source: &str,
+    single_labels: &[SingleLabel<'diagnostic>],
+    multi_labels: &[(usize, LabelStyle, MultiLabel<'diagnostic>)],
+) -> Option<WindowedLine<'diagnostic>> {
+    if source.len() <= MAX_LINE_LEN {
+        return None;
+    }
+
+    let offsets = single_labels
+        .iter()
+        .flat_map(|(_, range, _)| [range.start, range.end])
+        .chain(multi_labels.iter().filter_map(|(_, _, label)| match label {
+            MultiLabel::Top(start) => Some(*start),
+            MultiLabel::Bottom(end, _) => Some(*end),
+            MultiLabel::Left => None,
+        }))
+        .collect::<Vec<_>>();
+    let min = offsets.iter().copied().min().unwrap_or(0);
+    let max = offsets.iter().copied().max().unwrap_or(0);
+
+    let mut start = min.saturating_sub(WINDOW_CONTEXT).min(source.len());
+    while !source.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = max.saturating_add(WINDOW_CONTEXT).min(source.len());
+    while !source.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let prefix = if start > 0 { ELLIPSIS } else { "" };
+    let suffix = if end < source.trim_end().len() {
+        ELLIPSIS
+    } else {
+        ""
+    };
+    // the position of a byte of the original line in the windowed line
+    let shift = |offset: usize| offset - start + prefix.len();
+
+    Some(WindowedLine {
+        source: format!("{}{}{}", prefix, &source[start..end], suffix),
+        single_labels: single_labels
+            .iter()
+            .map(|(style, range, message)| (*style, shift(range.start)..shift(range.end), *message))
+            .collect(),
+        multi_labels: multi_labels
+            .iter()
+            .map(|(idx, style, label)| {
+                let label = match label {
+                    MultiLabel::Top(start) => MultiLabel::Top(shift(*start)),
+                    MultiLabel::Bottom(end, message) => MultiLabel::Bottom(shift(*end), message),
+                    MultiLabel::Left => MultiLabel::Left,
+                };
+                (*idx, *style, label)
+            })
+            .collect(),
+    })
+}
+
+/// Render a line of source code, windowing it if it is too long, see [`window_line`].
+#[allow(clippy::too_many_arguments)]
+fn render_line(
+    renderer: &mut Renderer<'_, '_>,
+    outer_padding: usize,
+    line_number: usize,
+    source: &str,
+    severity: Severity,
+    single_labels: &[SingleLabel<'_>],
+    num_multi_labels: usize,
+    multi_labels: &[(usize, LabelStyle, MultiLabel<'_>)],
+) -> Result<(), Error> {
+    match window_line(source, single_labels, multi_labels) {
+        Some(line) => renderer.render_snippet_source(
+            outer_padding,
+            line_number,
+            &line.source,
+            severity,
+            &line.single_labels,
+            num_multi_labels,
+            &line.multi_labels,
+        ),
+        None => renderer.render_snippet_source(
+            outer_padding,
+            line_number,
+            source,
+            severity,
+            single_labels,
+            num_multi_labels,
+            multi_labels,
+        ),
+    }
+}
+
 /// Output a richly formatted diagnostic, with source code previews.
 pub struct RichDiagnostic<'diagnostic, 'config, FileId> {
     diagnostic: &'diagnostic Diagnostic<FileId>,
@@ -314,7 +428,8 @@ where
                 .peekable();
 
             while let Some((line_index, line)) = lines.next() {
-                renderer.render_snippet_source(
+                render_line(
+                    renderer,
                     outer_padding,
                     line.number,
                     &source[line.range.clone()],
@@ -342,7 +457,8 @@ where
                                 .get(&(line_index + 1))
                                 .map_or(&[][..], |line| &line.multi_labels[..]);
 
-                            renderer.render_snippet_source(
+                            render_line(
+                                renderer,
                                 outer_padding,
                                 files.line_number(file_id, line_index + 1)?,
                                 &source[files.line_range(file_id, line_index + 1)?],
@@ -489,3 +605,26 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_long_lines() {
+        let line = format!("{}é{}\n", "a".repeat(1000), "b".repeat(1000));
+        let labels = [(LabelStyle::Primary, 1000..1002, "")];
+        let windowed = window_line(&line, &labels, &[]).unwrap();
+
+        let shown = &windowed.source[ELLIPSIS.len()..windowed.source.len() - ELLIPSIS.len()];
+        assert!(windowed.source.starts_with(ELLIPSIS) && windowed.source.ends_with(ELLIPSIS));
+        assert_eq!(shown, &line[880..1122]);
+        let range = windowed.single_labels[0].1.clone();
+        assert_eq!(&windowed.source[range], "é");
+
+        let short_line = "let a = 5;";
+        assert!(window_line(short_line, &labels, &[]).is_none());
+        let start = window_line(&line, &[(LabelStyle::Primary, 0..1, "")], &[]).unwrap();
+        assert!(start.source.starts_with('a'));
+    }
+}
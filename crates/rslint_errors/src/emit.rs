@@ -14,7 +14,6 @@ use crate::{
     suggestion::*,
     Diagnostic,
 };
-use rslint_text_edit::*;
 use std::{collections::HashMap, ops::Range};
 
 #[derive(Copy, Clone, PartialEq)]
@@ -191,18 +190,9 @@ impl Emitter<'_> {
 
         for (idx, suggestion) in d.suggestions.iter().enumerate() {
             diagnostic.render_extra_empty = true;
-            let replacement = match &suggestion.substitution {
-                SuggestionChange::Indels(indels) => {
-                    let mut old = self
-                        .files
-                        .source(suggestion.span.file)
-                        .expect("Non existant file id")[suggestion.span.range.clone()]
-                    .to_owned();
-                    apply_indels(indels, &mut old);
-                    old
-                }
-                SuggestionChange::String(string) => string.clone(),
-            };
+            let replacement = suggestion
+                .replacement(self.files)
+                .expect("Non existant file id");
 
             match suggestion.style {
                 SuggestionStyle::Full => {
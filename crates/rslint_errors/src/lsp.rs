@@ -4,9 +4,10 @@ use crate::file::Files;
 use crate::*;
 
 use lsp_types::{
-    DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag as LspTag, Location,
-    NumberOrString, Url,
+    CodeAction, CodeActionKind, DiagnosticRelatedInformation, DiagnosticSeverity,
+    DiagnosticTag as LspTag, Location, NumberOrString, TextEdit, Url, WorkspaceEdit,
 };
+use std::collections::HashMap;
 
 pub fn convert_to_lsp_diagnostic(
     diagnostic: Diagnostic,
@@ -74,6 +75,36 @@ pub fn convert_to_lsp_diagnostic(
     })
 }
 
+/// Convert the suggestions of a diagnostic into quick fixes for the document, so the user can
+/// choose which one to apply. Suggestions which are in other files are skipped.
+pub fn convert_to_lsp_code_actions(
+    diagnostic: &Diagnostic,
+    files: &impl crate::file::Files,
+    document_id: usize,
+    uri: Url,
+) -> Vec<CodeAction> {
+    diagnostic
+        .suggestions
+        .iter()
+        .filter(|suggestion| suggestion.span.file == document_id)
+        .filter_map(|suggestion| {
+            let range =
+                byte_span_to_range(files, document_id, suggestion.span.range.clone()).ok()?;
+            let edit = TextEdit::new(range, suggestion.replacement(files)?);
+            let mut changes = HashMap::new();
+            changes.insert(uri.clone(), vec![edit]);
+            Some(CodeAction {
+                title: suggestion.msg.clone(),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: None,
+                edit: Some(WorkspaceEdit::new(changes)),
+                command: None,
+                is_preferred: Some(suggestion.applicability == Applicability::Always),
+            })
+        })
+        .collect()
+}
+
 pub fn severity_to_lsp_severity(severity: Severity) -> DiagnosticSeverity {
     match severity {
         Severity::Error | Severity::Bug => DiagnosticSeverity::Error,
@@ -1,5 +1,8 @@
-use crate::{file::FileSpan, *};
-use rslint_text_edit::Indel;
+use crate::{
+    file::{FileSpan, Files},
+    *,
+};
+use rslint_text_edit::{apply_indels, Indel};
 use std::ops::Range;
 
 /// A Suggestion that is provided by rslint, and
@@ -17,6 +20,23 @@ pub struct CodeSuggestion {
     pub labels: Vec<Range<usize>>,
 }
 
+impl CodeSuggestion {
+    /// The text which replaces the span of the suggestion, or `None` if the file of the span is unknown.
+    pub fn replacement(&self, files: &dyn Files) -> Option<String> {
+        match &self.substitution {
+            SuggestionChange::Indels(indels) => {
+                let mut old = files
+                    .source(self.span.file)?
+                    .get(self.span.range.clone())?
+                    .to_owned();
+                apply_indels(indels, &mut old);
+                Some(old)
+            }
+            SuggestionChange::String(string) => Some(string.clone()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SuggestionChange {
     Indels(Vec<Indel>),
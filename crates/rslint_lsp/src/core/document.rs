@@ -1,8 +1,10 @@
 //! Core definitions related to documents.
 
-use crate::core::language::{Language, LanguageId};
+use crate::core::{
+    files::LspFiles,
+    language::{Language, LanguageId},
+};
 use rslint_core::{autofix::Fixer, Directive, DirectiveError, DirectiveParser, File};
-use rslint_errors::file::SimpleFiles;
 use rslint_parser::{FileKind, SyntaxNode};
 use std::convert::TryFrom;
 use tower_lsp::lsp_types::*;
@@ -13,11 +15,11 @@ pub struct RuleResult {
 }
 
 /// The current state of a document.
+///
+/// The contents of the document are kept in the `LspFiles` of the session, under the id of the document.
 pub struct Document {
-    /// The file backing of this document
-    pub file: File,
-    /// The files database containing the document.
-    pub files: SimpleFiles,
+    /// The id of the document in the files of the session.
+    pub file_id: usize,
     /// The language type of the document (e.g., JavaScript (script) or JavaScript (module)).
     pub language: Language,
     /// The language id of the document (e.g., "javascript").
@@ -34,8 +36,14 @@ pub struct Document {
 }
 
 impl Document {
-    /// Create a new Document.
-    pub fn new(uri: Url, language_id: String, text: String) -> anyhow::Result<Self> {
+    /// Create a new Document, opening its contents in `files`.
+    pub fn new(
+        files: &mut LspFiles,
+        uri: Url,
+        version: Option<i64>,
+        language_id: String,
+        text: String,
+    ) -> anyhow::Result<Self> {
         let language = {
             if let Ok(path) = uri.to_file_path() {
                 Language::try_from(path.as_path())?
@@ -44,23 +52,19 @@ impl Document {
             }
         };
 
-        let mut files = SimpleFiles::new();
-        let file_id = files.add(uri.to_string(), text.clone());
         let kind = match language {
             Language::JavaScriptModule => FileKind::Module,
             Language::JavaScriptScript => FileKind::Script,
             Language::TypeScript => FileKind::TypeScript,
         };
-        let mut file = File::from_string(text, kind, uri.path());
-        file.id = file_id;
+        let file_id = files.open(uri.clone(), version, text, kind);
+        let file = &files.get(&uri).expect("the document was just opened").file;
 
         let (parsing_errors, root) = file.parse_with_errors();
-
-        let res = DirectiveParser::new(root.clone(), &file).get_file_directives();
+        let res = DirectiveParser::new(root.clone(), file).get_file_directives();
 
         let document = Document {
-            files,
-            file,
+            file_id,
             language,
             language_id: LanguageId(language_id),
             directives: res.directives,
@@ -72,4 +76,15 @@ impl Document {
 
         Ok(document)
     }
+
+    /// Parse the document and its directives again after its contents changed.
+    pub fn reparse(&mut self, file: &File) {
+        let (parsing_errors, root) = file.parse_with_errors();
+        let res = DirectiveParser::new(root.clone(), file).get_file_directives();
+
+        self.root = root;
+        self.directives = res.directives;
+        self.directive_errors = res.diagnostics;
+        self.parsing_errors = parsing_errors;
+    }
 }
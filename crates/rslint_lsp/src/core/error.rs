@@ -11,6 +11,8 @@ pub(crate) enum Error {
     ClientNotInitialized,
     #[error("document not found: {0}")]
     DocumentNotFound(Url),
+    #[error("invalid range for document {0}: {1:?}")]
+    InvalidRange(Url, Range),
     #[error("invalid language extension: {0}")]
     InvalidLanguageExtension(String),
     #[error("invalid language id: {0}")]
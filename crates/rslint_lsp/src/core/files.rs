@@ -0,0 +1,136 @@
+//! Core definitions related to the contents of open documents.
+
+use crate::core::error::Error;
+use rslint_core::File;
+use rslint_errors::file::{FileId, Files};
+use rslint_parser::FileKind;
+use std::collections::HashMap;
+use std::ops::Range;
+use tower_lsp::lsp_types::{self, TextDocumentContentChangeEvent, Url};
+
+/// A document which is open in the editor.
+#[derive(Debug, Clone)]
+pub struct OpenFile {
+    /// The contents of the document, along with its line starts.
+    pub file: File,
+    /// The version of the document given by the editor, if any.
+    pub version: Option<i64>,
+}
+
+/// A files database of the documents which are open in the editor, keyed by their URI.
+///
+/// Unlike the files of a `FileWalker` these are never read from disk, their contents are given
+/// by the editor when they are opened and are kept up to date with the changes of the editor.
+#[derive(Debug, Clone, Default)]
+pub struct LspFiles {
+    files: HashMap<Url, OpenFile>,
+    uris: HashMap<FileId, Url>,
+    next_id: FileId,
+}
+
+impl LspFiles {
+    /// Create a new database without any documents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a document and return its file id, a document which is already open is replaced
+    /// but keeps its id.
+    pub fn open(&mut self, uri: Url, version: Option<i64>, text: String, kind: FileKind) -> FileId {
+        let id = match self.files.get(&uri) {
+            Some(open) => open.file.id,
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.uris.insert(id, uri.clone());
+                id
+            }
+        };
+        let mut file = File::from_string(text, kind, uri.as_str());
+        file.id = id;
+        self.files.insert(uri, OpenFile { file, version });
+        id
+    }
+
+    /// Close a document, returning its last contents.
+    pub fn close(&mut self, uri: &Url) -> Option<OpenFile> {
+        let open = self.files.remove(uri)?;
+        self.uris.remove(&open.file.id);
+        Some(open)
+    }
+
+    /// Apply the changes of a `textDocument/didChange` notification in order and set the version
+    /// of the document, returning its file id.
+    ///
+    /// A change without a range replaces the whole document, otherwise only the line starts inside
+    /// of the changed range are computed again. Positions are counted in UTF-16 code units, and
+    /// positions past the end of a line or of the document are clamped to it. If any change is
+    /// invalid the document is left untouched.
+    pub fn change(
+        &mut self,
+        uri: &Url,
+        version: Option<i64>,
+        changes: &[TextDocumentContentChangeEvent],
+    ) -> anyhow::Result<FileId> {
+        let open = self
+            .files
+            .get_mut(uri)
+            .ok_or_else(|| Error::DocumentNotFound(uri.clone()))?;
+        let mut file = open.file.clone();
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let range = byte_range(&file, range)
+                        .ok_or_else(|| Error::InvalidRange(uri.clone(), range))?;
+                    file.apply_edit(range, &change.text);
+                }
+                None => file.update_src(change.text.clone()),
+            }
+        }
+        open.file = file;
+        open.version = version;
+        Ok(open.file.id)
+    }
+
+    /// Get an open document.
+    pub fn get(&self, uri: &Url) -> Option<&OpenFile> {
+        self.files.get(uri)
+    }
+
+    /// Get the URI of the document with a file id.
+    pub fn uri(&self, id: FileId) -> Option<&Url> {
+        self.uris.get(&id)
+    }
+
+    fn file(&self, id: FileId) -> Option<&File> {
+        Some(&self.files.get(self.uris.get(&id)?)?.file)
+    }
+}
+
+impl Files for LspFiles {
+    fn name(&self, id: FileId) -> Option<&str> {
+        self.file(id)?.name(id)
+    }
+
+    fn source(&self, id: FileId) -> Option<&str> {
+        Files::source(self.file(id)?, id)
+    }
+
+    fn line_index(&self, id: FileId, byte_index: usize) -> Option<usize> {
+        Files::line_index(self.file(id)?, id, byte_index)
+    }
+
+    fn line_range(&self, id: FileId, line_index: usize) -> Option<Range<usize>> {
+        Files::line_range(self.file(id)?, id, line_index)
+    }
+}
+
+/// Convert the range of a change to a byte range of a file, or `None` if it ends before it starts.
+fn byte_range(file: &File, range: lsp_types::Range) -> Option<Range<usize>> {
+    let index = |position: lsp_types::Position| {
+        file.line_col_to_index_utf16(position.line as usize, position.character as usize)
+            .unwrap_or_else(|| file.byte_len())
+    };
+    let (start, end) = (index(range.start), index(range.end));
+    Some(start..end).filter(|_| start <= end)
+}
@@ -3,6 +3,9 @@
 // Core functionality related to documents.
 pub mod document;
 
+// Core definitions related to the contents of open documents.
+pub mod files;
+
 // Core definitions related to runtime errors.
 pub(crate) mod error;
 
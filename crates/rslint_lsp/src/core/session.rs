@@ -1,6 +1,6 @@
 //! Core definitions related to the LSP server session.
 
-use crate::core::{document::Document, error::Error};
+use crate::core::{document::Document, error::Error, files::LspFiles};
 use dashmap::{
     mapref::one::{Ref, RefMut},
     DashMap,
@@ -50,6 +50,10 @@ impl TomlDocument {
 pub struct Session {
     client: Option<Client>,
     documents: DashMap<Url, Document>,
+    /// The contents of the documents which are open in the editor.
+    ///
+    /// When both are needed, a document must be taken before its files are locked.
+    pub(crate) files: RwLock<LspFiles>,
     pub(crate) store: CstRuleStore,
     pub(crate) config: RwLock<Config>,
     pub(crate) config_doc: RwLock<Option<TomlDocument>>,
@@ -78,6 +82,7 @@ impl Session {
         Ok(Session {
             client,
             documents,
+            files: RwLock::new(LspFiles::new()),
             store,
             config,
             config_doc: RwLock::new(None),
@@ -6,6 +6,7 @@ use crate::{
     provider,
     service::synchronizer,
 };
+use rslint_parser::FileKind;
 use tower_lsp::{
     jsonrpc::{self, Result},
     lsp_types::*,
@@ -75,6 +76,13 @@ impl LanguageServer for Server {
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         if is_config_doc(&params.text_document.uri) {
             let doc = params.text_document;
+            // the config is kept with the other documents so that incremental changes can be applied to it
+            self.session.files.write().unwrap().open(
+                doc.uri.clone(),
+                Some(doc.version),
+                doc.text.clone(),
+                FileKind::Script,
+            );
             let parse = taplo::parser::parse(&doc.text);
             let mapper = taplo::util::coords::Mapper::new_utf16(&doc.text, false);
 
@@ -92,10 +100,18 @@ impl LanguageServer for Server {
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         if is_config_doc(&params.text_document.uri) {
+            let mut files = self.session.files.write().unwrap();
+            let uri = &params.text_document.uri;
+            if files
+                .change(uri, params.text_document.version, &params.content_changes)
+                .is_err()
+            {
+                return;
+            }
             if let Some(ref mut doc) = *self.session.config_doc.write().unwrap() {
-                let TextDocumentContentChangeEvent { text, .. } = params.content_changes[0].clone();
-                doc.parse = taplo::parser::parse(&text);
-                doc.mapper = taplo::util::coords::Mapper::new_utf16(&text, false);
+                let text = &files.get(uri).unwrap().file.source;
+                doc.parse = taplo::parser::parse(text);
+                doc.mapper = taplo::util::coords::Mapper::new_utf16(text, false);
             }
         } else {
             if uri_is_toml(&params.text_document.uri) {
@@ -25,7 +25,7 @@ pub fn capabilities() -> ServerCapabilities {
     let text_document_sync = Some(TextDocumentSyncCapability::Options(
         TextDocumentSyncOptions {
             open_close: Some(true),
-            change: Some(TextDocumentSyncKind::Full),
+            change: Some(TextDocumentSyncKind::Incremental),
             ..Default::default()
         },
     ));
@@ -15,6 +15,8 @@ pub async fn actions(
     params: CodeActionParams,
 ) -> Result<Option<CodeActionResponse>> {
     let document = session.get_document(&params.text_document.uri).await?;
+    let files = session.files.read().unwrap();
+    let file_id = document.file_id;
     if document
         .parsing_errors
         .iter()
@@ -24,8 +26,7 @@ pub async fn actions(
         return Ok(None);
     }
 
-    let action_range =
-        rslint_errors::lsp::range_to_byte_span(&document.files, document.file.id, &params.range)?;
+    let action_range = rslint_errors::lsp::range_to_byte_span(&*files, file_id, &params.range)?;
 
     let mut actions = vec![];
     let diagnostics = document
@@ -42,7 +43,7 @@ pub async fn actions(
 
     for res in document.rule_results.iter() {
         let matched_diag = res.diagnostics.iter().find(|d| {
-            rslint_errors::lsp::range_to_byte_span(&document.files, document.file.id, &d.range).ok()
+            rslint_errors::lsp::range_to_byte_span(&*files, file_id, &d.range).ok()
                 == Some(action_range.to_owned())
         });
 
@@ -54,8 +55,8 @@ pub async fn actions(
                     .filter_map(|i| {
                         Some(TextEdit {
                             range: rslint_errors::lsp::byte_span_to_range(
-                                &document.files,
-                                document.file.id,
+                                &*files,
+                                file_id,
                                 Range::<usize>::from(i.delete),
                             )
                             .ok()?,
@@ -74,12 +75,7 @@ pub async fn actions(
                     res.diagnostics
                         .iter()
                         .filter(|d| {
-                            rslint_errors::lsp::range_to_byte_span(
-                                &document.files,
-                                document.file.id,
-                                &d.range,
-                            )
-                            .ok()
+                            rslint_errors::lsp::range_to_byte_span(&*files, file_id, &d.range).ok()
                                 == Some(action_range.to_owned())
                         })
                         .cloned()
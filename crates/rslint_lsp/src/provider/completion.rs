@@ -39,15 +39,20 @@ pub async fn complete(
     session: &Session,
     params: CompletionParams,
 ) -> Result<Option<CompletionResponse>> {
-    let document = session
-        .get_document(&params.text_document_position.text_document.uri)
-        .await?;
+    let uri = &params.text_document_position.text_document.uri;
+    let document = session.get_document(uri).await?;
+    let files = session.files.read().unwrap();
+    let file = match files.get(uri) {
+        Some(open) => &open.file,
+        None => return Ok(None),
+    };
 
-    let loc = rslint_errors::lsp::position_to_byte_index(
-        &document.files,
-        document.file.id,
-        &params.text_document_position.position,
-    )?;
+    let position = params.text_document_position.position;
+    let loc =
+        match file.line_col_to_index_utf16(position.line as usize, position.character as usize) {
+            Some(loc) => loc,
+            None => return Ok(None),
+        };
 
     if let Some(err) = document
         .directive_errors
@@ -59,7 +64,7 @@ pub async fn complete(
                 command_name_completions()
             }
             DirectiveErrorKind::InvalidRule => {
-                let wrong_text = &document.file.source[err.range()];
+                let wrong_text = file.text_at(err.range()).unwrap_or_default();
                 let available_rules = CstRuleStore::new().builtins().rules.into_iter();
 
                 let mut list = available_rules
@@ -91,9 +96,12 @@ pub async fn complete(
         .covering_element(TextRange::at(start, 0.into()))
         .into_token()
     {
-        if let Some(c) = comment.comment() {
-            let content = c.content.trim();
-            if content.len() <= 7 && "rslint-".find(content) == Some(0) {
+        if comment.comment().is_some() {
+            // only the text of the comment before the cursor is completed
+            let start = usize::from(comment.text_range().start());
+            let typed = file.text_at(start..loc).unwrap_or_default();
+            let typed = typed.trim_start_matches(['/', '*']).trim();
+            if "rslint-".starts_with(typed) {
                 return Ok(Some(command_name_completions()));
             }
         }
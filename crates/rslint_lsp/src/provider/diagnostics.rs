@@ -1,29 +1,25 @@
 //! Provider for LSP diagnostics.
 
-use crate::core::{
-    document::{Document, RuleResult},
-    session::Session,
-};
+use crate::core::{document::RuleResult, files::LspFiles, session::Session};
 use rslint_core::{
-    apply_top_level_directives, directives::DirectiveResult, run_rule, DirectiveParser,
+    apply_suppression_directives, apply_top_level_directives, directives::DirectiveResult,
+    run_rule_with_editorconfig, DirectiveParser, EditorConfig,
 };
 use rslint_errors::{lsp::convert_to_lsp_diagnostic, Diagnostic as RslintDiagnostic};
 use std::{collections::HashMap, sync::Arc};
 use tower_lsp::lsp_types::*;
 
 fn process_diagnostics(
-    document: &Document,
+    files: &LspFiles,
+    file_id: usize,
     uri: Url,
     diagnostics: Vec<RslintDiagnostic>,
     out: &mut Vec<Diagnostic>,
 ) {
-    let files = document.files.clone();
-    let file_id = document.file.id;
-
     for diagnostic in diagnostics {
         if let Some(lsp_diag) = convert_to_lsp_diagnostic(
             diagnostic,
-            &files,
+            files,
             file_id,
             uri.clone(),
             Some("rslint".to_string()),
@@ -36,45 +32,58 @@ fn process_diagnostics(
 pub async fn publish_diagnostics(session: &Session, uri: Url) -> anyhow::Result<()> {
     let diags = {
         let mut document = session.get_mut_document(&uri)?;
+        let files = session.files.read().unwrap();
+        let file = &files
+            .get(&uri)
+            .ok_or_else(|| crate::core::error::Error::DocumentNotFound(uri.clone()))?
+            .file;
 
         let mut new_store = session.store.clone();
         let DirectiveResult {
             directives,
             diagnostics: mut directive_diagnostics,
-        } = DirectiveParser::new_with_store(document.root.clone(), &document.file, &session.store)
+        } = DirectiveParser::new_with_store(document.root.clone(), file, &session.store)
             .get_file_directives();
 
         apply_top_level_directives(
             directives.as_slice(),
             &mut new_store,
             &mut directive_diagnostics,
-            document.file.id,
+            file.id,
         );
 
         let verbose = false;
-        let src = Arc::from(document.file.source.clone());
-        let rule_results: HashMap<&str, rslint_core::RuleResult> = new_store
+        let src = Arc::from(file.source.clone());
+        let mut rule_results: HashMap<&str, rslint_core::RuleResult> = new_store
             .rules
             .iter()
+            .filter(|rule| rule.runs_on(file.kind))
             .map(|rule| {
                 (
                     rule.name(),
-                    run_rule(
+                    run_rule_with_editorconfig(
                         &**rule,
-                        document.file.id,
+                        file.id,
                         document.root.clone(),
                         verbose,
-                        &directives,
                         Arc::clone(&src),
+                        EditorConfig::default(),
                     ),
                 )
             })
             .collect();
+        apply_suppression_directives(
+            directives.as_slice(),
+            &mut rule_results,
+            &mut directive_diagnostics,
+            file.id,
+        );
 
         let mut diags = vec![];
 
         process_diagnostics(
-            &document,
+            &files,
+            file.id,
             uri.clone(),
             directive_diagnostics
                 .into_iter()
@@ -84,14 +93,15 @@ pub async fn publish_diagnostics(session: &Session, uri: Url) -> anyhow::Result<
         );
 
         process_diagnostics(
-            &document,
+            &files,
+            file.id,
             uri.clone(),
             document.parsing_errors.to_owned(),
             &mut diags,
         );
 
         for diagnostics in rule_results.clone().into_iter().map(|(_, r)| r.diagnostics) {
-            process_diagnostics(&document, uri.clone(), diagnostics, &mut diags);
+            process_diagnostics(&files, file.id, uri.clone(), diagnostics, &mut diags);
         }
 
         document.rule_results = rule_results
@@ -2,7 +2,6 @@
 
 use crate::core::session::Session;
 use anyhow::Result;
-use rslint_errors::file::Files;
 use tower_lsp::lsp_types::{
     Hover, HoverContents, HoverParams, MarkedString, Position, TextDocumentIdentifier,
     TextDocumentPositionParams,
@@ -16,21 +15,22 @@ pub async fn on_hover(session: &Session, params: HoverParams) -> Result<Option<H
 
     let doc = session.get_document(&uri).await.unwrap();
     let directives = doc.directives.as_slice();
+    let files = session.files.read().unwrap();
+    let file = match files.get(&uri) {
+        Some(open) => &open.file,
+        None => return Ok(None),
+    };
 
-    if let Some(start) = doc
-        .files
-        .line_range(doc.file.id, line as usize)
-        .map(|r| r.start)
-    {
-        let idx = start + character as usize;
+    // the character of a position is counted in UTF-16 code units, not in bytes
+    if let Some(idx) = file.line_col_to_index_utf16(line as usize, character as usize) {
         let component = directives
             .iter()
             .flat_map(|d| d.component_at(From::from(idx as u32)))
             .next();
         if let Some(documentation) = component.and_then(|c| c.kind.documentation()) {
             let range = rslint_errors::lsp::byte_span_to_range(
-                &doc.files,
-                doc.file.id,
+                &*files,
+                file.id,
                 component.unwrap().range.into(),
             )?;
 
@@ -6,8 +6,6 @@ pub(crate) mod document {
         core::{document::Document, session::Session},
         provider,
     };
-    use rslint_core::DirectiveParser;
-    use rslint_errors::file::SimpleFiles;
     use tower_lsp::lsp_types::*;
 
     /// Handle a document "change" event.
@@ -16,31 +14,18 @@ pub(crate) mod document {
         params: DidChangeTextDocumentParams,
     ) -> anyhow::Result<()> {
         let DidChangeTextDocumentParams {
-            text_document: VersionedTextDocumentIdentifier { uri, .. },
+            text_document: VersionedTextDocumentIdentifier { uri, version },
             content_changes,
         } = params;
-        let TextDocumentContentChangeEvent { text, .. } = content_changes[0].clone();
 
         // NOTE: We use an explicit scope here because document (below) must be
         // dropped before calling publish_diagnostics (further below) otherwise
         // the server will block.
         {
-            let mut files = SimpleFiles::new();
-            let file_id = files.add(uri.to_string(), text.clone());
-
             let mut document = session.get_mut_document(&uri)?;
-            document.files = files;
-            document.file.id = file_id;
-            document.file.source = text.clone();
-
-            let (parsing_errors, root) = document.file.parse_with_errors();
-
-            let res = DirectiveParser::new(root.clone(), &document.file).get_file_directives();
-
-            document.root = root;
-            document.directives = res.directives;
-            document.directive_errors = res.diagnostics;
-            document.parsing_errors = parsing_errors;
+            let mut files = session.files.write().unwrap();
+            files.change(&uri, version, &content_changes)?;
+            document.reparse(&files.get(&uri).unwrap().file);
         }
 
         provider::diagnostics::publish_diagnostics(session, uri).await?;
@@ -58,6 +43,7 @@ pub(crate) mod document {
         } = params;
 
         session.remove_document(&uri)?;
+        session.files.write().unwrap().close(&uri);
 
         let diagnostics = Default::default();
         let version = Default::default();
@@ -79,12 +65,15 @@ pub(crate) mod document {
                 TextDocumentItem {
                     uri,
                     language_id,
+                    version,
                     text,
-                    ..
                 },
         } = params;
 
-        let document = Document::new(uri.clone(), language_id, text)?;
+        let document = {
+            let mut files = session.files.write().unwrap();
+            Document::new(&mut files, uri.clone(), Some(version), language_id, text)?
+        };
         session.insert_document(uri.clone(), document)?;
 
         provider::diagnostics::publish_diagnostics(session, uri).await?;
@@ -344,6 +344,28 @@ pub trait SyntaxTokenExt {
     fn in_ts(&self, set: TokenSet) -> bool {
         set.contains(self.to_token().kind())
     }
+
+    /// The whitespace and comment tokens directly before this token in the order of the source,
+    /// which may be part of other nodes.
+    ///
+    /// Trivia are not stored on the tokens, they are tokens of the tree like any other token,
+    /// so this collects the tokens back to the previous token which is not trivia.
+    fn leading_trivia(&self) -> Vec<SyntaxToken> {
+        let mut trivia =
+            std::iter::successors(self.to_token().prev_token(), |token| token.prev_token())
+                .take_while(|token| token.kind().is_trivia())
+                .collect::<Vec<_>>();
+        trivia.reverse();
+        trivia
+    }
+
+    /// The whitespace and comment tokens directly after this token up to the next token which is
+    /// not trivia, which may be part of other nodes.
+    fn trailing_trivia(&self) -> Vec<SyntaxToken> {
+        std::iter::successors(self.to_token().next_token(), |token| token.next_token())
+            .take_while(|token| token.kind().is_trivia())
+            .collect()
+    }
 }
 
 impl SyntaxTokenExt for SyntaxToken {
@@ -46,6 +46,8 @@ module.exports = {
             "/guide/directives",
             "/guide/formatters",
             "/guide/autofix",
+            "/guide/plugins",
+            "/guide/server",
           ]
         }
       ],